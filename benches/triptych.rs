@@ -15,8 +15,9 @@ use itertools::izip;
 use rand_chacha::ChaCha12Rng;
 use rand_core::{CryptoRngCore, SeedableRng};
 use triptych::{
+    context::VerificationContext,
     parameters::Parameters,
-    proof::Proof,
+    proof::{Proof, VerifyTuning},
     statement::{InputSet, Statement},
     witness::Witness,
     Transcript,
@@ -27,6 +28,11 @@ const N_VALUES: [u32; 1] = [2];
 const M_VALUES: [u32; 4] = [2, 4, 8, 10];
 const BATCH_SIZES: [usize; 1] = [2];
 
+// Parameters for window size tuning (N = 4096)
+const TUNING_N: u32 = 2;
+const TUNING_M: u32 = 12;
+const TUNING_WINDOWS: [Option<usize>; 8] = [None, Some(4), Some(5), Some(6), Some(7), Some(8), Some(9), Some(10)];
+
 // Generate a batch of witnesses, statements, and transcripts
 #[allow(non_snake_case)]
 #[allow(clippy::arithmetic_side_effects)]
@@ -37,7 +43,7 @@ fn generate_data<R: CryptoRngCore>(
 ) -> (Vec<Witness>, Vec<Statement>, Vec<Transcript>) {
     // Generate witnesses; for this test, we use adjacent indexes for simplicity
     // This means the batch size must not exceed the input set size!
-    assert!(b <= params.get_N() as usize);
+    assert!(b <= usize::try_from(params.get_N()).unwrap());
     let mut witnesses = Vec::with_capacity(b);
     witnesses.push(Witness::random(params, rng));
     for _ in 1..b {
@@ -51,7 +57,7 @@ fn generate_data<R: CryptoRngCore>(
         .map(|_| RistrettoPoint::random(rng))
         .collect::<Vec<RistrettoPoint>>();
     for witness in &witnesses {
-        M[witness.get_l() as usize] = witness.compute_verification_key();
+        M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
     }
     let input_set = Arc::new(InputSet::new(&M));
 
@@ -177,6 +183,47 @@ fn verify_proof(c: &mut Criterion) {
     group.finish();
 }
 
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+fn verify_proof_context(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_proof_context");
+    let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+    for n in N_VALUES {
+        for m in M_VALUES {
+            // Generate parameters
+            let params = Arc::new(Parameters::new(n, m).unwrap());
+
+            let label = format!(
+                "Verify proof with context: n = {}, m = {} (N = {})",
+                n,
+                m,
+                params.get_N()
+            );
+            group.bench_function(&label, |b| {
+                // Generate data
+                let (witnesses, statements, transcripts) = generate_data(&params, 1, &mut rng);
+
+                // Generate the proof and context
+                let proof = Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+                    .unwrap();
+                let context = VerificationContext::new(&statements[0]);
+
+                // Start the benchmark
+                b.iter_batched_ref(
+                    || transcripts[0].clone(),
+                    |t| {
+                        // Verify the proof
+                        assert!(context.verify(&proof, t).is_ok());
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
 #[allow(non_snake_case)]
 #[allow(non_upper_case_globals)]
 fn verify_batch_proof(c: &mut Criterion) {
@@ -221,6 +268,50 @@ fn verify_batch_proof(c: &mut Criterion) {
     group.finish();
 }
 
+#[allow(non_snake_case)]
+#[allow(non_upper_case_globals)]
+fn verify_proof_tuning(c: &mut Criterion) {
+    let mut group = c.benchmark_group("verify_proof_tuning");
+    let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+    // Generate parameters
+    let params = Arc::new(Parameters::new(TUNING_N, TUNING_M).unwrap());
+
+    // Generate data
+    let (witnesses, statements, transcripts) = generate_data(&params, 1, &mut rng);
+
+    // Generate the proof
+    let proof =
+        Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
+
+    for msm_window in TUNING_WINDOWS {
+        let tuning = VerifyTuning {
+            msm_window,
+            ..VerifyTuning::default()
+        };
+
+        let label = format!(
+            "Verify proof (window = {:?}): n = {}, m = {} (N = {})",
+            msm_window,
+            TUNING_N,
+            TUNING_M,
+            params.get_N()
+        );
+        group.bench_function(&label, |b| {
+            // Start the benchmark
+            b.iter_batched_ref(
+                || transcripts[0].clone(),
+                |t| {
+                    // Verify the proof
+                    assert!(proof.verify_with_tuning(&statements[0], t, tuning).is_ok());
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
 criterion_group! {
     name = generate;
     config = Criterion::default();
@@ -230,7 +321,7 @@ criterion_group! {
 criterion_group! {
     name = verify;
     config = Criterion::default();
-    targets = verify_proof, verify_batch_proof
+    targets = verify_proof, verify_proof_context, verify_batch_proof, verify_proof_tuning
 }
 
 criterion_main!(generate, verify);
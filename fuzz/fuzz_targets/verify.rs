@@ -0,0 +1,46 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+
+#![no_main]
+
+use std::sync::Arc;
+
+use curve25519_dalek::ristretto::CompressedRistretto;
+use libfuzzer_sys::fuzz_target;
+use triptych::{InputSet, Parameters, Proof, Statement, Transcript};
+
+// Test verification of arbitrary proofs against arbitrary statements
+fuzz_target!(|data: &[u8]| {
+	// Use small dimensions, which need not match those of the proof
+	let Some((&[n, m], data)) = data.split_first_chunk::<2>() else {
+		return;
+	};
+	let Ok(params) = Parameters::new(u32::from(n % 3) + 2, u32::from(m % 2) + 2) else {
+		return;
+	};
+
+	// Parse the verification keys and linking tag, followed by the proof
+	let Ok(size) = usize::try_from(params.get_N()) else {
+		return;
+	};
+	let Some((points, proof)) = size.checked_add(1).and_then(|count| count.checked_mul(32)).and_then(|len| data.split_at_checked(len)) else {
+		return;
+	};
+	let Some(mut points) = points
+		.chunks_exact(32)
+		.map(|bytes| CompressedRistretto::from_slice(bytes).ok()?.decompress())
+		.collect::<Option<Vec<_>>>()
+	else {
+		return;
+	};
+	let J = points.pop().unwrap();
+	let Ok(statement) = Statement::new(&Arc::new(params), &Arc::new(InputSet::new(&points)), &J) else {
+		return;
+	};
+
+	// Verification must fail cleanly, since a proof can't be found without a witness
+	if let Ok(proof) = Proof::from_bytes(proof) {
+		assert!(proof.verify(&statement, &mut Transcript::new(b"Fuzz transcript")).is_err());
+	}
+});
@@ -0,0 +1,663 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{sync::Arc, vec::Vec};
+use core::iter::once;
+
+#[cfg(feature = "prover")]
+use curve25519_dalek::traits::MultiscalarMul;
+use curve25519_dalek::{
+    traits::{Identity, VartimeMultiscalarMul},
+    RistrettoPoint,
+    Scalar,
+};
+#[cfg(feature = "prover")]
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "prover")]
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+#[cfg(feature = "prover")]
+use zeroize::Zeroizing;
+
+use crate::{
+    gray::GrayIterator,
+    proof::ProofError,
+    transcript::{append_message, append_u64, challenge_bytes},
+    util::NullRng,
+    FiatShamirTranscript,
+    InputSet,
+    Statement,
+};
+#[cfg(feature = "prover")]
+use crate::{
+    proof::{compute_p, DecompositionCommitments},
+    util::OperationTiming,
+    Witness,
+};
+
+// Version identifier
+const VERSION: u64 = 0;
+
+// Domain separator
+const DOMAIN: &str = "Triptych balance proof";
+
+/// A Triptych proof that also proves the signer's value commitment balances against an output commitment.
+///
+/// Each verification key `M[i]` in the statement's [`InputSet`] has an associated value commitment `C[i]`, supplied as
+/// a second [`InputSet`] of the same size.
+/// Value commitments are assumed to have the form `v*H + b*G`, where `G` is the statement's
+/// [`Parameters::base_generator`](`crate::Parameters::base_generator`) (which is also used for verification keys), `v`
+/// is a value, and `b` is a blinding factor.
+/// The value generator `H` is chosen by the caller, and its discrete logarithm with respect to `G` must be unknown;
+/// otherwise commitments do not bind their values.
+///
+/// This proves the relation
+///
+/// `{ M, C, J, C_out ; (l, r, delta) : M[l] = r*G, r*J = U, C[l] - C_out = delta*G }`
+///
+/// where `U` is the statement's linkability generator.
+/// If `C[l]` and `C_out` commit to the same value, their difference is a commitment to zero, and `delta` is the
+/// difference of their blinding factors.
+/// The index `l` is shared by all three equations, so the proof shows that the commitment associated to the signing key
+/// balances, without revealing which one it is.
+///
+/// The construction extends a Triptych proof with a parallel commitment relation, as described in the Triptych paper.
+/// The prover sends vectors `X` and `X1` that mask the `M` and `C` terms respectively, and responses `z` and `z1`, but
+/// only one set of index commitments `A`, `B`, `C`, `D` and responses `f`.
+/// Soundness follows as for Triptych: from `m + 1` accepting transcripts with the same commitments and distinct
+/// challenges, an extractor recovers the index `l` from the `f` responses, then recovers `r` from the `M` equation and
+/// `delta` from the `C` equation. Since both equations use the same `f`, both openings are at the same index `l`.
+///
+/// Balance proofs use a separate transcript domain and are not interoperable with [`Proofs`](`crate::Proof`).
+#[allow(non_snake_case)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BalanceProof {
+    A: RistrettoPoint,
+    B: RistrettoPoint,
+    C: RistrettoPoint,
+    D: RistrettoPoint,
+    X: Vec<RistrettoPoint>,
+    X1: Vec<RistrettoPoint>,
+    Y: Vec<RistrettoPoint>,
+    f: Vec<Vec<Scalar>>,
+    z_A: Scalar,
+    z_C: Scalar,
+    z: Scalar,
+    z1: Scalar,
+}
+
+impl BalanceProof {
+    /// Generate a [`BalanceProof`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`, as
+    /// well as value `commitments` for the statement's input set and an `output_commitment` such that the commitment
+    /// at the witness index minus `output_commitment` is `blinding_delta*G`.
+    /// If the witness and statement are invalid, or if the commitments do not balance, returns a [`ProofError`].
+    ///
+    /// This function provides a cryptographically-secure random number generator for you.
+    ///
+    /// You must also supply a [`Transcript`](`crate::Transcript`) `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "rand")]
+    pub fn prove<T: FiatShamirTranscript>(
+        witness: &Witness,
+        statement: &Statement,
+        commitments: &Arc<InputSet>,
+        output_commitment: &RistrettoPoint,
+        blinding_delta: &Scalar,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        use rand_core::OsRng;
+
+        Self::prove_with_rng(
+            witness,
+            statement,
+            commitments,
+            output_commitment,
+            blinding_delta,
+            &mut OsRng,
+            transcript,
+        )
+    }
+
+    /// Generate a [`BalanceProof`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`, as
+    /// well as value `commitments` for the statement's input set and an `output_commitment` such that the commitment
+    /// at the witness index minus `output_commitment` is `blinding_delta*G`.
+    /// If the witness and statement are invalid, or if the commitments do not balance, returns a [`ProofError`].
+    ///
+    /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`](`crate::Transcript`)
+    /// `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "prover")]
+    #[allow(clippy::too_many_lines, non_snake_case)]
+    pub fn prove_with_rng<T: FiatShamirTranscript, R: CryptoRngCore>(
+        witness: &Witness,
+        statement: &Statement,
+        commitments: &Arc<InputSet>,
+        output_commitment: &RistrettoPoint,
+        blinding_delta: &Scalar,
+        rng: &mut R,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        // Check that the witness, statement, and commitments are consistent
+        let params = statement.get_params();
+        if witness.get_params() != params {
+            return Err(ProofError::InvalidParameter);
+        }
+        let M = statement.get_input_set().keys();
+        let C_set = commitments.keys();
+        if usize::try_from(params.get_N()).ok() != Some(M.len()) || M.len() != C_set.len() {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Extract values for convenience
+        let r = witness.get_r();
+        let l = witness.get_l();
+        let J = statement.get_J();
+        let G = params.base_generator();
+
+        // Check that the witness is valid against the statement and commitments in constant time
+        let mut M_l = RistrettoPoint::identity();
+        let mut C_l = RistrettoPoint::identity();
+        for (index, (M_item, C_item)) in (0u64..).zip(M.clone().zip(C_set.clone())) {
+            M_l.conditional_assign(M_item, index.ct_eq(&l));
+            C_l.conditional_assign(C_item, index.ct_eq(&l));
+        }
+        if M_l != params.mul_base_generator(r) {
+            return Err(ProofError::WitnessKeyMismatch { index: l });
+        }
+        if &(r * J) != params.linkability_generator() {
+            return Err(ProofError::InvalidWitness);
+        }
+        if C_l - output_commitment != params.mul_base_generator(blinding_delta) {
+            return Err(ProofError::InvalidWitness);
+        }
+
+        // Set up the transcript and its generator, binding in the witness
+        Self::bind_statement(transcript, statement, commitments, output_commitment);
+        let mut transcript_rng = transcript.witness_rng(
+            &[
+                (b"l", &l.to_le_bytes()),
+                (b"r", r.as_bytes()),
+                (b"delta", blinding_delta.as_bytes()),
+            ],
+            rng,
+        );
+
+        // Commit to the index decomposition
+        let DecompositionCommitments {
+            A,
+            B,
+            C,
+            D,
+            a,
+            sigma,
+            r_A,
+            r_B,
+            r_C,
+            r_D,
+        } = DecompositionCommitments::new(params, l, &mut transcript_rng, OperationTiming::Constant)?;
+
+        // Random masks for the key and commitment relations
+        let rho = Zeroizing::new(
+            (0..params.get_m())
+                .map(|_| Scalar::random(&mut transcript_rng))
+                .collect::<Vec<Scalar>>(),
+        );
+        let rho1 = Zeroizing::new(
+            (0..params.get_m())
+                .map(|_| Scalar::random(&mut transcript_rng))
+                .collect::<Vec<Scalar>>(),
+        );
+
+        // Compute `p` polynomial vector coefficients and the `X`, `X1`, and `Y` vectors
+        let p = compute_p(params, &a, &sigma)?;
+        let X = rho
+            .iter()
+            .enumerate()
+            .map(|(j, rho)| {
+                RistrettoPoint::multiscalar_mul(p.iter().map(|p| &p[j]).chain(once(rho)), M.clone().chain(once(G)))
+            })
+            .collect::<Vec<RistrettoPoint>>();
+        let X1 = rho1
+            .iter()
+            .enumerate()
+            .map(|(j, rho1)| {
+                RistrettoPoint::multiscalar_mul(p.iter().map(|p| &p[j]).chain(once(rho1)), C_set.clone().chain(once(G)))
+            })
+            .collect::<Vec<RistrettoPoint>>();
+        let Y = rho.iter().map(|rho| rho * J).collect::<Vec<RistrettoPoint>>();
+
+        // Get the challenge powers
+        let xi_powers = Self::commit(transcript, params.get_m(), &A, &B, &C, &D, &X, &X1, &Y)?;
+
+        // Compute the `f` matrix
+        let f = (0..params.get_m() as usize)
+            .map(|j| {
+                (1..params.get_n() as usize)
+                    .map(|i| sigma[j][i] * xi_powers[1] + a[j][i])
+                    .collect::<Vec<Scalar>>()
+            })
+            .collect::<Vec<Vec<Scalar>>>();
+
+        // Compute the remaining response values
+        let xi_m = xi_powers[params.get_m() as usize];
+        let z_A = r_A + xi_powers[1] * r_B;
+        let z_C = xi_powers[1] * r_C + r_D;
+        let z = r * xi_m -
+            rho.iter()
+                .zip(xi_powers.iter())
+                .map(|(rho, xi_power)| rho * xi_power)
+                .sum::<Scalar>();
+        let z1 = blinding_delta * xi_m -
+            rho1.iter()
+                .zip(xi_powers.iter())
+                .map(|(rho1, xi_power)| rho1 * xi_power)
+                .sum::<Scalar>();
+
+        // Bind the responses so the transcript state matches that of the verifier
+        Self::response(transcript, &f, &z_A, &z_C, &z, &z1);
+
+        Ok(Self {
+            A,
+            B,
+            C,
+            D,
+            X,
+            X1,
+            Y,
+            f,
+            z_A,
+            z_C,
+            z,
+            z1,
+        })
+    }
+
+    /// Verify a [`BalanceProof`].
+    ///
+    /// Verification requires that the `statement`, `commitments`, `output_commitment`, and `transcript` match those
+    /// used when the proof was generated.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    #[allow(non_snake_case)]
+    pub fn verify<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        commitments: &Arc<InputSet>,
+        output_commitment: &RistrettoPoint,
+        transcript: &mut T,
+    ) -> Result<(), ProofError> {
+        // Check that the input sets and proof are valid for the parameters; this must happen before any indexing
+        let params = statement.get_params();
+        let M = statement.get_input_set().keys();
+        let C_set = commitments.keys();
+        if usize::try_from(params.get_N()).ok() != Some(M.len()) || M.len() != C_set.len() {
+            return Err(ProofError::InvalidParameter);
+        }
+        let m = params.get_m() as usize;
+        let n_minus_1 = params.get_n().checked_sub(1).ok_or(ProofError::InvalidParameter)? as usize;
+        if self.X.len() != m ||
+            self.X1.len() != m ||
+            self.Y.len() != m ||
+            self.f.len() != m ||
+            self.f.iter().any(|f_row| f_row.len() != n_minus_1)
+        {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Replay the transcript to get the challenge powers and weights
+        Self::bind_statement(transcript, statement, commitments, output_commitment);
+        let xi_powers = Self::commit(
+            transcript,
+            params.get_m(),
+            &self.A,
+            &self.B,
+            &self.C,
+            &self.D,
+            &self.X,
+            &self.X1,
+            &self.Y,
+        )?;
+        let xi = xi_powers[1];
+        Self::response(transcript, &self.f, &self.z_A, &self.z_C, &self.z, &self.z1);
+
+        // Generate nonzero weights for the verification equations
+        let mut transcript_weights_rng = transcript.witness_rng(&[], &mut NullRng);
+        let mut w = [Scalar::ZERO; 5];
+        while w.contains(&Scalar::ZERO) {
+            for w_item in &mut w {
+                *w_item = Scalar::random(&mut transcript_weights_rng);
+            }
+        }
+        let [w1, w2, w3, w4, w5] = w;
+
+        // Reconstruct the remaining `f` terms, which must be nonzero for batch inversion
+        let f = self
+            .f
+            .iter()
+            .map(|f_row| {
+                once(xi - f_row.iter().sum::<Scalar>())
+                    .chain(f_row.iter().copied())
+                    .collect()
+            })
+            .collect::<Vec<Vec<Scalar>>>();
+        if f.iter().flatten().any(|f| f == &Scalar::ZERO) {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Compute the `f` products for each index using the Gray iterator
+        let mut f_inverse_flat = f.iter().flatten().copied().collect::<Vec<Scalar>>();
+        Scalar::batch_invert(&mut f_inverse_flat);
+        let f_inverse = f_inverse_flat
+            .chunks_exact(params.get_n() as usize)
+            .collect::<Vec<&[Scalar]>>();
+        let mut f_product = f.iter().map(|f_row| f_row[0]).product::<Scalar>();
+        let f_products = GrayIterator::new(params.get_n(), params.get_m())
+            .ok_or(ProofError::InvalidParameter)?
+            .map(|(gray_index, gray_old, gray_new)| {
+                f_product *= f_inverse[gray_index][gray_old as usize] * f[gray_index][gray_new as usize];
+                f_product
+            })
+            .collect::<Vec<Scalar>>();
+
+        // Assemble the weighted verification equations
+        let scalars = [-w1, -w1 * xi, -w2 * xi, -w2]
+            .into_iter()
+            .chain(xi_powers[0..m].iter().map(|xi_power| -w3 * xi_power))
+            .chain(xi_powers[0..m].iter().map(|xi_power| -w5 * xi_power))
+            .chain(xi_powers[0..m].iter().map(|xi_power| -w4 * xi_power))
+            .chain(once(-w4 * self.z))
+            .chain(once(-w5 * xi_powers[m]))
+            .chain(once(-w3 * self.z - w5 * self.z1))
+            .chain(f.iter().flatten().map(|f| w1 * f + w2 * f * (xi - f)))
+            .chain(once(w1 * self.z_A + w2 * self.z_C))
+            .chain(once(w4 * f_products.iter().sum::<Scalar>()))
+            .chain(f_products.iter().map(|f_product| w3 * f_product))
+            .chain(f_products.iter().map(|f_product| w5 * f_product))
+            .collect::<Vec<Scalar>>();
+        let points = [&self.A, &self.B, &self.C, &self.D]
+            .into_iter()
+            .chain(self.X.iter())
+            .chain(self.X1.iter())
+            .chain(self.Y.iter())
+            .chain(once(statement.get_J()))
+            .chain(once(output_commitment))
+            .chain(once(params.base_generator()))
+            .chain(params.commitment_generators().iter())
+            .chain(once(params.commitment_blinding_generator()))
+            .chain(once(params.linkability_generator()))
+            .chain(M)
+            .chain(C_set)
+            .collect::<Vec<&RistrettoPoint>>();
+
+        // Perform the final check; this can be done in variable time since it holds no secrets
+        if RistrettoPoint::vartime_multiscalar_mul(scalars, points) == RistrettoPoint::identity() {
+            Ok(())
+        } else {
+            Err(ProofError::FailedVerification)
+        }
+    }
+
+    /// Bind the statement, commitments, and output commitment into the transcript.
+    fn bind_statement<T: FiatShamirTranscript>(
+        transcript: &mut T,
+        statement: &Statement,
+        commitments: &InputSet,
+        output_commitment: &RistrettoPoint,
+    ) {
+        append_message(transcript, b"dom-sep", DOMAIN.as_bytes());
+        append_u64(transcript, b"version", VERSION);
+        append_message(transcript, b"params", statement.get_params().get_hash());
+        append_message(transcript, b"M", statement.get_input_set().get_hash());
+        append_message(transcript, b"C", commitments.get_hash());
+        append_message(transcript, b"J", statement.get_J().compress().as_bytes());
+        append_message(transcript, b"C_out", output_commitment.compress().as_bytes());
+        if let Some(message) = statement.get_message() {
+            append_message(transcript, b"message", message);
+        }
+    }
+
+    /// Bind the commitments into the transcript and produce the challenge powers.
+    #[allow(non_snake_case, clippy::too_many_arguments)]
+    fn commit<T: FiatShamirTranscript>(
+        transcript: &mut T,
+        m: u32,
+        A: &RistrettoPoint,
+        B: &RistrettoPoint,
+        C: &RistrettoPoint,
+        D: &RistrettoPoint,
+        X: &[RistrettoPoint],
+        X1: &[RistrettoPoint],
+        Y: &[RistrettoPoint],
+    ) -> Result<Vec<Scalar>, ProofError> {
+        append_message(transcript, b"A", A.compress().as_bytes());
+        append_message(transcript, b"B", B.compress().as_bytes());
+        append_message(transcript, b"C", C.compress().as_bytes());
+        append_message(transcript, b"D", D.compress().as_bytes());
+        for X_item in X {
+            append_message(transcript, b"X", X_item.compress().as_bytes());
+        }
+        for X1_item in X1 {
+            append_message(transcript, b"X1", X1_item.compress().as_bytes());
+        }
+        for Y_item in Y {
+            append_message(transcript, b"Y", Y_item.compress().as_bytes());
+        }
+
+        // Get the challenge using wide reduction
+        let mut xi_bytes = [0u8; 64];
+        challenge_bytes(transcript, b"xi", &mut xi_bytes);
+        let xi = Scalar::from_bytes_mod_order_wide(&xi_bytes);
+
+        // Get powers of the challenge and confirm they are nonzero
+        let m = m as usize;
+        let mut xi_powers = Vec::with_capacity(m.checked_add(1).ok_or(ProofError::InvalidParameter)?);
+        let mut xi_power = Scalar::ONE;
+        for _ in 0..=m {
+            if xi_power == Scalar::ZERO {
+                return Err(ProofError::InvalidChallenge);
+            }
+
+            xi_powers.push(xi_power);
+            xi_power *= xi;
+        }
+
+        Ok(xi_powers)
+    }
+
+    /// Bind the responses into the transcript.
+    #[allow(non_snake_case)]
+    fn response<T: FiatShamirTranscript>(
+        transcript: &mut T,
+        f: &[Vec<Scalar>],
+        z_A: &Scalar,
+        z_C: &Scalar,
+        z: &Scalar,
+        z1: &Scalar,
+    ) {
+        for f in f.iter().flatten() {
+            append_message(transcript, b"f", f.as_bytes());
+        }
+        append_message(transcript, b"z_A", z_A.as_bytes());
+        append_message(transcript, b"z_C", z_C.as_bytes());
+        append_message(transcript, b"z", z.as_bytes());
+        append_message(transcript, b"z1", z1.as_bytes());
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::{RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::{CryptoRngCore, SeedableRng};
+
+    use crate::{proof::ProofError, testing::random_witness_statement, BalanceProof, InputSet, Transcript};
+
+    // Generate value commitments to random values for each index, returning them with the signer's value and blinding
+    #[allow(non_snake_case)]
+    fn generate_commitments<R: CryptoRngCore>(
+        G: &RistrettoPoint,
+        H: &RistrettoPoint,
+        N: u64,
+        l: u64,
+        rng: &mut R,
+    ) -> (Vec<RistrettoPoint>, Scalar, Scalar) {
+        let value = Scalar::from(12345u32);
+        let blinding = Scalar::random(rng);
+        let C = (0..N)
+            .map(|i| {
+                if i == l {
+                    value * H + blinding * G
+                } else {
+                    Scalar::random(rng) * H + Scalar::random(rng) * G
+                }
+            })
+            .collect::<Vec<RistrettoPoint>>();
+
+        (C, value, blinding)
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_balanced() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witness, statement) = random_witness_statement(2, 4, &mut rng).unwrap();
+        let params = statement.get_params();
+        let G = params.base_generator();
+        let H = RistrettoPoint::random(&mut rng);
+        let l = witness.get_l();
+
+        // Generate an output commitment to the signer's value
+        let (C, value, blinding_in) = generate_commitments(G, &H, params.get_N(), l, &mut rng);
+        let commitments = Arc::new(InputSet::new(&C));
+        let blinding_out = Scalar::random(&mut rng);
+        let C_out = value * H + blinding_out * G;
+        let delta = blinding_in - blinding_out;
+
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = BalanceProof::prove_with_rng(
+            &witness,
+            &statement,
+            &commitments,
+            &C_out,
+            &delta,
+            &mut rng,
+            &mut transcript.clone(),
+        )
+        .unwrap();
+        assert!(proof
+            .verify(&statement, &commitments, &C_out, &mut transcript.clone())
+            .is_ok());
+
+        // The proof must not verify against a different output commitment, commitment set, or transcript
+        let C_evil = (value + Scalar::ONE) * H + blinding_out * G;
+        assert!(proof
+            .verify(&statement, &commitments, &C_evil, &mut transcript.clone())
+            .is_err());
+        let mut evil_C = C.clone();
+        evil_C[usize::try_from(l).unwrap()] = C_evil;
+        assert!(proof
+            .verify(
+                &statement,
+                &Arc::new(InputSet::new(&evil_C)),
+                &C_out,
+                &mut transcript.clone()
+            )
+            .is_err());
+        assert!(proof
+            .verify(
+                &statement,
+                &commitments,
+                &C_out,
+                &mut Transcript::new(b"Evil transcript")
+            )
+            .is_err());
+
+        // The proof must not verify if tampered
+        let mut evil_proof = proof.clone();
+        evil_proof.z1 += Scalar::ONE;
+        assert!(evil_proof
+            .verify(&statement, &commitments, &C_out, &mut transcript.clone())
+            .is_err());
+        let mut evil_proof = proof.clone();
+        evil_proof.X1.pop();
+        assert!(matches!(
+            evil_proof.verify(&statement, &commitments, &C_out, &mut transcript.clone()),
+            Err(ProofError::InvalidParameter)
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_unbalanced() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witness, statement) = random_witness_statement(2, 4, &mut rng).unwrap();
+        let params = statement.get_params();
+        let G = params.base_generator();
+        let H = RistrettoPoint::random(&mut rng);
+        let l = witness.get_l();
+        let (C, value, blinding_in) = generate_commitments(G, &H, params.get_N(), l, &mut rng);
+        let commitments = Arc::new(InputSet::new(&C));
+        let transcript = Transcript::new(b"Test transcript");
+
+        // The prover cannot produce a proof for an output commitment to a different value
+        let blinding_out = Scalar::random(&mut rng);
+        let C_out = (value + Scalar::ONE) * H + blinding_out * G;
+        assert!(matches!(
+            BalanceProof::prove_with_rng(
+                &witness,
+                &statement,
+                &commitments,
+                &C_out,
+                &(blinding_in - blinding_out),
+                &mut rng,
+                &mut transcript.clone()
+            ),
+            Err(ProofError::InvalidWitness)
+        ));
+
+        // The prover cannot use a commitment that balances but belongs to a different index than the signing key
+        let other = usize::try_from((l + 1) % params.get_N()).unwrap();
+        let other_blinding = Scalar::random(&mut rng);
+        let mut other_C = C.clone();
+        other_C[other] = value * H + other_blinding * G;
+        let other_commitments = Arc::new(InputSet::new(&other_C));
+        let C_out = value * H + blinding_out * G;
+        assert!(matches!(
+            BalanceProof::prove_with_rng(
+                &witness,
+                &statement,
+                &other_commitments,
+                &C_out,
+                &(other_blinding - blinding_out),
+                &mut rng,
+                &mut transcript.clone()
+            ),
+            Err(ProofError::InvalidWitness)
+        ));
+
+        // A proof for the signer's commitment does not verify against another commitment set
+        let proof = BalanceProof::prove_with_rng(
+            &witness,
+            &statement,
+            &commitments,
+            &C_out,
+            &(blinding_in - blinding_out),
+            &mut rng,
+            &mut transcript.clone(),
+        )
+        .unwrap();
+        assert!(proof
+            .verify(&statement, &other_commitments, &C_out, &mut transcript.clone())
+            .is_err());
+    }
+}
@@ -0,0 +1,261 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{sync::Arc, vec::Vec};
+
+use snafu::prelude::*;
+
+use crate::{
+    parameters::ParameterError,
+    proof::{DeserializeLimits, ProofError},
+    statement::StatementError,
+    FiatShamirTranscript,
+    Parameters,
+    Proof,
+    Statement,
+};
+
+/// Errors that can arise when verifying a bundle.
+#[derive(Debug, Snafu)]
+pub enum BundleError {
+    /// The bundle could not be split into its parts.
+    #[snafu(display("The bundle could not be deserialized"))]
+    FailedDeserialization,
+    /// The bundle parameters could not be deserialized or exceed the limits.
+    #[snafu(display("The bundle parameters are invalid"))]
+    InvalidParameters {
+        /// The underlying error.
+        source: ParameterError,
+    },
+    /// The bundle statement could not be deserialized or does not match the parameters.
+    #[snafu(display("The bundle statement is invalid"))]
+    InvalidStatement {
+        /// The underlying error.
+        source: StatementError,
+    },
+    /// The bundle proof could not be deserialized or failed to verify.
+    #[snafu(display("The bundle proof is invalid"))]
+    InvalidProof {
+        /// The underlying error.
+        source: ProofError,
+    },
+}
+
+/// Serialize a `proof` and its `statement` into a self-contained bundle for [`verify_bundle`].
+///
+/// The encoding consists of the length of the parameter encoding as an 8-byte little-endian integer, followed by the
+/// encoding produced by [`Parameters::to_bytes`]; the length of the statement encoding in the same way, followed by the
+/// encoding produced by [`Statement::to_bytes`]; and the encoding produced by [`Proof::to_bytes`].
+pub fn encode_bundle(statement: &Statement, proof: &Proof) -> Vec<u8> {
+    let params = statement.get_params().to_bytes();
+    let statement = statement.to_bytes();
+    let proof = proof.to_bytes();
+
+    let mut result = Vec::with_capacity(
+        16usize
+            .saturating_add(params.len())
+            .saturating_add(statement.len())
+            .saturating_add(proof.len()),
+    );
+    result.extend((params.len() as u64).to_le_bytes());
+    result.extend(params);
+    result.extend((statement.len() as u64).to_le_bytes());
+    result.extend(statement);
+    result.extend(proof);
+
+    result
+}
+
+/// Verify a self-contained bundle produced by [`encode_bundle`], returning its verified [`Statement`] on success.
+///
+/// This deserializes the parameters, statement, and proof with full validation, and then verifies the proof using the
+/// caller's `transcript`. Dimensions that exceed `limits` are rejected before any generators are derived or any proof
+/// elements are parsed, and the input set must be fully present in `bytes` before it is allocated.
+///
+/// The bundle carries its own parameters and input set, which whoever produced it is free to choose, including the
+/// generators `G` and `U`. A successful result only means the proof is valid for the returned statement, so before
+/// relying on its linking tag, you must check that its parameters and input set are ones you accept; for example, by
+/// comparing [`Statement::get_params`] against your own [`Parameters`] and [`Statement::get_input_set`] against the
+/// input set you expect.
+///
+/// If any part of the bundle is invalid, or the proof does not verify, returns a [`BundleError`] identifying the part.
+pub fn verify_bundle<T: FiatShamirTranscript>(
+    bytes: &[u8],
+    limits: DeserializeLimits,
+    transcript: &mut T,
+) -> Result<Statement, BundleError> {
+    // Helper to parse a length-prefixed part from the front of a byte slice
+    fn parse_part<'a>(bytes: &mut &'a [u8]) -> Result<&'a [u8], BundleError> {
+        let (len, rest) = bytes
+            .split_first_chunk::<8>()
+            .ok_or(BundleError::FailedDeserialization)?;
+        let len = usize::try_from(u64::from_le_bytes(*len)).map_err(|_| BundleError::FailedDeserialization)?;
+        if len > rest.len() {
+            return Err(BundleError::FailedDeserialization);
+        }
+        let (part, rest) = rest.split_at(len);
+        *bytes = rest;
+
+        Ok(part)
+    }
+    let mut bytes = bytes;
+
+    // Check the proof dimensions before doing any work, since they must match the parameters
+    let params_bytes = parse_part(&mut bytes)?;
+    let statement_bytes = parse_part(&mut bytes)?;
+    let proof = Proof::from_bytes_with_limits(bytes, limits).map_err(|source| BundleError::InvalidProof { source })?;
+
+    let params = Arc::new(
+        Parameters::from_bytes(params_bytes, limits).map_err(|source| BundleError::InvalidParameters { source })?,
+    );
+    let statement =
+        Statement::from_bytes(statement_bytes, &params).map_err(|source| BundleError::InvalidStatement { source })?;
+    proof
+        .verify(&statement, transcript)
+        .map_err(|source| BundleError::InvalidProof { source })?;
+
+    Ok(statement)
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::RistrettoPoint;
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use crate::{
+        bundle::{encode_bundle, verify_bundle, BundleError},
+        parameters::ParameterError,
+        proof::DeserializeLimits,
+        statement::StatementError,
+        testing::random_witness_statement_with_params,
+        InputSet,
+        Parameters,
+        ParametersBuilder,
+        Proof,
+        Statement,
+        Transcript,
+    };
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_verify_bundle() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a proof using parameters with a context
+        let params = Arc::new(
+            ParametersBuilder::new()
+                .radix(2)
+                .digits(2)
+                .context(b"context")
+                .build()
+                .unwrap(),
+        );
+        let (witness, statement) = random_witness_statement_with_params(&params, &mut rng).unwrap();
+        let statement =
+            Statement::new_with_message(&params, statement.get_input_set(), statement.get_J(), b"message").unwrap();
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        let limits = DeserializeLimits { max_n: 2, max_m: 2 };
+
+        // The bundle verifies and yields the statement
+        let bundle = encode_bundle(&statement, &proof);
+        assert!(verify_bundle(&bundle, limits, &mut transcript.clone()).unwrap() == statement);
+
+        // A different transcript fails
+        assert!(matches!(
+            verify_bundle(&bundle, limits, &mut Transcript::new(b"Evil transcript")),
+            Err(BundleError::InvalidProof { .. })
+        ));
+
+        // Dimensions that exceed the limits fail
+        assert!(verify_bundle(
+            &bundle,
+            DeserializeLimits { max_n: 2, max_m: 1 },
+            &mut transcript.clone()
+        )
+        .is_err());
+
+        // A statement that does not match the parameters fails
+        let other_params = Arc::new(Parameters::new(2, 2).unwrap());
+        let mut evil = Vec::new();
+        let other_params_bytes = other_params.to_bytes();
+        evil.extend((other_params_bytes.len() as u64).to_le_bytes());
+        evil.extend(other_params_bytes);
+        evil.extend_from_slice(&bundle[8 + params.to_bytes().len()..]);
+        assert!(matches!(
+            verify_bundle(&evil, limits, &mut transcript.clone()),
+            Err(BundleError::InvalidStatement {
+                source: StatementError::HashMismatch
+            })
+        ));
+
+        // Invalid parameters fail
+        let mut evil = bundle.clone();
+        evil[8] = 1;
+        assert!(matches!(
+            verify_bundle(&evil, limits, &mut transcript.clone()),
+            Err(BundleError::InvalidParameters {
+                source: ParameterError::FailedDeserialization
+            })
+        ));
+
+        // Truncated or extended bundles fail
+        for len in 0..bundle.len() {
+            assert!(verify_bundle(&bundle[..len], limits, &mut transcript.clone()).is_err());
+        }
+        let mut extended = bundle.clone();
+        extended.push(0);
+        assert!(verify_bundle(&extended, limits, &mut transcript.clone()).is_err());
+
+        // A part length that exceeds the bundle fails
+        let mut evil = bundle;
+        evil[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            verify_bundle(&evil, limits, &mut transcript.clone()),
+            Err(BundleError::FailedDeserialization)
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_verify_bundle_untrusted() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // The parameters and input set the caller expects
+        let params = Arc::new(Parameters::new(2, 2).unwrap());
+        let input_set = Arc::new(InputSet::new(
+            &(0..params.get_N())
+                .map(|_| RistrettoPoint::random(&mut rng))
+                .collect::<Vec<RistrettoPoint>>(),
+        ));
+
+        // An attacker chooses its own generators and ring
+        let evil_params = Arc::new(
+            Parameters::new_with_generators(
+                2,
+                2,
+                &RistrettoPoint::random(&mut rng),
+                &RistrettoPoint::random(&mut rng),
+            )
+            .unwrap(),
+        );
+        let (witness, evil_statement) = random_witness_statement_with_params(&evil_params, &mut rng).unwrap();
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &evil_statement, &mut rng, &mut transcript.clone()).unwrap();
+
+        // The bundle verifies, but the caller can see that its parameters and input set are not the expected ones
+        let statement = verify_bundle(
+            &encode_bundle(&evil_statement, &proof),
+            DeserializeLimits::default(),
+            &mut transcript.clone(),
+        )
+        .unwrap();
+        assert!(statement == evil_statement);
+        assert!(**statement.get_params() != *params);
+        assert!(statement.get_params().base_generator() != params.base_generator());
+        assert!(**statement.get_input_set() != *input_set);
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use core::slice;
+
+use curve25519_dalek::{ristretto::VartimeRistrettoPrecomputation, traits::VartimePrecomputedMultiscalarMul};
+
+use crate::{
+    proof::{ProofError, VerifyTuning},
+    FiatShamirTranscript,
+    Proof,
+    Statement,
+};
+
+/// A reusable context for verifying many Triptych [`Proofs`](`Proof`) that share [`Parameters`](`crate::Parameters`)
+/// and an [`InputSet`](`crate::InputSet`).
+///
+/// Building a context precomputes tables for the generators used in every verification, which makes each subsequent
+/// verification cheaper. This is worthwhile when verifying many proofs against the same parameters and input set, but
+/// building the context is more expensive than a single verification.
+///
+/// Transcripts are supplied by the caller and may contain arbitrary data, so they are not cached.
+pub struct VerificationContext {
+    statement: Statement,
+    precomputation: VartimeRistrettoPrecomputation,
+}
+
+impl VerificationContext {
+    /// Build a new [`VerificationContext`] for a [`Statement`].
+    pub fn new(statement: &Statement) -> Self {
+        Self {
+            statement: statement.clone(),
+            precomputation: VartimeRistrettoPrecomputation::new(statement.get_params().fixed_generators()),
+        }
+    }
+
+    /// Get the [`Statement`] from this [`VerificationContext`].
+    pub fn get_statement(&self) -> &Statement {
+        &self.statement
+    }
+
+    /// Verify a Triptych [`Proof`] against the [`Statement`] used to build this [`VerificationContext`].
+    ///
+    /// This is equivalent to [`Proof::verify`], but faster.
+    ///
+    /// Verification requires that the `transcript` matches the one used when the proof was generated.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify<T: FiatShamirTranscript>(&self, proof: &Proof, transcript: &mut T) -> Result<(), ProofError> {
+        self.verify_with_statement(&self.statement, proof, transcript)
+    }
+
+    /// Verify a Triptych [`Proof`] against another [`Statement`].
+    ///
+    /// The `statement` may have a different linking tag or message than the one used to build this
+    /// [`VerificationContext`], but must share its [`Parameters`](`crate::Parameters`) and
+    /// [`InputSet`](`crate::InputSet`).
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    ///
+    /// If any of these requirements are not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_with_statement<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        proof: &Proof,
+        transcript: &mut T,
+    ) -> Result<(), ProofError> {
+        self.verify_batch(
+            slice::from_ref(statement),
+            slice::from_ref(proof),
+            slice::from_mut(transcript),
+        )
+    }
+
+    /// Verify a batch of Triptych [`Proofs`](`Proof`).
+    ///
+    /// This is equivalent to [`Proof::verify_batch`], but faster.
+    /// An empty batch is valid by definition.
+    ///
+    /// Verification requires that the `statements` and `transcripts` match those used when the `proofs` were generated,
+    /// and that the `statements` share the [`Parameters`](`crate::Parameters`) and [`InputSet`](`crate::InputSet`) used
+    /// to build this [`VerificationContext`].
+    ///
+    /// If any of the above requirements are not met, or if any proof is invalid, returns a [`ProofError`].
+    pub fn verify_batch<T: FiatShamirTranscript>(
+        &self,
+        statements: &[Statement],
+        proofs: &[Proof],
+        transcripts: &mut [T],
+    ) -> Result<(), ProofError> {
+        // Each statement must match the context (checked using the hashes for efficiency)
+        if !statements.iter().all(|s| {
+            s.get_params().get_hash() == self.statement.get_params().get_hash() &&
+                s.get_input_set().get_hash() == self.statement.get_input_set().get_hash()
+        }) {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        Proof::verify_batch_internal(
+            statements,
+            proofs,
+            transcripts,
+            VerifyTuning::default(),
+            None,
+            Some(&self.precomputation),
+            None,
+        )
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::{RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use crate::{context::VerificationContext, InputSet, Parameters, Proof, Statement, Transcript, Witness};
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verification_context() {
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate witnesses that share parameters and an input set
+        let params = Arc::new(Parameters::new(n, m).unwrap());
+        let witnesses = (0..3)
+            .map(|l| Witness::new(&params, l, &Scalar::random(&mut rng)).unwrap())
+            .collect::<Vec<Witness>>();
+        let mut M = (0..params.get_N())
+            .map(|_| RistrettoPoint::random(&mut rng))
+            .collect::<Vec<RistrettoPoint>>();
+        for witness in &witnesses {
+            M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        }
+        let input_set = Arc::new(InputSet::new(&M));
+        let statements = witnesses
+            .iter()
+            .map(|witness| Statement::new(&params, &input_set, &witness.compute_linking_tag()).unwrap())
+            .collect::<Vec<Statement>>();
+        let transcripts = (0..witnesses.len())
+            .map(|_| Transcript::new(b"Test transcript"))
+            .collect::<Vec<Transcript>>();
+        let proofs = witnesses
+            .iter()
+            .zip(statements.iter())
+            .zip(transcripts.iter())
+            .map(|((witness, statement), transcript)| {
+                Proof::prove_with_rng(witness, statement, &mut rng, &mut transcript.clone()).unwrap()
+            })
+            .collect::<Vec<Proof>>();
+
+        // The context agrees with regular verification for its own statement
+        let context = VerificationContext::new(&statements[0]);
+        assert!(context.verify(&proofs[0], &mut transcripts[0].clone()).is_ok());
+        assert!(context
+            .verify(&proofs[0], &mut Transcript::new(b"Evil transcript"))
+            .is_err());
+        assert!(context.verify(&proofs[1], &mut transcripts[1].clone()).is_err());
+
+        // It also verifies other statements that share parameters and an input set
+        assert!(context
+            .verify_with_statement(&statements[1], &proofs[1], &mut transcripts[1].clone())
+            .is_ok());
+        assert!(context
+            .verify_batch(&statements, &proofs, &mut transcripts.clone())
+            .is_ok());
+        assert!(context.verify_batch::<Transcript>(&[], &[], &mut []).is_ok());
+
+        // Statements with a different input set are rejected
+        let mut evil_M = M.clone();
+        evil_M[0] = RistrettoPoint::random(&mut rng);
+        let evil_statement = Statement::new(&params, &Arc::new(InputSet::new(&evil_M)), statements[0].get_J()).unwrap();
+        assert!(context
+            .verify_with_statement(&evil_statement, &proofs[0], &mut transcripts[0].clone())
+            .is_err());
+
+        // Statements with different parameters are rejected, even with a matching input set
+        let G = RistrettoPoint::random(&mut rng);
+        let U = RistrettoPoint::random(&mut rng);
+        let evil_params = Arc::new(Parameters::new_with_generators(n, m, &G, &U).unwrap());
+        let evil_statement = Statement::new(&evil_params, &input_set, statements[0].get_J()).unwrap();
+        assert!(context
+            .verify_with_statement(&evil_statement, &proofs[0], &mut transcripts[0].clone())
+            .is_err());
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{sync::Arc, vec::Vec};
+
+use curve25519_dalek::{ristretto::CompressedRistretto, RistrettoPoint, Scalar};
+
+use crate::{
+    proof::DeserializeLimits,
+    transcript::append_message,
+    Dimensions,
+    InputSet,
+    Parameters,
+    Statement,
+    Transcript,
+    Witness,
+};
+
+// Transcript label
+const TRANSCRIPT_LABEL: &[u8] = b"Triptych message";
+
+/// The largest dimensions accepted in a statement encoding.
+pub(crate) const STATEMENT_LIMITS: DeserializeLimits = DeserializeLimits { max_n: 256, max_m: 32 };
+
+/// Build a transcript with a bound message.
+pub(crate) fn message_transcript(message: &[u8]) -> Transcript {
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    append_message(&mut transcript, b"message", message);
+
+    transcript
+}
+
+// Parse a `u32` from the front of a byte slice, returning the rest
+fn parse_u32(bytes: &[u8]) -> Option<(u32, &[u8])> {
+    let (value, rest) = bytes.split_first_chunk::<4>()?;
+
+    Some((u32::from_le_bytes(*value), rest))
+}
+
+// Parse a `u64` from the front of a byte slice, returning the rest
+fn parse_u64(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let (value, rest) = bytes.split_first_chunk::<8>()?;
+
+    Some((u64::from_le_bytes(*value), rest))
+}
+
+// Parse a 32-byte array from the front of a byte slice, returning the rest
+fn parse_32(bytes: &[u8]) -> Option<(&[u8; 32], &[u8])> {
+    bytes.split_first_chunk::<32>()
+}
+
+/// Parse a witness encoding, which must match the parameters.
+pub(crate) fn parse_witness(bytes: &[u8], params: &Arc<Parameters>) -> Option<Witness> {
+    let (n, bytes) = parse_u32(bytes)?;
+    let (m, bytes) = parse_u32(bytes)?;
+    let (l, bytes) = parse_u64(bytes)?;
+    let (r, bytes) = parse_32(bytes)?;
+    if !bytes.is_empty() || n != params.get_n() || m != params.get_m() {
+        return None;
+    }
+
+    let r = Option::<Scalar>::from(Scalar::from_canonical_bytes(*r))?;
+
+    Witness::new(params, l, &r).ok()
+}
+
+/// Parse a statement encoding.
+///
+/// The dimensions are checked against [`STATEMENT_LIMITS`] and the encoding length before any [`Parameters`] are
+/// generated, so untrusted input cannot force expensive generator derivation.
+#[allow(non_snake_case)]
+pub(crate) fn parse_statement(bytes: &[u8]) -> Option<Statement> {
+    let (n, bytes) = parse_u32(bytes)?;
+    let (m, bytes) = parse_u32(bytes)?;
+    let (J, bytes) = parse_32(bytes)?;
+    let dimensions = Dimensions::new(n, m).ok()?;
+    if n > STATEMENT_LIMITS.max_n || m > STATEMENT_LIMITS.max_m {
+        return None;
+    }
+
+    // The verification keys must exactly fill the remaining bytes
+    if bytes.len() != usize::try_from(dimensions.get_N()).ok()?.checked_mul(32)? {
+        return None;
+    }
+    let params = Arc::new(Parameters::new(n, m).ok()?);
+    let M = bytes
+        .chunks_exact(32)
+        .map(|chunk| CompressedRistretto::from_slice(chunk).ok()?.decompress())
+        .collect::<Option<Vec<RistrettoPoint>>>()?;
+    let J = CompressedRistretto(*J).decompress()?;
+
+    Statement::new(&params, &Arc::new(InputSet::new(&M)), &J).ok()
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use alloc::{sync::Arc, vec, vec::Vec};
+
+    use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_COMPRESSED, RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::{CryptoRngCore, SeedableRng};
+
+    use super::{parse_statement, parse_witness, STATEMENT_LIMITS};
+    use crate::{Parameters, Witness};
+
+    /// Generate random witness and statement encodings.
+    #[allow(non_snake_case)]
+    pub(crate) fn generate_encodings<R: CryptoRngCore>(rng: &mut R) -> (Vec<u8>, Vec<u8>) {
+        let params = Arc::new(Parameters::new(2, 3).unwrap());
+        let witness = Witness::random(&params, rng);
+
+        // Encode the witness
+        let mut witness_bytes = Vec::new();
+        witness_bytes.extend(params.get_n().to_le_bytes());
+        witness_bytes.extend(params.get_m().to_le_bytes());
+        witness_bytes.extend(witness.get_l().to_le_bytes());
+        witness_bytes.extend(witness.get_r().as_bytes());
+
+        // Encode the statement
+        let mut statement_bytes = Vec::new();
+        statement_bytes.extend(params.get_n().to_le_bytes());
+        statement_bytes.extend(params.get_m().to_le_bytes());
+        statement_bytes.extend(witness.compute_linking_tag().compress().as_bytes());
+        for i in 0..params.get_N() {
+            let M_i = if i == witness.get_l() {
+                witness.compute_verification_key()
+            } else {
+                RistrettoPoint::random(rng)
+            };
+            statement_bytes.extend(M_i.compress().as_bytes());
+        }
+
+        (witness_bytes, statement_bytes)
+    }
+
+    // Encode a statement header with the given dimensions, followed by `body`
+    fn encode_statement(n: u32, m: u32, body: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(n.to_le_bytes());
+        bytes.extend(m.to_le_bytes());
+        bytes.extend(RISTRETTO_BASEPOINT_COMPRESSED.as_bytes());
+        bytes.extend(body);
+
+        bytes
+    }
+
+    #[test]
+    fn test_parse_witness() {
+        // Indexes beyond the `u32` range are encoded in full
+        let params = Arc::new(Parameters::new(2, 33).unwrap());
+        let l = 1u64 << 32;
+        let mut witness_bytes = Vec::new();
+        witness_bytes.extend(params.get_n().to_le_bytes());
+        witness_bytes.extend(params.get_m().to_le_bytes());
+        witness_bytes.extend(l.to_le_bytes());
+        witness_bytes.extend(Scalar::ONE.as_bytes());
+        assert_eq!(parse_witness(&witness_bytes, &params).unwrap().get_l(), l);
+
+        // Truncated encodings and indexes outside the input set are rejected
+        assert!(parse_witness(&witness_bytes[1..], &params).is_none());
+        witness_bytes[8..16].copy_from_slice(&params.get_N().to_le_bytes());
+        assert!(parse_witness(&witness_bytes, &params).is_none());
+    }
+
+    #[test]
+    fn test_parse_statement() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (_, statement_bytes) = generate_encodings(&mut rng);
+        assert!(parse_statement(&statement_bytes).is_some());
+
+        // Huge dimensions with a short body are rejected without generating parameters
+        assert!(parse_statement(&encode_statement((1 << 31) - 1, 2, &[0u8; 32])).is_none());
+        assert!(parse_statement(&encode_statement(2, 63, &[0u8; 32])).is_none());
+
+        // Dimensions beyond the limits are rejected even with a body of the right length
+        let n = STATEMENT_LIMITS.max_n + 1;
+        assert!(parse_statement(&encode_statement(n, 2, &vec![0u8; 32 * (n as usize).pow(2)])).is_none());
+
+        // Degenerate dimensions are rejected
+        assert!(parse_statement(&encode_statement(1, 2, &[0u8; 32])).is_none());
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::vec::Vec;
+
+use crate::{
+    proof::{DeserializeLimits, ProofError},
+    Proof,
+};
+
+/// A [`Proof`] carried together with opaque auxiliary data.
+///
+/// The auxiliary data `aux` can hold anything an application wants to transport alongside a proof, such as an output
+/// index. It is **not** bound into the proof transcript, so anyone can change it without invalidating the proof. If the
+/// data must be authenticated, bind it into the [`Transcript`](`crate::Transcript`) used for proving and verification
+/// instead.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofEnvelope {
+    /// The proof.
+    pub proof: Proof,
+    /// Auxiliary data that is not bound into the proof.
+    pub aux: Vec<u8>,
+}
+
+impl ProofEnvelope {
+    /// Generate a new [`ProofEnvelope`] from a `proof` and auxiliary data `aux`.
+    pub fn new(proof: Proof, aux: &[u8]) -> Self {
+        Self {
+            proof,
+            aux: aux.to_vec(),
+        }
+    }
+
+    /// Serialize a [`ProofEnvelope`] to a canonical byte vector.
+    ///
+    /// The encoding consists of the length of the auxiliary data as an 8-byte little-endian integer, the auxiliary
+    /// data, and the canonical encoding of the proof produced by [`Proof::to_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let proof = self.proof.to_bytes();
+
+        let mut result = Vec::with_capacity(8usize.saturating_add(self.aux.len()).saturating_add(proof.len()));
+        result.extend((self.aux.len() as u64).to_le_bytes());
+        result.extend_from_slice(&self.aux);
+        result.extend(proof);
+
+        result
+    }
+
+    /// Deserialize a [`ProofEnvelope`] from a canonical byte slice.
+    ///
+    /// If `bytes` does not represent a canonical encoding, returns a [`ProofError`].
+    ///
+    /// Memory use is bounded by the length of `bytes`. To further restrict the dimensions of proofs that can be
+    /// deserialized, use [`ProofEnvelope::from_bytes_with_limits`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        Self::from_bytes_with_limits(bytes, DeserializeLimits::default())
+    }
+
+    /// Deserialize a [`ProofEnvelope`] from a canonical byte slice, rejecting proofs whose dimensions exceed `limits`.
+    ///
+    /// If `bytes` does not represent a canonical encoding, or if the proof dimensions exceed `limits`, returns a
+    /// [`ProofError`].
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: DeserializeLimits) -> Result<Self, ProofError> {
+        // Parse the auxiliary data, which must fit in the remaining bytes
+        let (aux_len, rest) = bytes
+            .split_first_chunk::<8>()
+            .ok_or(ProofError::FailedDeserialization)?;
+        let aux_len = usize::try_from(u64::from_le_bytes(*aux_len)).map_err(|_| ProofError::FailedDeserialization)?;
+        if aux_len > rest.len() {
+            return Err(ProofError::FailedDeserialization);
+        }
+        let (aux, proof) = rest.split_at(aux_len);
+
+        // The proof must use the rest of the encoding
+        Ok(Self {
+            proof: Proof::from_bytes_with_limits(proof, limits)?,
+            aux: aux.to_vec(),
+        })
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use crate::{testing::random_witness_statement, Proof, ProofEnvelope, Transcript};
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_serialization() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a proof
+        let (witness, statement) = random_witness_statement(2, 2, &mut rng).unwrap();
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+
+        // Envelopes round trip, with or without auxiliary data
+        for aux in [&[][..], &[1, 2, 3]] {
+            let envelope = ProofEnvelope::new(proof.clone(), aux);
+            let serialized = envelope.to_bytes();
+            assert_eq!(serialized.len(), 8 + aux.len() + proof.to_bytes().len());
+            assert_eq!(ProofEnvelope::from_bytes(&serialized).unwrap(), envelope);
+
+            // Truncated or extended encodings fail
+            for len in 0..serialized.len() {
+                assert!(ProofEnvelope::from_bytes(&serialized[..len]).is_err());
+            }
+            let mut extended = serialized.clone();
+            extended.push(0);
+            assert!(ProofEnvelope::from_bytes(&extended).is_err());
+        }
+
+        // Auxiliary data is not bound into the proof
+        let envelope = ProofEnvelope::new(proof, b"other data");
+        assert!(envelope.proof.verify(&statement, &mut transcript.clone()).is_ok());
+
+        // An auxiliary data length that exceeds the encoding fails
+        let mut evil = envelope.to_bytes();
+        evil[..8].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(ProofEnvelope::from_bytes(&evil).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! These functions expose a stable C ABI for proving and verifying.
+//!
+//! Witnesses and statements use the same byte encodings as the `wasm` feature entry points:
+//! - A witness is `n` and `m` as 4-byte little-endian integers and `l` as an 8-byte little-endian integer, followed by
+//!   the canonical 32-byte signing key.
+//! - A statement is `n` and `m` as 4-byte little-endian integers, followed by the 32-byte compressed linking tag and
+//!   the 32-byte compressed encodings of all `n**m` verification keys, in order. Statements with `n > 256` or `m > 32`
+//!   are rejected, as are statements whose length does not match their dimensions, before any parameters are generated.
+//!
+//! Messages are bound into a Merlin transcript with label `Triptych message` using the label `message`.
+//!
+//! Every function returns one of the `TRIPTYCH_*` status codes; `TRIPTYCH_OK` indicates success.
+//! Panics are caught at the boundary and reported as `TRIPTYCH_ERROR_PANIC`, so they never unwind into C.
+//!
+//! # Memory ownership
+//!
+//! - Input buffers are borrowed for the duration of the call only, and are never retained or freed by this library. A
+//!   buffer with length zero may be null.
+//! - A proof handle written by [`triptych_prove`](`crate::ffi::triptych_prove`) or
+//!   [`triptych_proof_deserialize`](`crate::ffi::triptych_proof_deserialize`) is owned by the caller, and must be
+//!   released exactly once using [`triptych_free_proof`](`crate::ffi::triptych_free_proof`). It must not be used after
+//!   it is released.
+//! - Serialized proofs are written into caller-owned buffers by
+//!   [`triptych_proof_serialize`](`crate::ffi::triptych_proof_serialize`).
+
+use alloc::boxed::Box;
+use core::slice;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use rand_core::OsRng;
+
+use crate::{
+    encoding::{message_transcript, parse_statement, parse_witness},
+    proof::ProofError,
+    Proof,
+};
+
+/// The operation succeeded.
+pub const TRIPTYCH_OK: i32 = 0;
+/// A required pointer was null.
+pub const TRIPTYCH_ERROR_NULL_POINTER: i32 = 1;
+/// An invalid parameter was provided.
+pub const TRIPTYCH_ERROR_INVALID_PARAMETER: i32 = 2;
+/// A transcript challenge was invalid.
+pub const TRIPTYCH_ERROR_INVALID_CHALLENGE: i32 = 3;
+/// Deserialization failed.
+pub const TRIPTYCH_ERROR_FAILED_DESERIALIZATION: i32 = 4;
+/// Proof verification failed.
+pub const TRIPTYCH_ERROR_FAILED_VERIFICATION: i32 = 5;
+/// A caller-supplied output buffer was too small.
+pub const TRIPTYCH_ERROR_BUFFER_TOO_SMALL: i32 = 6;
+/// An internal panic was caught.
+pub const TRIPTYCH_ERROR_PANIC: i32 = 7;
+/// The witness was not valid for the statement, such as a signing key that does not match its verification key.
+pub const TRIPTYCH_ERROR_INVALID_WITNESS: i32 = 8;
+
+/// An opaque handle to a [`Proof`].
+pub struct TriptychProof(Proof);
+
+/// Generate a proof from an encoded witness, encoded statement, and message.
+///
+/// On success, writes a newly-allocated proof handle to `proof_out`, which the caller must release using
+/// [`triptych_free_proof`].
+///
+/// # Safety
+///
+/// Each input pointer must be valid for reads of its corresponding length, or may be null if that length is zero.
+/// The pointer `proof_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn triptych_prove(
+    witness: *const u8,
+    witness_len: usize,
+    statement: *const u8,
+    statement_len: usize,
+    message: *const u8,
+    message_len: usize,
+    proof_out: *mut *mut TriptychProof,
+) -> i32 {
+    guard(|| {
+        if proof_out.is_null() {
+            return TRIPTYCH_ERROR_NULL_POINTER;
+        }
+        let (witness, statement, message) = match (
+            as_slice(witness, witness_len),
+            as_slice(statement, statement_len),
+            as_slice(message, message_len),
+        ) {
+            (Some(witness), Some(statement), Some(message)) => (witness, statement, message),
+            _ => return TRIPTYCH_ERROR_NULL_POINTER,
+        };
+
+        // Parse the inputs
+        let statement = match parse_statement(statement) {
+            Some(statement) => statement,
+            None => return TRIPTYCH_ERROR_FAILED_DESERIALIZATION,
+        };
+        let witness = match parse_witness(witness, statement.get_params()) {
+            Some(witness) => witness,
+            None => return TRIPTYCH_ERROR_FAILED_DESERIALIZATION,
+        };
+
+        match Proof::prove_with_rng(&witness, &statement, &mut OsRng, &mut message_transcript(message)) {
+            Ok(proof) => {
+                *proof_out = Box::into_raw(Box::new(TriptychProof(proof)));
+                TRIPTYCH_OK
+            },
+            Err(error) => error_code(&error),
+        }
+    })
+}
+
+/// Verify a proof against an encoded statement and message.
+///
+/// Returns `TRIPTYCH_OK` if and only if the proof is valid.
+///
+/// # Safety
+///
+/// Each input pointer must be valid for reads of its corresponding length, or may be null if that length is zero.
+/// The pointer `proof` must be null or a live handle.
+#[no_mangle]
+#[must_use = "verification result must be checked"]
+pub unsafe extern "C" fn triptych_verify(
+    statement: *const u8,
+    statement_len: usize,
+    proof: *const TriptychProof,
+    message: *const u8,
+    message_len: usize,
+) -> i32 {
+    guard(|| {
+        let proof = match proof.as_ref() {
+            Some(proof) => &proof.0,
+            None => return TRIPTYCH_ERROR_NULL_POINTER,
+        };
+        let (statement, message) = match (as_slice(statement, statement_len), as_slice(message, message_len)) {
+            (Some(statement), Some(message)) => (statement, message),
+            _ => return TRIPTYCH_ERROR_NULL_POINTER,
+        };
+
+        let statement = match parse_statement(statement) {
+            Some(statement) => statement,
+            None => return TRIPTYCH_ERROR_FAILED_DESERIALIZATION,
+        };
+
+        match proof.verify(&statement, &mut message_transcript(message)) {
+            Ok(()) => TRIPTYCH_OK,
+            Err(error) => error_code(&error),
+        }
+    })
+}
+
+/// Serialize a proof into a caller-owned buffer.
+///
+/// The serialized length is always written to `written`. If the buffer `bytes` of length `bytes_len` is too small,
+/// nothing else is written and `TRIPTYCH_ERROR_BUFFER_TOO_SMALL` is returned; the caller can retry with a buffer of
+/// the reported length.
+///
+/// # Safety
+///
+/// The pointer `proof` must be a live handle, `bytes` must be valid for writes of `bytes_len` bytes (or may be null if
+/// `bytes_len` is zero), and `written` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn triptych_proof_serialize(
+    proof: *const TriptychProof,
+    bytes: *mut u8,
+    bytes_len: usize,
+    written: *mut usize,
+) -> i32 {
+    guard(|| {
+        let proof = match proof.as_ref() {
+            Some(proof) => &proof.0,
+            None => return TRIPTYCH_ERROR_NULL_POINTER,
+        };
+        if written.is_null() {
+            return TRIPTYCH_ERROR_NULL_POINTER;
+        }
+
+        let serialized = proof.to_bytes();
+        *written = serialized.len();
+        if serialized.len() > bytes_len {
+            return TRIPTYCH_ERROR_BUFFER_TOO_SMALL;
+        }
+        if bytes.is_null() {
+            return TRIPTYCH_ERROR_NULL_POINTER;
+        }
+        slice::from_raw_parts_mut(bytes, serialized.len()).copy_from_slice(&serialized);
+
+        TRIPTYCH_OK
+    })
+}
+
+/// Deserialize a proof from a buffer.
+///
+/// On success, writes a newly-allocated proof handle to `proof_out`, which the caller must release using
+/// [`triptych_free_proof`].
+///
+/// # Safety
+///
+/// The pointer `bytes` must be valid for reads of `bytes_len` bytes, or may be null if `bytes_len` is zero.
+/// The pointer `proof_out` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn triptych_proof_deserialize(
+    bytes: *const u8,
+    bytes_len: usize,
+    proof_out: *mut *mut TriptychProof,
+) -> i32 {
+    guard(|| {
+        if proof_out.is_null() {
+            return TRIPTYCH_ERROR_NULL_POINTER;
+        }
+        let bytes = match as_slice(bytes, bytes_len) {
+            Some(bytes) => bytes,
+            None => return TRIPTYCH_ERROR_NULL_POINTER,
+        };
+
+        match Proof::from_bytes(bytes) {
+            Ok(proof) => {
+                *proof_out = Box::into_raw(Box::new(TriptychProof(proof)));
+                TRIPTYCH_OK
+            },
+            Err(error) => error_code(&error),
+        }
+    })
+}
+
+/// Release a proof handle.
+///
+/// Passing null is a no-op.
+///
+/// # Safety
+///
+/// The pointer `proof` must be null or a live handle produced by this library, and must not be used afterward.
+#[no_mangle]
+pub unsafe extern "C" fn triptych_free_proof(proof: *mut TriptychProof) {
+    if !proof.is_null() {
+        drop(Box::from_raw(proof));
+    }
+}
+
+// Run a function, catching any panic so it does not unwind across the boundary
+fn guard<F: FnOnce() -> i32>(f: F) -> i32 {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(TRIPTYCH_ERROR_PANIC)
+}
+
+// Map a proof error to a status code
+fn error_code(error: &ProofError) -> i32 {
+    match error {
+        ProofError::InvalidParameter => TRIPTYCH_ERROR_INVALID_PARAMETER,
+        ProofError::WitnessKeyMismatch { .. } | ProofError::InvalidWitness => TRIPTYCH_ERROR_INVALID_WITNESS,
+        ProofError::InvalidChallenge => TRIPTYCH_ERROR_INVALID_CHALLENGE,
+        ProofError::FailedDeserialization |
+        ProofError::Truncated |
+        ProofError::FailedRead |
+        ProofError::UnsupportedVersion { .. } => TRIPTYCH_ERROR_FAILED_DESERIALIZATION,
+        ProofError::FailedVerification |
+        ProofError::MissingRequiredMember { .. } |
+        ProofError::FailedBatchVerification |
+        ProofError::FailedBatchVerificationWithSingleBlame { .. } |
+        ProofError::FailedBatchVerificationWithFullBlame { .. } => TRIPTYCH_ERROR_FAILED_VERIFICATION,
+    }
+}
+
+// Borrow a byte slice from a pointer and length, allowing a null pointer only for an empty slice
+unsafe fn as_slice<'a>(bytes: *const u8, len: usize) -> Option<&'a [u8]> {
+    if len == 0 {
+        return Some(&[]);
+    }
+    if bytes.is_null() {
+        return None;
+    }
+
+    Some(slice::from_raw_parts(bytes, len))
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{vec, vec::Vec};
+    use core::ptr;
+
+    use curve25519_dalek::{constants::RISTRETTO_BASEPOINT_COMPRESSED, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use super::*;
+    use crate::encoding::test::generate_encodings;
+
+    #[test]
+    fn test_prove_verify() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witness, statement) = generate_encodings(&mut rng);
+        let message = b"message";
+
+        unsafe {
+            // Generate a proof
+            let mut proof = ptr::null_mut();
+            assert_eq!(
+                triptych_prove(
+                    witness.as_ptr(),
+                    witness.len(),
+                    statement.as_ptr(),
+                    statement.len(),
+                    message.as_ptr(),
+                    message.len(),
+                    &mut proof
+                ),
+                TRIPTYCH_OK
+            );
+            assert!(!proof.is_null());
+
+            // Verify the proof
+            assert_eq!(
+                triptych_verify(
+                    statement.as_ptr(),
+                    statement.len(),
+                    proof,
+                    message.as_ptr(),
+                    message.len()
+                ),
+                TRIPTYCH_OK
+            );
+
+            // Verification fails against a different message
+            assert_eq!(
+                triptych_verify(statement.as_ptr(), statement.len(), proof, ptr::null(), 0),
+                TRIPTYCH_ERROR_FAILED_VERIFICATION
+            );
+
+            // Serialization reports the required length for a short buffer
+            let mut written = 0;
+            assert_eq!(
+                triptych_proof_serialize(proof, ptr::null_mut(), 0, &mut written),
+                TRIPTYCH_ERROR_BUFFER_TOO_SMALL
+            );
+            let mut bytes = vec![0u8; written];
+            assert_eq!(
+                triptych_proof_serialize(proof, bytes.as_mut_ptr(), bytes.len(), &mut written),
+                TRIPTYCH_OK
+            );
+            assert_eq!(bytes, (*proof).0.to_bytes());
+
+            // Deserialization round-trips
+            let mut deserialized = ptr::null_mut();
+            assert_eq!(
+                triptych_proof_deserialize(bytes.as_ptr(), bytes.len(), &mut deserialized),
+                TRIPTYCH_OK
+            );
+            assert_eq!((*deserialized).0, (*proof).0);
+
+            triptych_free_proof(deserialized);
+            triptych_free_proof(proof);
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witness, statement) = generate_encodings(&mut rng);
+
+        unsafe {
+            // Null output pointer
+            assert_eq!(
+                triptych_prove(
+                    witness.as_ptr(),
+                    witness.len(),
+                    statement.as_ptr(),
+                    statement.len(),
+                    ptr::null(),
+                    0,
+                    ptr::null_mut()
+                ),
+                TRIPTYCH_ERROR_NULL_POINTER
+            );
+
+            // Null input with nonzero length
+            let mut proof = ptr::null_mut();
+            assert_eq!(
+                triptych_prove(
+                    ptr::null(),
+                    witness.len(),
+                    statement.as_ptr(),
+                    statement.len(),
+                    ptr::null(),
+                    0,
+                    &mut proof
+                ),
+                TRIPTYCH_ERROR_NULL_POINTER
+            );
+
+            // Malformed statement
+            assert_eq!(
+                triptych_prove(
+                    witness.as_ptr(),
+                    witness.len(),
+                    statement.as_ptr(),
+                    statement.len() - 1,
+                    ptr::null(),
+                    0,
+                    &mut proof
+                ),
+                TRIPTYCH_ERROR_FAILED_DESERIALIZATION
+            );
+            assert!(proof.is_null());
+
+            // Witness whose signing key does not match the verification key at its index
+            let mut evil_witness = witness.clone();
+            let r_start = evil_witness.len() - 32;
+            evil_witness[r_start..].copy_from_slice(Scalar::ONE.as_bytes());
+            assert_eq!(
+                triptych_prove(
+                    evil_witness.as_ptr(),
+                    evil_witness.len(),
+                    statement.as_ptr(),
+                    statement.len(),
+                    ptr::null(),
+                    0,
+                    &mut proof
+                ),
+                TRIPTYCH_ERROR_INVALID_WITNESS
+            );
+            assert!(proof.is_null());
+
+            // Statement whose linking tag does not match the witness
+            let mut evil_statement = statement.clone();
+            evil_statement[8..40].copy_from_slice(RISTRETTO_BASEPOINT_COMPRESSED.as_bytes());
+            assert_eq!(
+                triptych_prove(
+                    witness.as_ptr(),
+                    witness.len(),
+                    evil_statement.as_ptr(),
+                    evil_statement.len(),
+                    ptr::null(),
+                    0,
+                    &mut proof
+                ),
+                TRIPTYCH_ERROR_INVALID_WITNESS
+            );
+            assert!(proof.is_null());
+
+            // Malformed proof
+            let bytes: Vec<u8> = vec![0u8; 16];
+            assert_eq!(
+                triptych_proof_deserialize(bytes.as_ptr(), bytes.len(), &mut proof),
+                TRIPTYCH_ERROR_FAILED_DESERIALIZATION
+            );
+
+            // Null proof handle
+            assert_eq!(
+                triptych_verify(statement.as_ptr(), statement.len(), ptr::null(), ptr::null(), 0),
+                TRIPTYCH_ERROR_NULL_POINTER
+            );
+            triptych_free_proof(ptr::null_mut());
+        }
+    }
+}
@@ -2,24 +2,76 @@
 // SPDX-License-Identifier: BSD-3-Clause
 
 use alloc::{vec, vec::Vec};
+#[cfg(feature = "prover")]
 use core::num::NonZeroU32;
 
+#[cfg(feature = "prover")]
 use crypto_bigint::{NonZero, U64};
 
+/// An odometer over the base-`N` decompositions of successive values, starting from zero.
+///
+/// Each step increments the least significant digit and carries on overflow, so it takes amortized constant time.
+#[allow(non_snake_case)]
+pub(crate) struct DecompositionIter {
+    N: u32, // base
+    // state information
+    digits: Vec<u32>,
+}
+
+impl DecompositionIter {
+    /// Generate a new decomposition iterator.
+    ///
+    /// You must provide a base `N > 1` and number of digits `M > 0`. If either condition is not met, returns `None`.
+    #[allow(non_snake_case)]
+    pub(crate) fn new(N: u32, M: u32) -> Option<Self> {
+        // Check inputs
+        if N <= 1 || M == 0 {
+            return None;
+        }
+
+        Some(Self {
+            N,
+            digits: vec![0; M as usize],
+        })
+    }
+}
+
+impl Iterator for DecompositionIter {
+    type Item = usize;
+
+    /// Advance to the decomposition of the next value.
+    ///
+    /// This returns the index of the most significant digit that changed; all less significant digits wrapped to zero.
+    /// Once the largest value has been reached, returns `None`.
+    fn next(&mut self) -> Option<Self::Item> {
+        // Find the first digit that won't overflow
+        let index = self
+            .digits
+            .iter()
+            .position(|digit| digit.checked_add(1) != Some(self.N))?;
+
+        // Carry into it
+        self.digits[..index].fill(0);
+        self.digits[index] = self.digits[index].checked_add(1)?;
+
+        Some(index)
+    }
+}
+
 /// An iterator for arbitrary-base Gray codes.
 #[allow(non_snake_case)]
 pub(crate) struct GrayIterator {
     N: u32, // base
-    M: u32, // number of digits
     // state information
-    i: u32,
+    started: bool,
+    decomposition: DecompositionIter,
     last: Vec<u32>,
 }
 
 impl GrayIterator {
     /// Generate a new Gray iterator.
     ///
-    /// You must provide a base `N > 1` and number of digits `M > 0` such that `N**M` does not overflow `u32`.
+    /// You must provide a base `N > 1` and number of digits `M > 0` such that `N**M` does not overflow `u64`.
     /// If any of these conditions is not met, returns `None`.
     ///
     /// Operations using this iterator run in variable time, so don't use this for secret data.
@@ -30,12 +82,12 @@ impl GrayIterator {
         if N <= 1 || M == 0 {
             return None;
         }
-        N.checked_pow(M)?;
+        u64::from(N).checked_pow(M)?;
 
         Some(Self {
             N,
-            M,
-            i: 0,
+            started: false,
+            decomposition: DecompositionIter::new(N, M)?,
             last: vec![0; M as usize],
         })
     }
@@ -45,8 +97,9 @@ impl GrayIterator {
     /// You must provide a valid value `v` based on the supplied parameters `N` and `M`.
     /// If anything goes wrong, returns `None`.
     /// Otherwise, returns the Gray code as a `u32` digit vector.
+    #[cfg_attr(not(feature = "prover"), allow(dead_code))]
     #[allow(non_snake_case)]
-    pub(crate) fn decompose_vartime(N: u32, M: u32, mut v: u32) -> Option<Vec<u32>> {
+    pub(crate) fn decompose_vartime(N: u32, M: u32, mut v: u64) -> Option<Vec<u32>> {
         if N <= 1 || M == 0 {
             return None;
         }
@@ -54,9 +107,9 @@ impl GrayIterator {
         // Get a base-`N` decomposition
         let mut base_N = Vec::with_capacity(M as usize);
         for _ in 0..M {
-            // These are always defined since `N > 0`
-            base_N.push(v.checked_rem(N)?);
-            v = v.checked_div(N)?;
+            // These are always defined since `N > 0`, and each remainder fits into `u32`
+            base_N.push(u32::try_from(v.checked_rem(u64::from(N))?).ok()?);
+            v = v.checked_div(u64::from(N))?;
         }
 
         // Now get the Gray decomposition from the base-`N` decomposition
@@ -76,14 +129,15 @@ impl GrayIterator {
     /// You must provide a valid value `v` based on the supplied parameters `N` and `M`.
     /// If anything goes wrong, returns `None`.
     /// Otherwise, returns the Gray code as a `u32` digit vector.
+    #[cfg(feature = "prover")]
     #[allow(non_snake_case)]
-    pub(crate) fn decompose(N: u32, M: u32, v: u32) -> Option<Vec<u32>> {
+    pub(crate) fn decompose(N: u32, M: u32, v: u64) -> Option<Vec<u32>> {
         if N <= 1 || M == 0 {
             return None;
         }
 
         // Convert to constant-time-friendly `U64`
-        let mut v_U64 = U64::from_u32(v);
+        let mut v_U64 = U64::from_u64(v);
         let N_nonzero = NonZero::<U64>::from_u32(NonZeroU32::new(N)?);
 
         // Get a base-`N` decomposition in constant time
@@ -126,33 +180,20 @@ impl Iterator for GrayIterator {
     ///
     /// Keep in mind that this does not return the actual Gray code!
     /// You must keep track of that yourself.
-    #[allow(non_snake_case)]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.i == 0 {
-            self.i = 1;
+        if !self.started {
+            self.started = true;
             return Some((0, 0, 0));
         }
 
-        // We have iterated over all codes
-        if self.i == self.N.checked_pow(self.M)? {
-            return None;
-        }
-
-        // Decompose the index
-        let next = Self::decompose_vartime(self.N, self.M, self.i)?;
-
-        // Locate the changed digit
-        let index = self
-            .last
-            .iter()
-            .zip(next.iter())
-            .position(|(last, next)| last != next)?;
+        // Each Gray digit is the difference of adjacent base-`N` digits, so incrementing the base-`N` decomposition
+        // only increments the Gray digit at the carry index; this returns `None` once we have iterated over all codes
+        let index = self.decomposition.next()?;
         let old = self.last[index];
-        let new = next[index];
+        let new = old.checked_add(1)?.checked_rem(self.N)?;
 
         // Update the state
-        self.i = self.i.checked_add(1)?;
-        self.last = next;
+        self.last[index] = new;
 
         Some((index, old, new))
     }
@@ -163,6 +204,7 @@ mod test {
     use super::*;
 
     #[test]
+    #[cfg(feature = "prover")]
     #[allow(non_snake_case)]
     fn test_gray_iterator() {
         // Set up parameters
@@ -185,7 +227,7 @@ mod test {
             // Check against the value getter
             assert_eq!(
                 digits,
-                GrayIterator::decompose(N, K, u32::try_from(i).unwrap()).unwrap()
+                GrayIterator::decompose(N, K, u64::try_from(i).unwrap()).unwrap()
             );
 
             // Make sure we haven't seen this decomposition before
@@ -217,7 +259,7 @@ mod test {
             // Check against the value getter
             assert_eq!(
                 digits,
-                GrayIterator::decompose_vartime(N, K, u32::try_from(i).unwrap()).unwrap()
+                GrayIterator::decompose_vartime(N, K, u64::try_from(i).unwrap()).unwrap()
             );
 
             // Make sure we haven't seen this decomposition before
@@ -225,4 +267,81 @@ mod test {
             digits_seen.push(digits.clone());
         }
     }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_decomposition_iter() {
+        for (N, K) in [(2u32, 1u32), (2, 4), (3, 3), (5, 2)] {
+            // The first decomposition is zero
+            let mut iter = DecompositionIter::new(N, K).unwrap();
+            assert!(iter.digits.iter().all(|digit| *digit == 0));
+
+            // Each successive decomposition is correct, and the changed digit is reported
+            for v in 1..u64::from(N).pow(K) {
+                let before = iter.digits.clone();
+                let index = iter.next().unwrap();
+
+                let mut expected = Vec::with_capacity(K as usize);
+                let mut remaining = v;
+                for _ in 0..K {
+                    expected.push(u32::try_from(remaining % u64::from(N)).unwrap());
+                    remaining /= u64::from(N);
+                }
+                assert_eq!(iter.digits, expected);
+                assert_ne!(before[index], iter.digits[index]);
+                assert!(iter.digits[..index].iter().all(|digit| *digit == 0));
+                assert_eq!(before[index + 1..], iter.digits[index + 1..]);
+            }
+
+            // The iterator ends after the largest value
+            assert!(iter.next().is_none());
+        }
+
+        // Invalid parameters are rejected
+        assert!(DecompositionIter::new(1, 2).is_none());
+        assert!(DecompositionIter::new(2, 0).is_none());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_gray_iterator_count() {
+        // Every code is produced exactly once, across a range of parameters
+        for (N, K) in [(2u32, 1u32), (2, 5), (3, 3), (4, 2), (7, 2)] {
+            let mut digits = vec![0; K as usize];
+            let mut count = 0u64;
+            for (i, (index, old, new)) in GrayIterator::new(N, K).unwrap().enumerate() {
+                assert_eq!(digits[index], old);
+                digits[index] = new;
+                assert_eq!(
+                    digits,
+                    GrayIterator::decompose_vartime(N, K, u64::try_from(i).unwrap()).unwrap()
+                );
+                count += 1;
+            }
+            assert_eq!(count, u64::from(N).pow(K));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_wide_index() {
+        // Set up parameters so that indexes exceed `u32`
+        let N = 2u32;
+        let K = 33u32;
+
+        // Both decompositions must agree across the `u32` boundary
+        for v in [(1u64 << 32) - 1, 1u64 << 32, (1u64 << 32) + 1, (1u64 << 33) - 1] {
+            let digits = GrayIterator::decompose(N, K, v).unwrap();
+            assert_eq!(digits, GrayIterator::decompose_vartime(N, K, v).unwrap());
+
+            // The most significant digit is only set when the index needs more than 32 bits
+            assert_eq!(digits[32], u32::from(v >= 1 << 32));
+        }
+
+        // Adjacent indexes differ in exactly one digit
+        let low = GrayIterator::decompose(N, K, (1u64 << 32) - 1).unwrap();
+        let high = GrayIterator::decompose(N, K, 1u64 << 32).unwrap();
+        assert_eq!(low.iter().zip(high.iter()).filter(|(a, b)| a != b).count(), 1);
+    }
 }
@@ -0,0 +1,95 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::collections::BTreeSet;
+
+/// Whether an input set has been observed before.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ReuseStatus {
+    /// The input set has not been observed before.
+    Fresh,
+    /// The input set has been observed before.
+    Reused,
+}
+
+/// A history of observed input sets, used to detect input set reuse.
+///
+/// Using the exact same input set repeatedly can degrade signer anonymity.
+/// This tracks input sets by their [`Statement::ring_fingerprint`](`crate::statement::Statement::ring_fingerprint`)
+/// values, and reports whether each newly-observed input set has been seen before.
+///
+/// The history grows with each distinct input set observed, so it is up to the caller to bound its lifetime.
+#[derive(Clone, Debug, Default)]
+pub struct RingHistory {
+    fingerprints: BTreeSet<[u8; 32]>,
+}
+
+impl RingHistory {
+    /// Generate a new empty [`RingHistory`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Observe an input set `fingerprint`, reporting whether it has been observed before.
+    pub fn observe(&mut self, fingerprint: [u8; 32]) -> ReuseStatus {
+        if self.fingerprints.insert(fingerprint) {
+            ReuseStatus::Fresh
+        } else {
+            ReuseStatus::Reused
+        }
+    }
+
+    /// Get the number of distinct input sets observed by this [`RingHistory`].
+    pub fn len(&self) -> usize {
+        self.fingerprints.len()
+    }
+
+    /// Check if this [`RingHistory`] has not observed any input sets.
+    pub fn is_empty(&self) -> bool {
+        self.fingerprints.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::RistrettoPoint;
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use crate::{history::ReuseStatus, InputSet, Parameters, RingHistory, Statement};
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_observe() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+
+        // Generate two input sets
+        let mut random_input_set = || {
+            Arc::new(InputSet::new(
+                &(0..params.get_N())
+                    .map(|_| RistrettoPoint::random(&mut rng))
+                    .collect::<Vec<RistrettoPoint>>(),
+            ))
+        };
+        let input_set = random_input_set();
+        let other_input_set = random_input_set();
+
+        // Statements with the same input set but different linking tags share a fingerprint
+        let statement = Statement::new(&params, &input_set, &RistrettoPoint::random(&mut rng)).unwrap();
+        let reused_statement = Statement::new(&params, &input_set, &RistrettoPoint::random(&mut rng)).unwrap();
+        let other_statement = Statement::new(&params, &other_input_set, &RistrettoPoint::random(&mut rng)).unwrap();
+
+        let mut history = RingHistory::new();
+        assert!(history.is_empty());
+        assert_eq!(history.observe(statement.ring_fingerprint()), ReuseStatus::Fresh);
+        assert_eq!(
+            history.observe(reused_statement.ring_fingerprint()),
+            ReuseStatus::Reused
+        );
+        assert_eq!(history.observe(other_statement.ring_fingerprint()), ReuseStatus::Fresh);
+        assert_eq!(history.len(), 2);
+    }
+}
@@ -0,0 +1,104 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{cell::Cell, thread_local};
+
+thread_local! {
+    static STATS: Cell<ProveStats> = const { Cell::new(ProveStats::ZERO) };
+}
+
+/// Counts of expensive operations performed while generating a [`Proof`](`crate::Proof`).
+///
+/// These are reported by [`Proof::prove_instrumented`](`crate::Proof::prove_instrumented`), and are useful for
+/// comparing the cost of different [`Parameters`](`crate::Parameters`) without an external profiler.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ProveStats {
+    /// The number of single scalar-point multiplications.
+    pub scalar_muls: u64,
+    /// The number of multiscalar multiplications.
+    pub multiscalar_muls: u64,
+    /// The total number of terms across all multiscalar multiplications.
+    pub multiscalar_mul_terms: u64,
+    /// The number of random scalars generated.
+    pub random_scalars: u64,
+}
+
+impl ProveStats {
+    const ZERO: Self = Self {
+        scalar_muls: 0,
+        multiscalar_muls: 0,
+        multiscalar_mul_terms: 0,
+        random_scalars: 0,
+    };
+}
+
+// Update the counts on the current thread
+fn update<F: FnOnce(&mut ProveStats)>(f: F) {
+    STATS.with(|stats| {
+        let mut value = stats.get();
+        f(&mut value);
+        stats.set(value);
+    });
+}
+
+/// Record single scalar-point multiplications.
+pub(crate) fn record_scalar_muls(count: usize) {
+    update(|stats| stats.scalar_muls = stats.scalar_muls.saturating_add(count as u64));
+}
+
+/// Record a multiscalar multiplication with a number of `terms`.
+pub(crate) fn record_multiscalar_mul(terms: usize) {
+    update(|stats| {
+        stats.multiscalar_muls = stats.multiscalar_muls.saturating_add(1);
+        stats.multiscalar_mul_terms = stats.multiscalar_mul_terms.saturating_add(terms as u64);
+    });
+}
+
+/// Record random scalar generation.
+pub(crate) fn record_random_scalars(count: usize) {
+    update(|stats| stats.random_scalars = stats.random_scalars.saturating_add(count as u64));
+}
+
+/// Remove and return the counts recorded on the current thread.
+pub(crate) fn take() -> ProveStats {
+    STATS.with(|stats| stats.replace(ProveStats::ZERO))
+}
+
+#[cfg(test)]
+mod test {
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use super::ProveStats;
+    use crate::{testing::random_witness_statement, Proof, Transcript};
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_instrumented() {
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a statement
+        let (witness, statement) = random_witness_statement(n, m, &mut rng).unwrap();
+        let transcript = Transcript::new(b"Test transcript");
+
+        // The proof is valid
+        let (proof, stats) =
+            Proof::prove_instrumented(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&statement, &mut transcript.clone()).is_ok());
+
+        // There are four matrix commitments and `m` input set commitments, two witness checks and `m` linking tag
+        // multiples, and a mask for each matrix entry, matrix commitment, and input set commitment
+        assert_eq!(stats, ProveStats {
+            scalar_muls: 6,
+            multiscalar_muls: 8,
+            multiscalar_mul_terms: 4 * 9 + 4 * 17,
+            random_scalars: 4 + 8 + 4,
+        });
+
+        // Counts are reset between runs
+        let (_, stats) = Proof::prove_instrumented(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert_eq!(stats.scalar_muls, 6);
+    }
+}
@@ -14,6 +14,8 @@
 //!
 //! Triptych proofs scale nicely, with their size increasingly only logarithmically with the size of the verification
 //! key set. Proofs sharing the same verification key set can also be verified efficiently in batches to save time.
+//! If you're not sure how large a verification key set to use, [`Parameters::recommended`] is a sensible default, and
+//! [`Parameters::for_ring_size`] chooses parameters for a given size.
 //!
 //! More formally, let `G` and `U` be fixed independent generators of the Ristretto group.
 //! Let `N = n**m`, where `n, m > 1` are fixed parameters.
@@ -29,23 +31,59 @@
 //!
 //! This implementation makes several opinionated choices:
 //! - It uses [Ristretto](https://ristretto.group/) for group operations.
-//! - It uses [Merlin](https://merlin.cool/) for Fiat-Shamir transcript operations.
+//! - It uses [Merlin](https://merlin.cool/) for Fiat-Shamir transcript operations by default; other transcripts can be
+//!   used by implementing [`FiatShamirTranscript`].
 //! - It uses [BLAKE3](https://github.com/BLAKE3-team/BLAKE3) for other cryptographic hashing operations.
 //!
 //! The implementation keeps dependencies to a minimum, and is `no_std` friendly.
 //!
 //! There are several features that are enabled by default:
+//! - `prover`: adds proof generation and witnesses; without it, the crate can only verify proofs
 //! - `rand`: adds additional prover functionality that supplies a cryptographically-secure random number generator
 //! - `serde`: adds proof serialization and deserialization via `serde`
-//! - `std`: adds corresponding dependency features
+//! - `std`: adds corresponding dependency features, and implements `std::error::Error` for all error types
+//!
+//! For a verifier that should not include any proving code, disable default features and enable only what you need,
+//! such as `default-features = false, features = ["std", "verifier"]`. Features that generate proofs, such as `rand`
+//! and `ffi`, enable `prover` themselves.
+//!
+//! There are also optional features that are not enabled by default:
+//! - `ffi`: adds a C-compatible interface for proving and verifying
+//! - `framed`: adds a framed proof encoding that carries the proof dimensions and a checksum, so truncation can be
+//!   detected
+//! - `instrument`: adds a prover that reports counts of expensive operations, for profiling parameter choices
+//! - `test-helpers`: adds helpers for generating valid random inputs in downstream tests
+//! - `test-vectors`: adds deterministic test vector generation and access to intermediate challenge values for
+//!   cross-implementation conformance testing
+//! - `trace`: records the operations that proving and verification apply to the caller's transcript, for auditing the
+//!   Fiat-Shamir transform
+//! - `verifier`: enables nothing, since verification is always available; it lets verifier-only builds name the feature
+//!   they rely on
+//! - `wasm`: adds byte-oriented proving and verification entry points exported to JavaScript using `wasm-bindgen`
 //!
 //! The underlying [curve library](https://crates.io/crates/curve25519-dalek) chooses an arithmetic backend based on CPU feature detection.
+//! On x86-64 targets, it uses an AVX2 backend when the CPU supports it, and a serial backend otherwise.
 //! Using a nightly compiler broadens the backend set, and may provide better performance.
+//! The curve library selects backends using configuration flags rather than crate features.
+//! Because of this, this crate can't forward backend selection as features.
+//! To force a particular backend, set `curve25519_dalek_backend` in `RUSTFLAGS` when building your application.
+//! The options are `serial`, `fiat`, and `simd`.
 //! You can examine performance using the benchmarks: either `cargo bench` or `cargo +nightly bench`.
+//! No benchmark numbers are provided, since the relative performance of backends depends on the CPU.
+//! To compare the serial and AVX2 backends on your machine, save a baseline using one and compare the other against it:
+//! - `RUSTFLAGS='--cfg curve25519_dalek_backend="serial"' cargo bench --bench triptych -- --save-baseline serial`
+//! - `RUSTFLAGS='--cfg curve25519_dalek_backend="simd"' cargo bench --bench triptych -- --baseline serial`
+//!
+//! The `fiat` backend can be compared in the same way.
 //!
 //! Proofs support a custom serialization format designed to be efficient and canonical.
 //! This functionality has an associated fuzzer that can be run using a nightly compiler: `cargo +nightly fuzz run
-//! proofs`.
+//! proofs`. Verification of arbitrary proofs against arbitrary statements has its own fuzzer: `cargo +nightly fuzz run
+//! verify`. With the `std` feature, proofs can be written to and read from streams in this format without buffering
+//! each one first, using [`Proof::write_to`] and [`Proof::read_from`]. Parameters can be serialized as a compact
+//! description from which the recipient reconstructs identical generators. A proof, its statement, and its parameters
+//! can be encoded together as a self-contained bundle using [`encode_bundle`] and verified in one call using
+//! [`verify_bundle`].
 //!
 //! # Warning
 //!
@@ -109,24 +147,95 @@
 #![no_std]
 
 extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
 
 pub use merlin::Transcript;
 
+/// Triptych proofs with commitment balance.
+pub mod balance;
+pub use balance::BalanceProof;
+/// Verification of self-contained serialized proofs.
+pub mod bundle;
+pub use bundle::{encode_bundle, verify_bundle};
+/// Reusable verification contexts.
+pub mod context;
+pub use context::VerificationContext;
+/// Byte encodings shared by foreign-language entry points.
+#[cfg(any(feature = "ffi", feature = "wasm"))]
+pub(crate) mod encoding;
+/// Proofs with attached unbound metadata.
+pub mod envelope;
+pub use envelope::ProofEnvelope;
+/// C-compatible proving and verification entry points.
+#[cfg(feature = "ffi")]
+pub mod ffi;
 /// Iterated arbitrary-base Gray code functionaity.
 pub(crate) mod gray;
+/// Hashing to the Ristretto group.
+pub mod hash;
+pub use hash::hash_to_point;
+/// Input set reuse tracking.
+pub mod history;
+pub use history::RingHistory;
+/// Operation counts for profiling.
+#[cfg(feature = "instrument")]
+pub mod instrument;
+/// Multiscalar multiplication functionality.
+pub(crate) mod msm;
 /// Public parameters used for generating and verifying Triptych proofs.
 pub mod parameters;
-pub use parameters::Parameters;
+pub use parameters::{Dimensions, Parameters, ParametersBuilder};
 /// Triptych proofs.
 pub mod proof;
 pub use proof::Proof;
+/// Lookup of public parameters by hash.
+pub mod registry;
+pub use registry::ParametersRegistry;
 /// Triptych proof statements.
 pub mod statement;
 pub use statement::{InputSet, Statement};
+/// Canonical test vectors.
+#[cfg(feature = "test-vectors")]
+pub mod test_vectors;
+/// Helpers for generating test inputs.
+#[cfg(any(all(test, feature = "prover"), feature = "test-helpers"))]
+pub mod testing;
+/// Transcript operation tracing for auditing.
+#[cfg(feature = "trace")]
+pub mod trace;
 /// Triptych proof transcripts.
 pub(crate) mod transcript;
+pub use transcript::FiatShamirTranscript;
+/// Triptych proofs without linkability.
+pub mod unlinkable;
+pub use unlinkable::UnlinkableProof;
 /// Various utility functionality.
 pub(crate) mod util;
+/// WASM-friendly proving and verification entry points.
+#[cfg(feature = "wasm")]
+pub mod wasm;
 /// Triptych proof witnesses.
+#[cfg(feature = "prover")]
 pub mod witness;
+#[cfg(feature = "prover")]
 pub use witness::Witness;
+
+// Public types must be safe to share across threads, such as in a verification pool
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<BalanceProof>();
+    assert_send_sync::<Dimensions>();
+    assert_send_sync::<InputSet>();
+    assert_send_sync::<Parameters>();
+    assert_send_sync::<ParametersRegistry>();
+    assert_send_sync::<Proof>();
+    assert_send_sync::<ProofEnvelope>();
+    assert_send_sync::<RingHistory>();
+    assert_send_sync::<Statement>();
+    assert_send_sync::<UnlinkableProof>();
+    assert_send_sync::<VerificationContext>();
+    #[cfg(feature = "prover")]
+    assert_send_sync::<Witness>();
+};
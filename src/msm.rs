@@ -0,0 +1,141 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{vec, vec::Vec};
+use core::borrow::Borrow;
+
+use curve25519_dalek::{traits::Identity, RistrettoPoint, Scalar};
+
+// Number of bits in a scalar encoding
+const SCALAR_BITS: usize = 256;
+
+// Bounds on the window size, which determines the number of buckets
+pub(crate) const MIN_WINDOW: usize = 1;
+pub(crate) const MAX_WINDOW: usize = 16;
+
+/// Compute a multiscalar multiplication using the bucket method with a fixed window size, in variable time.
+///
+/// The window size must be between `MIN_WINDOW` and `MAX_WINDOW` inclusive, and the number of scalars and points must
+/// match. If any of these conditions is not met, returns `None`.
+///
+/// Operations run in variable time, so don't use this for secret data.
+#[allow(clippy::arithmetic_side_effects)]
+pub(crate) fn vartime_multiscalar_mul_windowed<I, J>(scalars: I, points: J, window: usize) -> Option<RistrettoPoint>
+where
+    I: IntoIterator,
+    I::Item: Borrow<Scalar>,
+    J: IntoIterator,
+    J::Item: Borrow<RistrettoPoint>,
+{
+    if !(MIN_WINDOW..=MAX_WINDOW).contains(&window) {
+        return None;
+    }
+
+    // Collect the scalar encodings and points, which must have the same length
+    let scalars = scalars
+        .into_iter()
+        .map(|scalar| scalar.borrow().to_bytes())
+        .collect::<Vec<[u8; 32]>>();
+    let points = points
+        .into_iter()
+        .map(|point| *point.borrow())
+        .collect::<Vec<RistrettoPoint>>();
+    if scalars.len() != points.len() {
+        return None;
+    }
+
+    // These cannot overflow since the window size is bounded
+    let windows = SCALAR_BITS.div_ceil(window);
+    let bucket_count = (1usize << window) - 1;
+
+    // Process windows from most to least significant
+    let mut result = RistrettoPoint::identity();
+    for index in (0..windows).rev() {
+        // Shift the previous result by the window size
+        for _ in 0..window {
+            result += result;
+        }
+
+        // Place each point into the bucket for its digit, skipping zero digits
+        let mut buckets = vec![RistrettoPoint::identity(); bucket_count];
+        for (scalar, point) in scalars.iter().zip(points.iter()) {
+            let digit = get_digit(scalar, index * window, window);
+            if digit > 0 {
+                buckets[digit - 1] += point;
+            }
+        }
+
+        // Sum the buckets, weighted by their digits, using a running sum
+        let mut running_sum = RistrettoPoint::identity();
+        let mut window_sum = RistrettoPoint::identity();
+        for bucket in buckets.iter().rev() {
+            running_sum += bucket;
+            window_sum += running_sum;
+        }
+
+        result += window_sum;
+    }
+
+    Some(result)
+}
+
+/// Extract the little-endian digit of `width` bits starting at bit `start` of a scalar encoding.
+#[allow(clippy::arithmetic_side_effects)]
+fn get_digit(scalar: &[u8; 32], start: usize, width: usize) -> usize {
+    // Bits beyond the encoding are treated as zero; the shifts cannot overflow since `width` is bounded
+    (0..width)
+        .map(|offset| start + offset)
+        .filter(|bit| *bit < SCALAR_BITS)
+        .fold(0usize, |digit, bit| {
+            digit | ((usize::from(scalar[bit / 8] >> (bit % 8)) & 1) << (bit - start))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use curve25519_dalek::{traits::VartimeMultiscalarMul, RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use super::{vartime_multiscalar_mul_windowed, MAX_WINDOW, MIN_WINDOW};
+
+    #[test]
+    fn test_windowed() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Include some edge-case scalars
+        const SIZE: usize = 32;
+        let mut scalars = (0..SIZE).map(|_| Scalar::random(&mut rng)).collect::<Vec<Scalar>>();
+        scalars[0] = Scalar::ZERO;
+        scalars[1] = Scalar::ONE;
+        scalars[2] = -Scalar::ONE;
+        let points = (0..SIZE)
+            .map(|_| RistrettoPoint::random(&mut rng))
+            .collect::<Vec<RistrettoPoint>>();
+
+        // Every valid window size must agree with the default implementation
+        let expected = RistrettoPoint::vartime_multiscalar_mul(scalars.iter(), points.iter());
+        for window in MIN_WINDOW..=MAX_WINDOW {
+            assert_eq!(
+                vartime_multiscalar_mul_windowed(scalars.iter(), points.iter(), window).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let scalars = [Scalar::random(&mut rng), Scalar::random(&mut rng)];
+        let points = [RistrettoPoint::random(&mut rng)];
+
+        // Mismatched lengths
+        assert!(vartime_multiscalar_mul_windowed(scalars.iter(), points.iter(), 4).is_none());
+
+        // Invalid window sizes
+        assert!(vartime_multiscalar_mul_windowed(scalars[..1].iter(), points.iter(), MIN_WINDOW - 1).is_none());
+        assert!(vartime_multiscalar_mul_windowed(scalars[..1].iter(), points.iter(), MAX_WINDOW + 1).is_none());
+    }
+}
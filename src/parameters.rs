@@ -1,75 +1,309 @@
 // Copyright (c) 2024, The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
+#[cfg(feature = "prover")]
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::iter::once;
 
 use blake3::Hasher;
+#[cfg(feature = "prover")]
+use curve25519_dalek::ristretto::RistrettoBasepointTable;
 use curve25519_dalek::{
     constants::RISTRETTO_BASEPOINT_POINT,
+    ristretto::CompressedRistretto,
     traits::{MultiscalarMul, VartimeMultiscalarMul},
     RistrettoPoint,
     Scalar,
 };
 use snafu::prelude::*;
 
-use crate::util::OperationTiming;
+use crate::{
+    hash::hash_to_point,
+    proof::DeserializeLimits,
+    transcript::{EXTERNAL_RNG_BYTES, PROVER_RNG_BUILDS},
+    util::OperationTiming,
+};
 
 /// Public parameters used for generating and verifying Triptych proofs.
 ///
 /// Parameters require a base and exponent that define the size of verification key vectors, as well as group generators
 /// `G` and `U` required by the protocol. You can either use [`Parameters::new`] to have these generators defined
 /// securely for you, or use [`Parameters::new_with_generators`] if your use case requires specific values for these.
+/// If you need further options, use a [`ParametersBuilder`].
+///
+/// Two [`Parameters`] are equal exactly when they have the same hash, which binds the base, exponent, all generators,
+/// and any context. Equal parameters produce interoperable proofs, regardless of how they were constructed or whether
+/// they have precomputed tables. The hash is always computed when [`Parameters`] are built, and is never taken from
+/// external data.
 #[allow(non_snake_case)]
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone)]
 pub struct Parameters {
-    n: u32,
-    m: u32,
+    dimensions: Dimensions,
     G: RistrettoPoint,
     U: RistrettoPoint,
     CommitmentG: Vec<RistrettoPoint>,
     CommitmentH: RistrettoPoint,
+    #[cfg(feature = "prover")]
+    G_table: Option<Arc<RistrettoBasepointTable>>,
+    context: Vec<u8>,
     hash: Vec<u8>,
 }
 
+// The hash binds everything that affects proofs, while precomputed tables are an implementation detail
+impl PartialEq for Parameters {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
+}
+
+impl Eq for Parameters {}
+
+impl Default for Parameters {
+    /// Get the recommended [`Parameters`], as with [`Parameters::recommended`].
+    fn default() -> Self {
+        Self::recommended()
+    }
+}
+
+/// The dimensions of a verification key vector, consisting of a base `n` and exponent `m`.
+///
+/// Dimensions are always valid: it must be the case that `n, m > 1`, that the vector size `n**m` does not overflow
+/// [`prim@u64`], and that the number of commitment generators `n*m` does not overflow [`prim@u32`]. Using named
+/// accessors, rather than passing bare integers, helps to avoid mixing up the base and exponent.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Dimensions {
+    n: u32,
+    m: u32,
+}
+
+impl Dimensions {
+    /// Generate new [`Dimensions`] from a base `n` and exponent `m`.
+    ///
+    /// If the base or exponent is too small, returns [`ParameterError::DegenerateDimension`].
+    /// If the vector size or number of commitment generators overflows, returns [`ParameterError::Overflow`].
+    pub fn new(n: u32, m: u32) -> Result<Self, ParameterError> {
+        // These bounds are required by the protocol
+        if n < 2 || m < 2 {
+            return Err(ParameterError::DegenerateDimension);
+        }
+
+        // Check that the vector size and number of commitment generators don't overflow
+        if u64::from(n).checked_pow(m).is_none() || n.checked_mul(m).is_none() {
+            return Err(ParameterError::Overflow);
+        }
+
+        Ok(Self { n, m })
+    }
+
+    /// Get the base `n` from these [`Dimensions`].
+    pub fn get_n(&self) -> u32 {
+        self.n
+    }
+
+    /// Get the exponent `m` from these [`Dimensions`].
+    pub fn get_m(&self) -> u32 {
+        self.m
+    }
+
+    /// Get the verification key vector size `N == n**m` from these [`Dimensions`].
+    #[allow(non_snake_case)]
+    pub fn get_N(&self) -> u64 {
+        // This is guaranteed not to overflow
+        u64::from(self.n).pow(self.m)
+    }
+
+    /// Estimate the work needed to verify a batch of `count` proofs with these [`Dimensions`].
+    fn verify_batch_cost(self, count: usize) -> VerifyCost {
+        let n = u64::from(self.n);
+        let m = u64::from(self.m);
+        let count = u64::try_from(count).unwrap_or(u64::MAX);
+
+        // The generators `G`, `CommitmentG`, `CommitmentH`, and `U`
+        let fixed_base_terms = n.saturating_mul(m).saturating_add(3);
+
+        // The input set, and `A`, `B`, `C`, `D`, `J`, `X`, and `Y` for each proof
+        let variable_base_terms = self
+            .get_N()
+            .saturating_add(count.saturating_mul(m.saturating_mul(2).saturating_add(5)));
+
+        VerifyCost {
+            msm_terms: fixed_base_terms.saturating_add(variable_base_terms),
+            fixed_base_terms,
+            variable_base_terms,
+        }
+    }
+
+    /// Get the number of group elements and scalars in a proof with these [`Dimensions`].
+    fn proof_elements(self) -> u64 {
+        let n = u64::from(self.n);
+        let m = u64::from(self.m);
+
+        // `A`, `B`, `C`, `D`, `X`, `Y`, `f`, `z_A`, `z_C`, and `z`
+        m.saturating_mul(n.saturating_add(1)).saturating_add(7)
+    }
+}
+
+/// An estimate of the work needed to verify proofs using some [`Parameters`].
+///
+/// Verification is dominated by a single multiscalar multiplication, whose cost grows slightly sublinearly with its
+/// number of terms. Terms whose points are fixed by the parameters could benefit from precomputation, while the others
+/// depend on the input set and proofs.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct VerifyCost {
+    /// The total number of multiscalar multiplication terms.
+    pub msm_terms: u64,
+    /// The number of terms whose points are generators fixed by the parameters.
+    pub fixed_base_terms: u64,
+    /// The number of terms whose points come from the input set or proofs.
+    pub variable_base_terms: u64,
+}
+
+/// A builder for [`Parameters`] with named options.
+///
+/// You must set the base using [`ParametersBuilder::radix`] and the exponent using [`ParametersBuilder::digits`].
+/// All other options have defaults that match [`Parameters::new`].
+#[allow(non_snake_case)]
+#[derive(Clone, Default)]
+pub struct ParametersBuilder {
+    n: u32,
+    m: u32,
+    generators: Option<(RistrettoPoint, RistrettoPoint)>,
+    context: Vec<u8>,
+    precompute: bool,
+}
+
+impl ParametersBuilder {
+    /// Create a new [`ParametersBuilder`] with default options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the base `n > 1` used for defining the verification key vector size.
+    #[must_use]
+    pub fn radix(mut self, n: u32) -> Self {
+        self.n = n;
+        self
+    }
+
+    /// Set the exponent `m > 1` used for defining the verification key vector size.
+    #[must_use]
+    pub fn digits(mut self, m: u32) -> Self {
+        self.m = m;
+        self
+    }
+
+    /// Set the base and exponent from [`Dimensions`].
+    #[must_use]
+    pub fn dimensions(self, dimensions: Dimensions) -> Self {
+        self.radix(dimensions.get_n()).digits(dimensions.get_m())
+    }
+
+    /// Use specific group generators `G` and `U`, as with [`Parameters::new_with_generators`].
+    ///
+    /// The security of these generators cannot be checked.
+    /// If this is not set, the generators are securely defined for you, as with [`Parameters::new`].
+    #[allow(non_snake_case)]
+    #[must_use]
+    pub fn generators(mut self, G: &RistrettoPoint, U: &RistrettoPoint) -> Self {
+        self.generators = Some((*G, *U));
+        self
+    }
+
+    /// Bind an application-specific `context` into the [`Parameters`] for domain separation.
+    ///
+    /// Proofs generated using parameters with one context do not verify using parameters with any other context.
+    /// An empty context is the same as not setting one.
+    #[must_use]
+    pub fn context(mut self, context: &[u8]) -> Self {
+        self.context = context.to_vec();
+        self
+    }
+
+    /// Precompute a table for the generator `G`.
+    ///
+    /// This makes computing verification keys and generating proofs faster, at the cost of about 30 KiB of memory.
+    /// It does not affect the resulting parameters in any other way, and is disabled by default.
+    /// Without the `prover` feature, the table would never be used, so this has no effect.
+    #[must_use]
+    pub fn precompute(mut self, precompute: bool) -> Self {
+        self.precompute = precompute;
+        self
+    }
+
+    /// Build the [`Parameters`].
+    ///
+    /// It must be the case that `n**m` does not overflow [`prim@u64`]. If the base or exponent is too small, returns
+    /// [`ParameterError::DegenerateDimension`]. If the size overflows, returns [`ParameterError::Overflow`].
+    #[allow(non_snake_case)]
+    pub fn build(self) -> Result<Parameters, ParameterError> {
+        let (G, U) = self.generators.unwrap_or_else(Parameters::default_generators);
+
+        Ok(Parameters::generate(
+            Dimensions::new(self.n, self.m)?,
+            &G,
+            &U,
+            &self.context,
+            self.precompute,
+        ))
+    }
+}
+
 /// Errors that can arise relating to [`Parameters`].
 #[derive(Debug, Snafu)]
 pub enum ParameterError {
     /// An invalid parameter was provided.
     #[snafu(display("An invalid parameter was provided"))]
     InvalidParameter,
+    /// The base or exponent is too small to be used; both must be at least 2.
+    #[snafu(display("The base or exponent is too small to be used"))]
+    DegenerateDimension,
+    /// The verification key vector size `n**m` overflows.
+    #[snafu(display("The verification key vector size overflows"))]
+    Overflow,
+    /// An encoding of parameters could not be deserialized.
+    #[snafu(display("An encoding of parameters could not be deserialized"))]
+    FailedDeserialization,
 }
 
 impl Parameters {
-    // Version identifier used for hashing
+    // Flags indicating whether serialized parameters use the default generators
+    const DEFAULT_GENERATORS: u8 = 0;
+    const SPECIFIC_GENERATORS: u8 = 1;
+    // Version identifier used for hashing and serialization
     const VERSION: u64 = 0;
 
+    /// Get the default group generators `G` and `U`.
+    #[allow(non_snake_case)]
+    fn default_generators() -> (RistrettoPoint, RistrettoPoint) {
+        // Use the default base point for `G` (this is arbitrary)
+        let G = RISTRETTO_BASEPOINT_POINT;
+
+        // Use `BLAKE3` to generate `U`
+        (G, hash_to_point(b"Triptych U", &[]))
+    }
+
     /// Generate new [`Parameters`] for Triptych proofs.
     ///
     /// The base `n > 1` and exponent `m > 1` define the size of verification key vectors, so it must be the case that
-    /// `n**m` does not overflow [`prim@u32`]. If any of these conditions is not met, returns a [`ParameterError`].
+    /// `n**m` does not overflow [`prim@u64`]. If either bound is not met, returns
+    /// [`ParameterError::DegenerateDimension`]. If the size overflows, returns [`ParameterError::Overflow`].
+    ///
+    /// In particular, single-digit parameters with `m == 1` are not supported. A ring whose size is a perfect power can
+    /// instead use a smaller base; for example, a ring of size 16 can use `n = 4` and `m = 2`.
     ///
     /// This function produces group generators `G` and `U` for you.
     /// If your use case requires specific generators, use [`Parameters::new_with_generators`] instead.
     #[allow(non_snake_case)]
     pub fn new(n: u32, m: u32) -> Result<Self, ParameterError> {
-        // Use the default base point for `G` (this is arbitrary)
-        let G = RISTRETTO_BASEPOINT_POINT;
-
-        // Use `BLAKE3` to generate `U`
-        let mut U_bytes = [0u8; 64];
-        let mut hasher = Hasher::new();
-        hasher.update(b"Triptych U");
-        hasher.finalize_xof().fill(&mut U_bytes);
-        let U = RistrettoPoint::from_uniform_bytes(&U_bytes);
-
-        Self::new_with_generators(n, m, &G, &U)
+        ParametersBuilder::new().radix(n).digits(m).build()
     }
 
     /// Generate new [`Parameters`] for Triptych proofs.
     ///
     /// The base `n > 1` and exponent `m > 1` define the size of verification key vectors, so it must be the case that
-    /// `n**m` does not overflow [`prim@u32`]. If any of these conditions is not met, returns a [`ParameterError`].
+    /// `n**m` does not overflow [`prim@u64`]. If either bound is not met, returns
+    /// [`ParameterError::DegenerateDimension`]. If the size overflows, returns [`ParameterError::Overflow`].
     ///
     /// You must also provide independent group generators `G` and `U`:
     /// - The generator `G` is used to define verification keys.
@@ -79,22 +313,77 @@ impl Parameters {
     /// If you'd rather have the generators securely defined for you, use [`Parameters::new`] instead.
     #[allow(non_snake_case)]
     pub fn new_with_generators(n: u32, m: u32, G: &RistrettoPoint, U: &RistrettoPoint) -> Result<Self, ParameterError> {
-        // These bounds are required by the protocol
-        if n < 2 || m < 2 {
-            return Err(ParameterError::InvalidParameter);
-        }
+        ParametersBuilder::new().radix(n).digits(m).generators(G, U).build()
+    }
 
-        // Check that the parameters don't overflow `u32`
-        if n.checked_pow(m).is_none() {
-            return Err(ParameterError::InvalidParameter);
+    /// Get recommended [`Parameters`] for Triptych proofs, if you don't have a particular input set size in mind.
+    ///
+    /// These use `n = 2` and `m = 7`, so input sets contain `N = 128` verification keys, and the generators are
+    /// produced for you as with [`Parameters::new`]. This is also the choice that [`Parameters::for_ring_size`] makes
+    /// for that size. The same [`Parameters`] are available using [`Default`].
+    ///
+    /// Larger input sets give more anonymity, but verification time grows linearly with `N`. Proofs are much smaller:
+    /// they contain `2*m + 4` group elements and `m*(n - 1) + 3` scalars, so their size grows only logarithmically with
+    /// `N`. For a much larger or smaller input set, use [`Parameters::for_ring_size`] to choose the base and exponent.
+    #[allow(non_snake_case)]
+    pub fn recommended() -> Self {
+        let (G, U) = Self::default_generators();
+
+        Self::generate(Dimensions { n: 2, m: 7 }, &G, &U, &[], false)
+    }
+
+    /// Generate new [`Parameters`] for Triptych proofs whose input sets contain at least `size` verification keys.
+    ///
+    /// This chooses the base `n` and exponent `m` that minimize the cost of verifying a proof, as estimated by
+    /// [`Parameters::verify_cost`], among those for which `N == n**m` is at least `size`; ties are broken by choosing
+    /// the smaller proof. Since verification cost is dominated by `N`, this is almost always the smallest supported
+    /// size that is at least `size`. If `N` is larger than the number of verification keys you have, you'll need to
+    /// choose more of them, or use [`InputSet::new_with_padding`](`crate::InputSet::new_with_padding`) if your use
+    /// case can safely allow this.
+    ///
+    /// The generators are produced for you as with [`Parameters::new`].
+    ///
+    /// If no supported size is at least `size`, returns [`ParameterError::Overflow`].
+    pub fn for_ring_size(size: u64) -> Result<Self, ParameterError> {
+        let dimensions = (2..u64::BITS)
+            .filter_map(|m| Dimensions::new(Self::min_radix(size, m), m).ok())
+            .min_by_key(|dimensions| (dimensions.verify_batch_cost(1).msm_terms, dimensions.proof_elements()))
+            .ok_or(ParameterError::Overflow)?;
+
+        ParametersBuilder::new().dimensions(dimensions).build()
+    }
+
+    /// Find the smallest base `n > 1` such that `n**m` is at least `size`, or overflows.
+    fn min_radix(size: u64, m: u32) -> u32 {
+        let mut low = 2u32;
+        let mut high = u32::MAX;
+        while low < high {
+            // This can't overflow, since `low < high`
+            let mid = low.saturating_add(high.abs_diff(low) / 2);
+            if u64::from(mid).checked_pow(m).is_none_or(|pow| pow >= size) {
+                high = mid;
+            } else {
+                low = mid.saturating_add(1);
+            }
         }
 
+        low
+    }
+
+    /// Generate new [`Parameters`] from the options supplied to a [`ParametersBuilder`].
+    #[allow(non_snake_case)]
+    fn generate(
+        dimensions: Dimensions,
+        G: &RistrettoPoint,
+        U: &RistrettoPoint,
+        context: &[u8],
+        #[cfg_attr(not(feature = "prover"), allow(unused_variables))] precompute: bool,
+    ) -> Self {
+        let n = dimensions.get_n();
+        let m = dimensions.get_m();
+
         // Use `BLAKE3` to generate `CommitmentH`
-        let mut CommitmentH_bytes = [0u8; 64];
-        let mut hasher = Hasher::new();
-        hasher.update(b"Triptych CommitmentH");
-        hasher.finalize_xof().fill(&mut CommitmentH_bytes);
-        let CommitmentH = RistrettoPoint::from_uniform_bytes(&CommitmentH_bytes);
+        let CommitmentH = hash_to_point(b"Triptych CommitmentH", &[]);
 
         // Use `BLAKE3` for the commitment matrix generators
         let mut hasher = Hasher::new();
@@ -103,7 +392,8 @@ impl Parameters {
         hasher.update(&m.to_le_bytes());
         let mut hasher_xof = hasher.finalize_xof();
         let mut CommitmentG_bytes = [0u8; 64];
-        let CommitmentG = (0..n.checked_mul(m).ok_or(ParameterError::InvalidParameter)?)
+        // The number of generators can't overflow, since this is guaranteed by the dimensions
+        let CommitmentG = (0..n.saturating_mul(m))
             .map(|_| {
                 hasher_xof.fill(&mut CommitmentG_bytes);
                 RistrettoPoint::from_uniform_bytes(&CommitmentG_bytes)
@@ -123,15 +413,24 @@ impl Parameters {
         }
         hasher.update(CommitmentH.compress().as_bytes());
 
-        Ok(Parameters {
-            n,
-            m,
+        // Only bind a nonempty context, so parameters without one are unchanged
+        if !context.is_empty() {
+            hasher.update(b"context");
+            hasher.update(&(context.len() as u64).to_le_bytes());
+            hasher.update(context);
+        }
+
+        Parameters {
+            dimensions,
             G: *G,
             U: *U,
             CommitmentG,
             CommitmentH,
+            #[cfg(feature = "prover")]
+            G_table: precompute.then(|| Arc::new(RistrettoBasepointTable::create(G))),
+            context: context.to_vec(),
             hash: hasher.finalize().as_bytes().to_vec(),
-        })
+        }
     }
 
     /// Commit to a matrix.
@@ -145,13 +444,18 @@ impl Parameters {
         timing: OperationTiming,
     ) -> Result<RistrettoPoint, ParameterError> {
         // Check that the matrix dimensions are valid
-        if matrix.len() != (self.m as usize) || matrix.iter().any(|m| m.len() != (self.n as usize)) {
+        if matrix.len() != (self.get_m() as usize) || matrix.iter().any(|m| m.len() != (self.get_n() as usize)) {
             return Err(ParameterError::InvalidParameter);
         }
 
         // Flatten before evaluating the commitment
         let scalars = matrix.iter().flatten().chain(once(mask)).collect::<Vec<&Scalar>>();
-        let points = self.get_CommitmentG().iter().chain(once(self.get_CommitmentH()));
+        let points = self
+            .commitment_generators()
+            .iter()
+            .chain(once(self.commitment_blinding_generator()));
+        #[cfg(feature = "instrument")]
+        crate::instrument::record_multiscalar_mul(scalars.len());
 
         match timing {
             OperationTiming::Constant => Ok(RistrettoPoint::multiscalar_mul(scalars, points)),
@@ -159,59 +463,710 @@ impl Parameters {
         }
     }
 
+    /// Check that a scalar `matrix` and masking scalar `blinding` open a matrix `commitment`, using these
+    /// [`Parameters`].
+    ///
+    /// This is useful for debugging, since it recomputes commitments like those in a [`Proof`](`crate::Proof`) from
+    /// their openings. The `matrix` must be `m x n`, with each row using consecutive commitment generators; if it is
+    /// not, returns `false`.
+    ///
+    /// This function runs in variable time, so it should not be used with secret data.
+    #[must_use = "verification result must be checked"]
+    pub fn verify_matrix_commitment(
+        &self,
+        matrix: &[Vec<Scalar>],
+        blinding: &Scalar,
+        commitment: &RistrettoPoint,
+    ) -> bool {
+        self.commit_matrix(matrix, blinding, OperationTiming::Variable)
+            .is_ok_and(|expected| &expected == commitment)
+    }
+
+    /// Commit to a vector of scalars `scalars` with a masking scalar `blinding`, using these [`Parameters`].
+    ///
+    /// The commitment uses a prefix of the commitment generators, so `scalars` may have any length up to `n*m`.
+    /// If `scalars` is longer than this, returns a [`ParameterError`].
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    pub fn commit_vector(&self, scalars: &[Scalar], blinding: &Scalar) -> Result<RistrettoPoint, ParameterError> {
+        // Check that we have enough generators
+        if scalars.len() > self.CommitmentG.len() {
+            return Err(ParameterError::InvalidParameter);
+        }
+
+        let points = self
+            .CommitmentG
+            .iter()
+            .take(scalars.len())
+            .chain(once(&self.CommitmentH));
+
+        Ok(RistrettoPoint::multiscalar_mul(
+            scalars.iter().chain(once(blinding)),
+            points,
+        ))
+    }
+
+    /// Get the group generator `G` from these [`Parameters`].
+    ///
+    /// This is the generator used for defining verification keys.
+    pub fn base_generator(&self) -> &RistrettoPoint {
+        &self.G
+    }
+
+    /// Determine if these [`Parameters`] have a precomputed table for the group generator `G`.
+    #[cfg(feature = "prover")]
+    pub(crate) fn has_precomputed_table(&self) -> bool {
+        self.G_table.is_some()
+    }
+
+    /// Multiply the group generator `G` by a scalar, using a precomputed table if available.
+    #[cfg(feature = "prover")]
+    pub(crate) fn mul_base_generator(&self, scalar: &Scalar) -> RistrettoPoint {
+        match &self.G_table {
+            Some(table) => table.as_ref() * scalar,
+            None => scalar * self.G,
+        }
+    }
+
     /// Get the group generator `G` from these [`Parameters`].
     ///
     /// This is the generator used for defining verification keys.
     #[allow(non_snake_case)]
+    #[deprecated(since = "0.2.0", note = "use `Parameters::base_generator` instead")]
     pub fn get_G(&self) -> &RistrettoPoint {
-        &self.G
+        self.base_generator()
+    }
+
+    /// Get the group generator `U` from these [`Parameters`].
+    ///
+    /// This is the generator used for defining linking tags.
+    pub fn linkability_generator(&self) -> &RistrettoPoint {
+        &self.U
     }
 
     /// Get the group generator `U` from these [`Parameters`].
     ///
     /// This is the generator used for defining linking tags.
     #[allow(non_snake_case)]
+    #[deprecated(since = "0.2.0", note = "use `Parameters::linkability_generator` instead")]
     pub fn get_U(&self) -> &RistrettoPoint {
-        &self.U
+        self.linkability_generator()
+    }
+
+    /// Get the [`Dimensions`] from these [`Parameters`].
+    pub fn get_dimensions(&self) -> Dimensions {
+        self.dimensions
     }
 
     /// Get the value `n` from these [`Parameters`].
     ///
     /// This is the base used for defining the verification key vector size.
     pub fn get_n(&self) -> u32 {
-        self.n
+        self.dimensions.get_n()
     }
 
     /// Get the value `m` from these [`Parameters`].
     ///
     /// This is the exponent used for defining the verification key vector size.
     pub fn get_m(&self) -> u32 {
-        self.m
+        self.dimensions.get_m()
     }
 
     /// Get the value `N == n**m` from these [`Parameters`].
     ///
     /// This is the verification key vector size.
+    ///
+    /// Note that verification key vectors must be stored in memory, so in practice this is limited by the platform's
+    /// pointer width and available memory; proving and verification costs are linear in this size.
     #[allow(non_snake_case)]
-    pub fn get_N(&self) -> u32 {
-        // This is guaranteed not to overflow
-        self.n.pow(self.m)
+    pub fn get_N(&self) -> u64 {
+        self.dimensions.get_N()
     }
 
-    /// Get the value `CommitmentG` from these [`Parameters`].
-    #[allow(non_snake_case)]
-    pub(crate) fn get_CommitmentG(&self) -> &Vec<RistrettoPoint> {
+    /// Get the commitment generators from these [`Parameters`].
+    ///
+    /// These are the `n*m` generators used for committing to scalar matrices and vectors.
+    pub fn commitment_generators(&self) -> &[RistrettoPoint] {
         &self.CommitmentG
     }
 
-    /// Get the value `CommitmentH` from these [`Parameters`].
-    #[allow(non_snake_case)]
-    pub(crate) fn get_CommitmentH(&self) -> &RistrettoPoint {
+    /// Get the commitment masking generator from these [`Parameters`].
+    ///
+    /// This is the generator used for masking commitments to scalar matrices and vectors.
+    pub fn commitment_blinding_generator(&self) -> &RistrettoPoint {
         &self.CommitmentH
     }
 
+    /// Get the generators whose use in verification does not depend on the statement or proof.
+    ///
+    /// These are `G`, the commitment generators, the commitment masking generator, and `U`, in that order.
+    pub(crate) fn fixed_generators(&self) -> impl Iterator<Item = &RistrettoPoint> {
+        once(&self.G)
+            .chain(self.CommitmentG.iter())
+            .chain(once(&self.CommitmentH))
+            .chain(once(&self.U))
+    }
+
+    /// Get the number of bytes a prover draws from its random number generator when generating a proof with these
+    /// [`Parameters`].
+    ///
+    /// The prover does not sample scalars directly from the random number generator you supply.
+    /// Instead, it builds a transcript-based generator that binds the transcript and witness, and finalizes it using
+    /// 32 bytes from your generator. This happens twice: when the transcript is initialized, and after the commitment
+    /// phase. All prover scalars are then sampled from the transcript-based generator, so the total is 64 bytes and
+    /// does not depend on `n` or `m`.
+    #[allow(clippy::arithmetic_side_effects, clippy::unused_self)]
+    pub fn prove_entropy_bytes(&self) -> usize {
+        // This cannot overflow since both values are small constants
+        EXTERNAL_RNG_BYTES * PROVER_RNG_BUILDS
+    }
+
+    /// Estimate the work needed to verify a single proof using these [`Parameters`].
+    ///
+    /// The verifier uses a multiscalar multiplication with `N + 2*m + n*m + 8` terms.
+    pub fn verify_cost(&self) -> VerifyCost {
+        self.verify_batch_cost(1)
+    }
+
+    /// Estimate the work needed to verify a batch of `count` proofs at once using these [`Parameters`].
+    ///
+    /// The proofs in a batch share their input set and generators, so the number of multiscalar multiplication terms
+    /// is `N + n*m + 3` plus `2*m + 5` for each proof. This is useful for choosing batch sizes. The estimate saturates
+    /// instead of overflowing.
+    pub fn verify_batch_cost(&self, count: usize) -> VerifyCost {
+        self.dimensions.verify_batch_cost(count)
+    }
+
     /// Get a cryptographic hash representation of these [`Parameters`], suitable for transcripting.
-    pub(crate) fn get_hash(&self) -> &[u8] {
+    ///
+    /// This is the 32-byte `BLAKE3` hash of the following, which is bound into every proof transcript:
+    /// - the domain separator `Triptych Parameters`;
+    /// - the version as an 8-byte little-endian integer;
+    /// - the base `n` and exponent `m`, each as a 4-byte little-endian integer;
+    /// - the compressed generators `G` and `U`;
+    /// - the compressed commitment generators, followed by the compressed commitment blinding generator;
+    /// - if the context is nonempty, the label `context`, the context length as an 8-byte little-endian integer, and
+    ///   the context.
+    ///
+    /// Since the commitment generators are derived from the base and exponent, the hash is determined by the encoding
+    /// produced by [`Parameters::to_bytes`].
+    pub fn get_hash(&self) -> &[u8] {
         &self.hash
     }
+
+    /// Serialize a compact description of these [`Parameters`] to a byte vector.
+    ///
+    /// The encoding consists of:
+    /// - the version as an 8-byte little-endian integer;
+    /// - the base `n` and exponent `m`, each as a 4-byte little-endian integer;
+    /// - a flag byte, which is `0` if the parameters use the default generators `G` and `U`, or `1` if they use
+    ///   specific generators, in which case the compressed `G` and `U` follow;
+    /// - the context length as an 8-byte little-endian integer, followed by the context.
+    ///
+    /// The commitment generators are always derived from the base and exponent, so they are never encoded.
+    /// Parameters with specific generators have a longer encoding, since those generators can't be derived.
+    /// Precomputed tables are not encoded.
+    #[allow(non_snake_case)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let generators =
+            ((self.G, self.U) != Self::default_generators()).then(|| [self.G.compress(), self.U.compress()]);
+
+        let mut result = Vec::with_capacity(
+            25usize
+                .saturating_add(if generators.is_some() { 64 } else { 0 })
+                .saturating_add(self.context.len()),
+        );
+        result.extend(Self::VERSION.to_le_bytes());
+        result.extend(self.get_n().to_le_bytes());
+        result.extend(self.get_m().to_le_bytes());
+        match generators {
+            Some([G, U]) => {
+                result.push(Self::SPECIFIC_GENERATORS);
+                result.extend_from_slice(G.as_bytes());
+                result.extend_from_slice(U.as_bytes());
+            },
+            None => result.push(Self::DEFAULT_GENERATORS),
+        }
+        result.extend((self.context.len() as u64).to_le_bytes());
+        result.extend_from_slice(&self.context);
+
+        result
+    }
+
+    /// Deserialize [`Parameters`] from a byte slice produced by [`Parameters::to_bytes`].
+    ///
+    /// This reconstructs all generators, so the resulting [`Parameters`] are equal to the originals and have the same
+    /// hash. Since the work and memory needed for this grow with `n*m`, dimensions that exceed `limits` are rejected
+    /// before any generators are derived.
+    ///
+    /// If `bytes` is not a canonical encoding with a supported version, or its dimensions exceed `limits`, returns
+    /// [`ParameterError::FailedDeserialization`]. If the encoded dimensions are invalid, returns another
+    /// [`ParameterError`].
+    #[allow(non_snake_case)]
+    pub fn from_bytes(bytes: &[u8], limits: DeserializeLimits) -> Result<Self, ParameterError> {
+        // Parse the version and dimensions
+        let (version, rest) = bytes
+            .split_first_chunk::<8>()
+            .ok_or(ParameterError::FailedDeserialization)?;
+        if u64::from_le_bytes(*version) != Self::VERSION {
+            return Err(ParameterError::FailedDeserialization);
+        }
+        let (n, rest) = rest
+            .split_first_chunk::<4>()
+            .ok_or(ParameterError::FailedDeserialization)?;
+        let (m, rest) = rest
+            .split_first_chunk::<4>()
+            .ok_or(ParameterError::FailedDeserialization)?;
+        let n = u32::from_le_bytes(*n);
+        let m = u32::from_le_bytes(*m);
+        if n > limits.max_n || m > limits.max_m {
+            return Err(ParameterError::FailedDeserialization);
+        }
+
+        // Parse the generators, which must only be encoded if they aren't the defaults
+        let (flag, rest) = rest.split_first().ok_or(ParameterError::FailedDeserialization)?;
+        let mut builder = ParametersBuilder::new().radix(n).digits(m);
+        let rest = match *flag {
+            Self::DEFAULT_GENERATORS => rest,
+            Self::SPECIFIC_GENERATORS => {
+                let (G, rest) = rest
+                    .split_first_chunk::<32>()
+                    .ok_or(ParameterError::FailedDeserialization)?;
+                let (U, rest) = rest
+                    .split_first_chunk::<32>()
+                    .ok_or(ParameterError::FailedDeserialization)?;
+                let G = CompressedRistretto(*G)
+                    .decompress()
+                    .ok_or(ParameterError::FailedDeserialization)?;
+                let U = CompressedRistretto(*U)
+                    .decompress()
+                    .ok_or(ParameterError::FailedDeserialization)?;
+                if (G, U) == Self::default_generators() {
+                    return Err(ParameterError::FailedDeserialization);
+                }
+                builder = builder.generators(&G, &U);
+
+                rest
+            },
+            _ => return Err(ParameterError::FailedDeserialization),
+        };
+
+        // Parse the context, which must use the rest of the encoding
+        let (context_len, context) = rest
+            .split_first_chunk::<8>()
+            .ok_or(ParameterError::FailedDeserialization)?;
+        if usize::try_from(u64::from_le_bytes(*context_len)).ok() != Some(context.len()) {
+            return Err(ParameterError::FailedDeserialization);
+        }
+
+        builder.context(context).build()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use curve25519_dalek::{RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use crate::{
+        parameters::{Dimensions, ParameterError, ParametersBuilder},
+        proof::DeserializeLimits,
+        util::OperationTiming,
+        Parameters,
+    };
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_bounds() {
+        // The base and exponent must be large enough
+        for (n, m) in [(0, 2), (1, 2), (2, 0), (2, 1)] {
+            assert!(matches!(
+                Parameters::new(n, m),
+                Err(ParameterError::DegenerateDimension)
+            ));
+        }
+
+        // Single-digit parameters are rejected for any base, however they are built
+        for n in [2, 3, 4, 16, u32::MAX] {
+            assert!(matches!(
+                Parameters::new(n, 1),
+                Err(ParameterError::DegenerateDimension)
+            ));
+            assert!(matches!(
+                ParametersBuilder::new().radix(n).digits(1).precompute(true).build(),
+                Err(ParameterError::DegenerateDimension)
+            ));
+        }
+
+        // The verification key vector size must not overflow
+        assert_eq!(Parameters::new(2, 31).unwrap().get_N(), 1 << 31);
+        assert_eq!(Parameters::new(2, 32).unwrap().get_N(), 1 << 32);
+        assert_eq!(Parameters::new(2, 63).unwrap().get_N(), 1 << 63);
+        assert!(matches!(Parameters::new(2, 64), Err(ParameterError::Overflow)));
+        assert!(matches!(Parameters::new(u32::MAX, 2), Err(ParameterError::Overflow)));
+        assert!(matches!(
+            Parameters::new(u32::MAX, u32::MAX),
+            Err(ParameterError::Overflow)
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_builder() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // The builder must agree with the positional constructors
+        let params = Parameters::new(2, 4).unwrap();
+        assert!(ParametersBuilder::new().radix(2).digits(4).build().unwrap() == params);
+
+        let G = RistrettoPoint::random(&mut rng);
+        let U = RistrettoPoint::random(&mut rng);
+        assert!(
+            ParametersBuilder::new()
+                .radix(2)
+                .digits(4)
+                .generators(&G, &U)
+                .build()
+                .unwrap() ==
+                Parameters::new_with_generators(2, 4, &G, &U).unwrap()
+        );
+
+        // The base and exponent must be set
+        assert!(matches!(
+            ParametersBuilder::new().radix(2).build(),
+            Err(ParameterError::DegenerateDimension)
+        ));
+        assert!(matches!(
+            ParametersBuilder::new().digits(4).build(),
+            Err(ParameterError::DegenerateDimension)
+        ));
+
+        // A nonempty context changes the parameters, and distinct contexts are distinct
+        let builder = ParametersBuilder::new().radix(2).digits(4);
+        assert!(builder.clone().context(&[]).build().unwrap() == params);
+        let context = builder.clone().context(b"context").build().unwrap();
+        assert!(context != params);
+        assert!(context != builder.clone().context(b"other context").build().unwrap());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_serialization() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let limits = DeserializeLimits::default();
+
+        // Default generators are not encoded
+        let params = Parameters::new(2, 4).unwrap();
+        let serialized = params.to_bytes();
+        assert_eq!(
+            serialized,
+            [&[0u8; 8][..], &[2, 0, 0, 0], &[4, 0, 0, 0], &[0], &[0; 8]].concat()
+        );
+
+        // Round trips reconstruct identical generators and the same hash
+        let G = RistrettoPoint::random(&mut rng);
+        let U = RistrettoPoint::random(&mut rng);
+        let builder = ParametersBuilder::new().radix(2).digits(4);
+        for params in [
+            params,
+            builder.clone().context(b"context").build().unwrap(),
+            builder.clone().generators(&G, &U).context(b"context").build().unwrap(),
+            builder.clone().precompute(true).build().unwrap(),
+        ] {
+            let deserialized = Parameters::from_bytes(&params.to_bytes(), limits).unwrap();
+            assert!(deserialized == params);
+            assert_eq!(deserialized.get_hash(), params.get_hash());
+            assert_eq!(deserialized.base_generator(), params.base_generator());
+            assert_eq!(deserialized.linkability_generator(), params.linkability_generator());
+            assert_eq!(deserialized.commitment_generators(), params.commitment_generators());
+            assert_eq!(deserialized.to_bytes(), params.to_bytes());
+        }
+
+        // Specific generators are encoded
+        let params = builder.clone().generators(&G, &U).build().unwrap();
+        let serialized = params.to_bytes();
+        assert_eq!(serialized.len(), 8 + 4 + 4 + 1 + 64 + 8);
+        assert_eq!(serialized[16], 1);
+
+        // Truncated or extended encodings fail
+        for len in 0..serialized.len() {
+            assert!(matches!(
+                Parameters::from_bytes(&serialized[..len], limits),
+                Err(ParameterError::FailedDeserialization)
+            ));
+        }
+        let mut extended = serialized.clone();
+        extended.push(0);
+        assert!(matches!(
+            Parameters::from_bytes(&extended, limits),
+            Err(ParameterError::FailedDeserialization)
+        ));
+
+        // Unsupported versions and flags fail
+        let mut evil = serialized.clone();
+        evil[0] = 1;
+        assert!(Parameters::from_bytes(&evil, limits).is_err());
+        let mut evil = serialized.clone();
+        evil[16] = 2;
+        assert!(Parameters::from_bytes(&evil, limits).is_err());
+
+        // Default generators must not be encoded explicitly
+        let default = Parameters::new(2, 4).unwrap();
+        let mut evil = serialized.clone();
+        evil[17..49].copy_from_slice(default.base_generator().compress().as_bytes());
+        evil[49..81].copy_from_slice(default.linkability_generator().compress().as_bytes());
+        assert!(Parameters::from_bytes(&evil, limits).is_err());
+
+        // Dimensions are checked against the limits and bounds
+        for limits in [DeserializeLimits { max_n: 1, max_m: 4 }, DeserializeLimits {
+            max_n: 2,
+            max_m: 3,
+        }] {
+            assert!(matches!(
+                Parameters::from_bytes(&serialized, limits),
+                Err(ParameterError::FailedDeserialization)
+            ));
+        }
+        let mut evil = serialized;
+        evil[12] = 1;
+        assert!(matches!(
+            Parameters::from_bytes(&evil, limits),
+            Err(ParameterError::DegenerateDimension)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    fn test_precompute() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Parameters::new(2, 4).unwrap();
+
+        // Precomputation does not affect the parameters or the generator
+        let precomputed = ParametersBuilder::new()
+            .radix(2)
+            .digits(4)
+            .precompute(true)
+            .build()
+            .unwrap();
+        assert!(precomputed.has_precomputed_table());
+        assert!(!params.has_precomputed_table());
+        assert!(precomputed == params);
+        let scalar = Scalar::random(&mut rng);
+        assert_eq!(
+            precomputed.mul_base_generator(&scalar),
+            params.mul_base_generator(&scalar)
+        );
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_verify_cost() {
+        let params = Parameters::new(2, 4).unwrap();
+
+        // A single proof uses `N + 2*m + n*m + 8` terms
+        let cost = params.verify_cost();
+        assert_eq!(cost.msm_terms, 16 + 2 * 4 + 2 * 4 + 8);
+        assert_eq!(cost.fixed_base_terms, 2 * 4 + 3);
+        assert_eq!(cost.msm_terms, cost.fixed_base_terms + cost.variable_base_terms);
+
+        // Each additional proof in a batch adds only its own terms
+        let batch_cost = params.verify_batch_cost(3);
+        assert_eq!(batch_cost.fixed_base_terms, cost.fixed_base_terms);
+        assert_eq!(batch_cost.msm_terms, cost.msm_terms + 2 * (2 * 4 + 5));
+        assert_eq!(params.verify_batch_cost(0).msm_terms, 16 + 2 * 4 + 3);
+
+        // Large batches saturate
+        assert_eq!(params.verify_batch_cost(usize::MAX).msm_terms, u64::MAX);
+    }
+
+    #[test]
+    fn test_recommended() {
+        let params = Parameters::recommended();
+        assert_eq!(params.get_n(), 2);
+        assert_eq!(params.get_m(), 7);
+
+        // The recommended parameters are the defaults, and match the usual constructor
+        assert!(Parameters::default() == params);
+        assert!(Parameters::new(2, 7).unwrap() == params);
+        assert!(Parameters::for_ring_size(params.get_N()).unwrap() == params);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_for_ring_size() {
+        // Small sizes use the smallest parameters
+        for size in [0, 1, 4] {
+            assert_eq!(
+                Parameters::for_ring_size(size).unwrap().get_dimensions(),
+                Dimensions::new(2, 2).unwrap()
+            );
+        }
+
+        // Verification cost is preferred, and then proof size
+        assert_eq!(
+            Parameters::for_ring_size(5).unwrap().get_dimensions(),
+            Dimensions::new(3, 2).unwrap()
+        );
+        assert_eq!(
+            Parameters::for_ring_size(256).unwrap().get_dimensions(),
+            Dimensions::new(4, 4).unwrap()
+        );
+
+        // Every choice is at least the size, and no other dimensions are better
+        for size in 0..300 {
+            let dimensions = Parameters::for_ring_size(size).unwrap().get_dimensions();
+            assert!(dimensions.get_N() >= size);
+            let best = (dimensions.verify_batch_cost(1).msm_terms, dimensions.proof_elements());
+
+            for n in 2..300 {
+                for m in 2..10 {
+                    let Ok(other) = Dimensions::new(n, m) else {
+                        continue;
+                    };
+                    if other.get_N() >= size {
+                        assert!(best <= (other.verify_batch_cost(1).msm_terms, other.proof_elements()));
+                    }
+                }
+            }
+        }
+
+        // Very large sizes are supported, until no size is large enough
+        let N = Parameters::for_ring_size(1 << 62).unwrap().get_N();
+        assert!(N >= 1 << 62);
+        assert!(matches!(
+            Parameters::for_ring_size(u64::MAX),
+            Err(ParameterError::Overflow)
+        ));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_accessors() {
+        let params = Parameters::new(2, 4).unwrap();
+
+        assert_eq!(params.get_G(), params.base_generator());
+        assert_eq!(params.get_U(), params.linkability_generator());
+    }
+
+    #[test]
+    fn test_dimensions() {
+        // The bounds match those for parameters
+        for (n, m) in [(0, 2), (1, 2), (2, 0), (2, 1)] {
+            assert!(matches!(
+                Dimensions::new(n, m),
+                Err(ParameterError::DegenerateDimension)
+            ));
+        }
+        for (n, m) in [(2, 64), (u32::MAX, 2)] {
+            assert!(matches!(Dimensions::new(n, m), Err(ParameterError::Overflow)));
+        }
+
+        // The base and exponent are not interchangeable
+        let dimensions = Dimensions::new(2, 4).unwrap();
+        assert_eq!(dimensions.get_n(), 2);
+        assert_eq!(dimensions.get_m(), 4);
+        assert_eq!(dimensions.get_N(), 16);
+        assert_ne!(Dimensions::new(4, 2).unwrap(), dimensions);
+
+        // Parameters can be built from dimensions, and report them
+        let params = ParametersBuilder::new().dimensions(dimensions).build().unwrap();
+        assert!(params == Parameters::new(2, 4).unwrap());
+        assert_eq!(params.get_dimensions(), dimensions);
+        assert_eq!(params.get_n(), dimensions.get_n());
+        assert_eq!(params.get_m(), dimensions.get_m());
+        assert_eq!(params.get_N(), dimensions.get_N());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_equality() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Parameters::new(2, 4).unwrap();
+
+        // Independently constructed parameters are equal, even with the default generators supplied explicitly
+        assert!(Parameters::new(2, 4).unwrap() == params);
+        assert!(
+            Parameters::new_with_generators(2, 4, params.base_generator(), params.linkability_generator()).unwrap() ==
+                params
+        );
+
+        // Dimensions matter, even for the same input set size
+        assert_eq!(Parameters::new(4, 2).unwrap().get_N(), params.get_N());
+        assert!(Parameters::new(4, 2).unwrap() != params);
+
+        // Each generator matters
+        let G = RistrettoPoint::random(&mut rng);
+        let U = RistrettoPoint::random(&mut rng);
+        assert!(Parameters::new_with_generators(2, 4, &G, params.linkability_generator()).unwrap() != params);
+        assert!(Parameters::new_with_generators(2, 4, params.base_generator(), &U).unwrap() != params);
+    }
+
+    #[test]
+    fn test_verify_matrix_commitment() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Parameters::new(2, 4).unwrap();
+        let blinding = Scalar::random(&mut rng);
+        let matrix = (0..4)
+            .map(|_| (0..2).map(|_| Scalar::random(&mut rng)).collect::<Vec<Scalar>>())
+            .collect::<Vec<Vec<Scalar>>>();
+        let commitment = params
+            .commit_matrix(&matrix, &blinding, OperationTiming::Constant)
+            .unwrap();
+
+        // The opening is valid
+        assert!(params.verify_matrix_commitment(&matrix, &blinding, &commitment));
+
+        // A different masking scalar or matrix is not a valid opening
+        assert!(!params.verify_matrix_commitment(&matrix, &Scalar::random(&mut rng), &commitment));
+        let mut evil_matrix = matrix.clone();
+        evil_matrix[1][0] = Scalar::random(&mut rng);
+        assert!(!params.verify_matrix_commitment(&evil_matrix, &blinding, &commitment));
+
+        // Transposing the matrix is not a valid opening
+        let transposed = (0..2)
+            .map(|i| matrix.iter().map(|row| row[i]).collect::<Vec<Scalar>>())
+            .collect::<Vec<Vec<Scalar>>>();
+        assert!(!params.verify_matrix_commitment(&transposed, &blinding, &commitment));
+
+        // The matrix must have the correct dimensions
+        assert!(!params.verify_matrix_commitment(&matrix[1..], &blinding, &commitment));
+        assert!(!params.verify_matrix_commitment(&[], &blinding, &commitment));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_commit_vector() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Parameters::new(2, 4).unwrap();
+        let blinding = Scalar::random(&mut rng);
+
+        // A full-width commitment matches the matrix commitment
+        let scalars = (0..8).map(|_| Scalar::random(&mut rng)).collect::<Vec<Scalar>>();
+        let matrix = scalars.chunks(2).map(<[Scalar]>::to_vec).collect::<Vec<Vec<Scalar>>>();
+        assert_eq!(
+            params.commit_vector(&scalars, &blinding).unwrap(),
+            params
+                .commit_matrix(&matrix, &blinding, OperationTiming::Variable)
+                .unwrap()
+        );
+
+        // A shorter commitment uses a prefix of the generators
+        let expected = scalars[0] * params.commitment_generators()[0] +
+            scalars[1] * params.commitment_generators()[1] +
+            blinding * params.commitment_blinding_generator();
+        assert_eq!(params.commit_vector(&scalars[..2], &blinding).unwrap(), expected);
+
+        // An empty commitment only uses the masking generator
+        assert_eq!(
+            params.commit_vector(&[], &blinding).unwrap(),
+            blinding * params.commitment_blinding_generator()
+        );
+
+        // There must be enough generators
+        let too_many = (0..9).map(|_| Scalar::random(&mut rng)).collect::<Vec<Scalar>>();
+        assert!(params.commit_vector(&too_many, &blinding).is_err());
+    }
 }
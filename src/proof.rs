@@ -1,12 +1,21 @@
 // Copyright (c) 2024, The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
-use alloc::{vec, vec::Vec};
-use core::{iter::once, slice, slice::ChunksExact};
+use alloc::{collections::VecDeque, sync::Arc, vec, vec::Vec};
+use core::{
+    convert::Infallible,
+    hash::{self, Hash},
+    iter::once,
+    slice,
+};
+#[cfg(feature = "std")]
+use std::io::{self, ErrorKind, Read, Write};
 
+#[cfg(feature = "prover")]
+use curve25519_dalek::traits::MultiscalarMul;
 use curve25519_dalek::{
-    ristretto::CompressedRistretto,
-    traits::{Identity, MultiscalarMul, VartimeMultiscalarMul},
+    ristretto::{CompressedRistretto, VartimeRistrettoPrecomputation},
+    traits::{Identity, VartimeMultiscalarMul, VartimePrecomputedMultiscalarMul},
     RistrettoPoint,
     Scalar,
 };
@@ -15,22 +24,56 @@ use rand_core::CryptoRngCore;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use snafu::prelude::*;
-use subtle::{ConditionallySelectable, ConstantTimeEq};
+#[cfg(feature = "prover")]
+use subtle::ConditionallySelectable;
+use subtle::{Choice, ConstantTimeEq};
+#[cfg(feature = "prover")]
 use zeroize::Zeroizing;
 
+#[cfg(feature = "instrument")]
+use crate::instrument::{self, ProveStats};
 use crate::{
     gray::GrayIterator,
-    transcript::ProofTranscript,
-    util::{delta, NullRng, OperationTiming},
+    msm::vartime_multiscalar_mul_windowed,
+    transcript::{append_message, append_u64, ProofTranscript, VERSION},
+    util::NullRng,
+    FiatShamirTranscript,
+    InputSet,
+    Parameters,
     Statement,
     Transcript,
+};
+#[cfg(feature = "prover")]
+use crate::{
+    util::{delta, EntropyRng, OperationTiming},
     Witness,
 };
 
 // Size of serialized proof elements in bytes
 const SERIALIZED_BYTES: usize = 32;
 
+// Size of the header of a canonical proof encoding in bytes: the version, `n - 1`, and `m`
+#[cfg(feature = "std")]
+const HEADER_BYTES: usize = 16;
+
+// Size of the frame header in bytes: `n`, `m`, and a checksum
+#[cfg(feature = "framed")]
+const FRAME_HEADER_BYTES: usize = 16;
+
 /// A Triptych proof.
+///
+/// Proofs can be composed with other protocols that use a Merlin [`Transcript`]. The prover and verifier each use the
+/// caller's transcript as is, so any data an outer protocol has already appended is bound into the proof's challenge.
+/// Proving and verifying update the transcript in exactly the same way, so after a proof is verified, the verifier's
+/// transcript matches the prover's. This means several sub-proofs can be bound together under one transcript by
+/// proving and verifying them in the same order, and an outer protocol can derive further challenges from it.
+///
+/// Any [`FiatShamirTranscript`] can be used in place of a Merlin [`Transcript`], as long as the prover and verifier use
+/// the same implementation.
+///
+/// Proofs implement [`Hash`] consistently with equality by hashing their canonical serialization, so they can key a
+/// cache of verification results. Proofs contain only public data, so this reveals nothing secret; however, a cached
+/// result only applies to the [`Statement`] and [`Transcript`] used to compute it, so these must be part of the key.
 #[allow(non_snake_case)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -53,15 +96,46 @@ pub enum ProofError {
     /// An invalid parameter was provided.
     #[snafu(display("An invalid parameter was provided"))]
     InvalidParameter,
+    /// The witness signing key does not match the verification key at its index in the input set.
+    #[snafu(display("Witness secret does not match ring key at index {index}"))]
+    WitnessKeyMismatch {
+        /// The witness index.
+        index: u64,
+    },
+    /// The witness is not valid for the statement.
+    ///
+    /// This indicates a wrong secret, such as a signing key that does not produce the statement's linking tag, rather
+    /// than a configuration problem. A signing key that does not match its verification key is reported more precisely
+    /// as [`ProofError::WitnessKeyMismatch`].
+    #[snafu(display("The witness is not valid for the statement"))]
+    InvalidWitness,
     /// A transcript challenge was invalid.
     #[snafu(display("A transcript challenge was invalid"))]
     InvalidChallenge,
     /// Proof deserialization failed.
     #[snafu(display("Proof deserialization failed"))]
     FailedDeserialization,
+    /// A framed or streamed proof encoding was shorter than its dimensions require.
+    #[snafu(display("The proof encoding was truncated"))]
+    Truncated,
+    /// Reading a proof encoding from a source failed for a reason other than the data ending early.
+    #[snafu(display("Reading the proof encoding failed"))]
+    FailedRead,
+    /// The proof uses an unsupported protocol version.
+    #[snafu(display("The proof uses an unsupported protocol version"))]
+    UnsupportedVersion {
+        /// The unsupported version.
+        version: u64,
+    },
     /// Single proof verification failed.
     #[snafu[display("Single proof verification failed")]]
     FailedVerification,
+    /// A verification key required by the verifier was not in the input set.
+    #[snafu(display("Required verification key at index {index} is not in the input set"))]
+    MissingRequiredMember {
+        /// The index of the missing verification key among those required.
+        index: usize,
+    },
     /// Batch proof verification failed.
     #[snafu[display("Batch proof verification failed")]]
     FailedBatchVerification,
@@ -79,7 +153,171 @@ pub enum ProofError {
     },
 }
 
+/// Options for tuning verification performance.
+///
+/// These options never affect the result of verification, only its performance.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct VerifyTuning {
+    /// The window size to use for the final multiscalar multiplication.
+    ///
+    /// If `None`, the curve library chooses its own algorithm using internal heuristics, which is a good choice in
+    /// most cases. Otherwise, a bucket method with the given fixed window size is used; this may be faster for
+    /// particular input set sizes on particular hardware. Larger windows reduce the number of group additions per
+    /// point, but require `2**window` buckets per window, so the best choice grows slowly with the input set size.
+    /// Use the benchmarks to determine a good value. The window size must be between 1 and 16 inclusive.
+    pub msm_window: Option<usize>,
+    /// The number of verification keys to include in each multiscalar multiplication.
+    ///
+    /// If `None`, all verification keys are included in a single multiscalar multiplication, which is fastest.
+    /// Otherwise, the verification keys are processed in chunks of this size, and their scalars are computed only as
+    /// each chunk is needed. This bounds the verifier's memory use beyond the input set itself, regardless of the
+    /// input set size, at some cost in speed. The chunk size must be nonzero.
+    pub chunk_size: Option<usize>,
+}
+
+/// An iterator that lazily verifies a stream of [`Proofs`](`Proof`), created by [`Proof::verify_stream`].
+#[must_use = "proofs are only verified when the stream is consumed, and each result must be checked"]
+pub struct VerifyStream<I> {
+    items: I,
+    batch_size: usize,
+    results: VecDeque<bool>,
+}
+
+impl<I, T> Iterator for VerifyStream<I>
+where
+    I: Iterator<Item = (Statement, Proof, T)>,
+    T: FiatShamirTranscript,
+{
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Verify the next batch if we're out of results
+        if self.results.is_empty() {
+            let mut statements = Vec::with_capacity(self.batch_size);
+            let mut proofs = Vec::with_capacity(self.batch_size);
+            let mut transcripts = Vec::with_capacity(self.batch_size);
+            for (statement, proof, transcript) in self.items.by_ref().take(self.batch_size) {
+                statements.push(statement);
+                proofs.push(proof);
+                transcripts.push(transcript);
+            }
+
+            // If the batch fails, fall back to verifying each proof to find out which are invalid
+            if Proof::verify_batch(&statements, &proofs, &mut transcripts.clone()).is_ok() {
+                self.results.resize(proofs.len(), true);
+            } else {
+                for (statement, proof, transcript) in izip!(statements.iter(), proofs.iter(), transcripts.iter_mut()) {
+                    self.results.push_back(proof.verify(statement, transcript).is_ok());
+                }
+            }
+        }
+
+        self.results.pop_front()
+    }
+}
+
+/// Reusable buffers for verifying [`Proofs`](`Proof`) using [`Proof::verify_in`].
+///
+/// The buffers grow as needed and keep their capacity between verifications, so a single scratch space should be reused
+/// for many verifications. Their contents are only used within a verification, and never affect the result.
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, Default)]
+pub struct VerifyScratch {
+    scalars: Vec<Scalar>,
+    M_scalars: Vec<Scalar>,
+}
+
+impl VerifyScratch {
+    /// Create a new empty [`VerifyScratch`] space.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// Limits on the dimensions of a [`Proof`] being deserialized.
+///
+/// The default limits allow any dimensions; even so, deserialization never allocates more memory than the length of
+/// its input justifies. Tighter limits let you reject proofs that couldn't match any [`Parameters`] you support before
+/// parsing any proof elements.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct DeserializeLimits {
+    /// The largest base `n` to accept.
+    pub max_n: u32,
+    /// The largest exponent `m` to accept.
+    pub max_m: u32,
+}
+
+impl Default for DeserializeLimits {
+    fn default() -> Self {
+        Self {
+            max_n: u32::MAX,
+            max_m: u32::MAX,
+        }
+    }
+}
+
+/// Options for how a batch of proofs is verified.
+///
+/// Both modes accept exactly the same batches, and neither depends on randomness, so they differ only in performance.
+/// The default is [`BatchMode::Full`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum BatchMode {
+    /// All proofs are checked together using a single combined multiscalar multiplication.
+    ///
+    /// This is the fastest way to accept a valid batch, and always does the same amount of work for a given batch
+    /// size, but can't reject an invalid batch any earlier or identify which proof is invalid.
+    #[default]
+    Full,
+    /// Proofs are checked individually in order, stopping at the first invalid proof.
+    ///
+    /// This can reject an invalid batch cheaply and identifies the first invalid proof, but is slower to accept a
+    /// valid batch, and the amount of work depends on which proofs are invalid.
+    FailFast,
+}
+
+/// Options for constant- or variable-time proving.
+///
+/// The default is [`ProvingMode::ConstantTime`], which should be used unless you are certain that the prover's timing
+/// cannot be observed.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ProvingMode {
+    /// The prover makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[default]
+    ConstantTime,
+    /// The prover avoids constant-time operations for efficiency, which may leak the witness through timing.
+    Variable,
+}
+
+#[cfg(feature = "prover")]
+impl From<ProvingMode> for OperationTiming {
+    fn from(mode: ProvingMode) -> Self {
+        match mode {
+            ProvingMode::ConstantTime => OperationTiming::Constant,
+            ProvingMode::Variable => OperationTiming::Variable,
+        }
+    }
+}
+
+/// Information about the ring used by a verified [`Proof`].
+#[allow(non_snake_case)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct RingInfo {
+    /// The base `n`.
+    pub n: u32,
+    /// The exponent `m`.
+    pub m: u32,
+    /// The ring size `N == n**m`.
+    pub N: u64,
+    /// Whether the ring size equals the configured maximum ring size.
+    pub is_max: bool,
+}
+
 impl Proof {
+    /// The minimum number of entropy bytes required by [`Proof::prove_from_entropy`].
+    #[cfg(feature = "prover")]
+    pub const MIN_ENTROPY_BYTES: usize = 32;
+
     /// Generate a Triptych [`Proof`].
     ///
     /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
@@ -91,11 +329,15 @@ impl Proof {
     /// You must also supply a [`Transcript`] `transcript`.
     ///
     /// This function specifically avoids constant-time operations for efficiency.
+    ///
+    /// **Warning**: the running time of this function depends on the witness, so it may leak the signing index and key
+    /// to anyone who can observe timing. Use it only when the prover's timing cannot be observed; otherwise, use
+    /// [`Proof::prove`] or [`Proof::prove_with_rng`].
     #[cfg(feature = "rand")]
-    pub fn prove_vartime(
+    pub fn prove_vartime<T: FiatShamirTranscript>(
         witness: &Witness,
         statement: &Statement,
-        transcript: &mut Transcript,
+        transcript: &mut T,
     ) -> Result<Self, ProofError> {
         use rand_core::OsRng;
 
@@ -111,11 +353,16 @@ impl Proof {
     /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`] `transcript`.
     ///
     /// This function specifically avoids constant-time operations for efficiency.
-    pub fn prove_with_rng_vartime<R: CryptoRngCore>(
+    ///
+    /// **Warning**: the running time of this function depends on the witness, so it may leak the signing index and key
+    /// to anyone who can observe timing. Use it only when the prover's timing cannot be observed; otherwise, use
+    /// [`Proof::prove`] or [`Proof::prove_with_rng`].
+    #[cfg(feature = "prover")]
+    pub fn prove_with_rng_vartime<T: FiatShamirTranscript, R: CryptoRngCore>(
         witness: &Witness,
         statement: &Statement,
         rng: &mut R,
-        transcript: &mut Transcript,
+        transcript: &mut T,
     ) -> Result<Self, ProofError> {
         Self::prove_internal(witness, statement, rng, transcript, OperationTiming::Variable)
     }
@@ -132,7 +379,11 @@ impl Proof {
     ///
     /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
     #[cfg(feature = "rand")]
-    pub fn prove(witness: &Witness, statement: &Statement, transcript: &mut Transcript) -> Result<Self, ProofError> {
+    pub fn prove<T: FiatShamirTranscript>(
+        witness: &Witness,
+        statement: &Statement,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
         use rand_core::OsRng;
 
         Self::prove_internal(witness, statement, &mut OsRng, transcript, OperationTiming::Constant)
@@ -147,22 +398,289 @@ impl Proof {
     /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`] `transcript`.
     ///
     /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
-    pub fn prove_with_rng<R: CryptoRngCore>(
+    #[cfg(feature = "prover")]
+    pub fn prove_with_rng<T: FiatShamirTranscript, R: CryptoRngCore>(
+        witness: &Witness,
+        statement: &Statement,
+        rng: &mut R,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        Self::prove_internal(witness, statement, rng, transcript, OperationTiming::Constant)
+    }
+
+    /// Generate a Triptych [`Proof`], reporting counts of the expensive operations used.
+    ///
+    /// This is otherwise identical to [`Proof::prove_with_rng`], and is useful for comparing the cost of different
+    /// [`Parameters`] without an external profiler. The counts are described by [`ProveStats`].
+    ///
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    #[cfg(feature = "instrument")]
+    pub fn prove_instrumented<T: FiatShamirTranscript, R: CryptoRngCore>(
+        witness: &Witness,
+        statement: &Statement,
+        rng: &mut R,
+        transcript: &mut T,
+    ) -> Result<(Self, ProveStats), ProofError> {
+        // Discard any counts left over from operations outside a proof
+        instrument::take();
+        let proof = Self::prove_with_rng(witness, statement, rng, transcript);
+        let stats = instrument::take();
+
+        Ok((proof?, stats))
+    }
+
+    /// Generate a Triptych [`Proof`] using an explicit [`ProvingMode`] `mode`.
+    ///
+    /// This makes the choice between constant- and variable-time proving explicit at the call site.
+    /// Unless you are certain that the prover's timing cannot be observed, use [`ProvingMode::ConstantTime`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`] `transcript`.
+    #[cfg(feature = "prover")]
+    pub fn prove_with_mode<T: FiatShamirTranscript, R: CryptoRngCore>(
+        witness: &Witness,
+        statement: &Statement,
+        rng: &mut R,
+        transcript: &mut T,
+        mode: ProvingMode,
+    ) -> Result<Self, ProofError> {
+        Self::prove_internal(witness, statement, rng, transcript, mode.into())
+    }
+
+    /// Generate a Triptych [`Proof`] using a caller-supplied `entropy` buffer instead of a random number generator.
+    ///
+    /// This is useful in constrained environments that have no [`CryptoRngCore`] implementation but can gather
+    /// entropy in advance. The buffer is deterministically expanded into all randomness needed by the prover, so it
+    /// must contain at least [`Proof::MIN_ENTROPY_BYTES`] bytes of cryptographically-secure entropy; if it is shorter,
+    /// returns [`ProofError::InvalidParameter`]. As with any prover, the transcript and witness are also bound into the
+    /// prover's randomness, but you should still never reuse an entropy buffer.
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// You must also supply a [`Transcript`] `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "prover")]
+    pub fn prove_from_entropy<T: FiatShamirTranscript>(
+        witness: &Witness,
+        statement: &Statement,
+        entropy: &[u8],
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        if entropy.len() < Self::MIN_ENTROPY_BYTES {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        Self::prove_internal(
+            witness,
+            statement,
+            &mut EntropyRng::new(entropy),
+            transcript,
+            OperationTiming::Constant,
+        )
+    }
+
+    /// Generate a Triptych [`Proof`], binding a message using a custom hash-to-scalar function.
+    ///
+    /// This is useful for compatibility with systems that hash messages externally.
+    /// The `message_hasher` is applied to `message`, and the resulting scalar is bound into the transcript with the
+    /// label `message hash`. The hasher must be collision resistant, since two messages that hash to the same scalar
+    /// are indistinguishable to the proof. The same hasher must be used with [`Proof::verify_with_message_hasher`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// This function provides a cryptographically-secure random number generator for you.
+    ///
+    /// You must also supply a [`Transcript`] `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "rand")]
+    pub fn prove_with_message_hasher<T: FiatShamirTranscript, H: Fn(&[u8]) -> Scalar>(
+        witness: &Witness,
+        statement: &Statement,
+        message: &[u8],
+        message_hasher: H,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        use rand_core::OsRng;
+
+        Self::prove_with_rng_and_message_hasher(witness, statement, message, message_hasher, &mut OsRng, transcript)
+    }
+
+    /// Generate a Triptych [`Proof`], binding a message using a custom hash-to-scalar function.
+    ///
+    /// This is useful for compatibility with systems that hash messages externally.
+    /// The `message_hasher` is applied to `message`, and the resulting scalar is bound into the transcript with the
+    /// label `message hash`. The hasher must be collision resistant, since two messages that hash to the same scalar
+    /// are indistinguishable to the proof. The same hasher must be used with [`Proof::verify_with_message_hasher`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`] `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "prover")]
+    pub fn prove_with_rng_and_message_hasher<T: FiatShamirTranscript, R: CryptoRngCore, H: Fn(&[u8]) -> Scalar>(
+        witness: &Witness,
+        statement: &Statement,
+        message: &[u8],
+        message_hasher: H,
+        rng: &mut R,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        Self::bind_message_hash(transcript, message, message_hasher);
+
+        Self::prove_internal(witness, statement, rng, transcript, OperationTiming::Constant)
+    }
+
+    /// Generate a Triptych [`Proof`], binding a precomputed message digest.
+    ///
+    /// This is useful for large messages, which would otherwise need to be passed through the transcript by both the
+    /// prover and verifier. The caller computes `message_digest` from the message, and it is bound into the transcript
+    /// with the label `message digest`. The prover and verifier must agree on how the digest is computed, and the hash
+    /// function must be collision resistant, since two messages with the same digest are indistinguishable to the
+    /// proof. Use [`Proof::verify_with_message_digest`] to verify the proof.
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// This function provides a cryptographically-secure random number generator for you.
+    ///
+    /// You must also supply a [`Transcript`] `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "rand")]
+    pub fn prove_with_message_digest<T: FiatShamirTranscript>(
+        witness: &Witness,
+        statement: &Statement,
+        message_digest: &[u8; 32],
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        use rand_core::OsRng;
+
+        Self::prove_with_rng_and_message_digest(witness, statement, message_digest, &mut OsRng, transcript)
+    }
+
+    /// Generate a Triptych [`Proof`], binding a precomputed message digest.
+    ///
+    /// This is useful for large messages, which would otherwise need to be passed through the transcript by both the
+    /// prover and verifier. The caller computes `message_digest` from the message, and it is bound into the transcript
+    /// with the label `message digest`. The prover and verifier must agree on how the digest is computed, and the hash
+    /// function must be collision resistant, since two messages with the same digest are indistinguishable to the
+    /// proof. Use [`Proof::verify_with_message_digest`] to verify the proof.
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`] `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "prover")]
+    pub fn prove_with_rng_and_message_digest<T: FiatShamirTranscript, R: CryptoRngCore>(
+        witness: &Witness,
+        statement: &Statement,
+        message_digest: &[u8; 32],
+        rng: &mut R,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        append_message(transcript, b"message digest", message_digest);
+
+        Self::prove_internal(witness, statement, rng, transcript, OperationTiming::Constant)
+    }
+
+    /// Generate a Triptych [`Proof`], binding a sequence of labeled auxiliary public data.
+    ///
+    /// This is useful for binding several structured public inputs, like amounts or commitments, without needing to
+    /// combine them into a single message. Each entry of `aux` is a `(label, data)` pair. The number of entries and
+    /// each label and data are bound into the transcript in order, with lengths, so the binding is unambiguous. The
+    /// same entries must be supplied in the same order to [`Proof::verify_with_aux`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// This function provides a cryptographically-secure random number generator for you.
+    ///
+    /// You must also supply a [`Transcript`] `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "rand")]
+    pub fn prove_with_aux<T: FiatShamirTranscript>(
+        witness: &Witness,
+        statement: &Statement,
+        aux: &[(&[u8], &[u8])],
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        use rand_core::OsRng;
+
+        Self::prove_with_rng_and_aux(witness, statement, aux, &mut OsRng, transcript)
+    }
+
+    /// Generate a Triptych [`Proof`], binding a sequence of labeled auxiliary public data.
+    ///
+    /// This is useful for binding several structured public inputs, like amounts or commitments, without needing to
+    /// combine them into a single message. Each entry of `aux` is a `(label, data)` pair. The number of entries and
+    /// each label and data are bound into the transcript in order, with lengths, so the binding is unambiguous. The
+    /// same entries must be supplied in the same order to [`Proof::verify_with_aux`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and corresponding [`Statement`] `statement`.
+    /// If the witness and statement do not share the same parameters, or if the statement is invalid for the witness,
+    /// returns a [`ProofError`].
+    ///
+    /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`] `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "prover")]
+    pub fn prove_with_rng_and_aux<T: FiatShamirTranscript, R: CryptoRngCore>(
         witness: &Witness,
         statement: &Statement,
+        aux: &[(&[u8], &[u8])],
         rng: &mut R,
-        transcript: &mut Transcript,
+        transcript: &mut T,
     ) -> Result<Self, ProofError> {
+        Self::bind_aux(transcript, aux);
+
         Self::prove_internal(witness, statement, rng, transcript, OperationTiming::Constant)
     }
 
+    /// Bind a sequence of labeled auxiliary data into a transcript.
+    fn bind_aux<T: FiatShamirTranscript>(transcript: &mut T, aux: &[(&[u8], &[u8])]) {
+        append_u64(transcript, b"aux count", aux.len() as u64);
+        for (label, data) in aux {
+            append_message(transcript, b"aux label", label);
+            append_message(transcript, b"aux data", data);
+        }
+    }
+
+    /// Bind a message into a transcript using a custom hash-to-scalar function.
+    fn bind_message_hash<T: FiatShamirTranscript, H: Fn(&[u8]) -> Scalar>(
+        transcript: &mut T,
+        message: &[u8],
+        message_hasher: H,
+    ) {
+        append_message(transcript, b"message hash", message_hasher(message).as_bytes());
+    }
+
     /// The actual prover functionality.
+    #[cfg(feature = "prover")]
     #[allow(clippy::too_many_lines, non_snake_case)]
-    fn prove_internal<R: CryptoRngCore>(
+    fn prove_internal<T: FiatShamirTranscript, R: CryptoRngCore>(
         witness: &Witness,
         statement: &Statement,
         rng: &mut R,
-        transcript: &mut Transcript,
+        transcript: &mut T,
         timing: OperationTiming,
     ) -> Result<Self, ProofError> {
         // Check that the witness and statement have identical parameters
@@ -173,176 +691,101 @@ impl Proof {
         // Extract values for convenience
         let r = witness.get_r();
         let l = witness.get_l();
-        let M = statement.get_input_set().get_keys();
+        let M = statement.get_input_set().keys();
         let params = statement.get_params();
         let J = statement.get_J();
 
+        // Check that the input set size matches the parameters
+        if usize::try_from(params.get_N()).ok() != Some(M.len()) {
+            return Err(ProofError::InvalidParameter);
+        }
+
         // Check that the witness is valid against the statement, in constant time if needed
         let mut M_l = RistrettoPoint::identity();
 
         match timing {
             OperationTiming::Constant => {
-                for (index, item) in M.iter().enumerate() {
-                    M_l.conditional_assign(item, index.ct_eq(&(l as usize)));
+                for (index, item) in (0u64..).zip(M.clone()) {
+                    M_l.conditional_assign(item, index.ct_eq(&l));
                 }
             },
             OperationTiming::Variable => {
-                M_l = M[l as usize];
+                M_l = *M
+                    .clone()
+                    .nth(usize::try_from(l).map_err(|_| ProofError::InvalidWitness)?)
+                    .ok_or(ProofError::InvalidWitness)?;
             },
         }
 
-        if M_l != r * params.get_G() {
-            return Err(ProofError::InvalidParameter);
+        #[cfg(feature = "instrument")]
+        crate::instrument::record_scalar_muls(2);
+        if M_l != params.mul_base_generator(r) {
+            return Err(ProofError::WitnessKeyMismatch { index: l });
         }
-        if &(r * J) != params.get_U() {
-            return Err(ProofError::InvalidParameter);
+        if &(r * J) != params.linkability_generator() {
+            return Err(ProofError::InvalidWitness);
         }
 
         // Set up the transcript
         let mut transcript = ProofTranscript::new(transcript, statement, rng, Some(witness));
 
-        // Compute the `A` matrix commitment
-        let r_A = Scalar::random(transcript.as_mut_rng());
-        let mut a = (0..params.get_m())
-            .map(|_| {
-                (0..params.get_n())
-                    .map(|_| Scalar::random(transcript.as_mut_rng()))
-                    .collect::<Vec<Scalar>>()
-            })
-            .collect::<Vec<Vec<Scalar>>>();
-        for j in (0..params.get_m()).map(|j| j as usize) {
-            a[j][0] = -a[j][1..].iter().sum::<Scalar>();
-        }
-        let A = params
-            .commit_matrix(&a, &r_A, timing)
-            .map_err(|_| ProofError::InvalidParameter)?;
-
-        // Compute the `B` matrix commitment
-        let r_B = Scalar::random(transcript.as_mut_rng());
-        let l_decomposed = match timing {
-            OperationTiming::Constant => {
-                GrayIterator::decompose(params.get_n(), params.get_m(), l).ok_or(ProofError::InvalidParameter)?
-            },
-            OperationTiming::Variable => GrayIterator::decompose_vartime(params.get_n(), params.get_m(), l)
-                .ok_or(ProofError::InvalidParameter)?,
-        };
-        let sigma = (0..params.get_m())
-            .map(|j| {
-                (0..params.get_n())
-                    .map(|i| delta(l_decomposed[j as usize], i, timing))
-                    .collect::<Vec<Scalar>>()
-            })
-            .collect::<Vec<Vec<Scalar>>>();
-        let B = params
-            .commit_matrix(&sigma, &r_B, timing)
-            .map_err(|_| ProofError::InvalidParameter)?;
-
-        // Compute the `C` matrix commitment
-        let two = Scalar::from(2u32);
-        let r_C = Scalar::random(transcript.as_mut_rng());
-        let a_sigma = (0..params.get_m())
-            .map(|j| {
-                (0..params.get_n())
-                    .map(|i| a[j as usize][i as usize] * (Scalar::ONE - two * sigma[j as usize][i as usize]))
-                    .collect::<Vec<Scalar>>()
-            })
-            .collect::<Vec<Vec<Scalar>>>();
-        let C = params
-            .commit_matrix(&a_sigma, &r_C, timing)
-            .map_err(|_| ProofError::InvalidParameter)?;
-
-        // Compute the `D` matrix commitment
-        let r_D = Scalar::random(transcript.as_mut_rng());
-        let a_square = (0..params.get_m())
-            .map(|j| {
-                (0..params.get_n())
-                    .map(|i| -a[j as usize][i as usize] * a[j as usize][i as usize])
-                    .collect::<Vec<Scalar>>()
-            })
-            .collect::<Vec<Vec<Scalar>>>();
-        let D = params
-            .commit_matrix(&a_square, &r_D, timing)
-            .map_err(|_| ProofError::InvalidParameter)?;
-
-        // Random masks
-        let rho = Zeroizing::new(
-            (0..params.get_m())
-                .map(|_| Scalar::random(transcript.as_mut_rng()))
-                .collect::<Vec<Scalar>>(),
-        );
-
-        // Compute `p` polynomial vector coefficients using repeated convolution
-        let mut p = Vec::<Vec<Scalar>>::with_capacity(params.get_N() as usize);
-        let mut k_decomposed = vec![0; params.get_m() as usize];
-        for (gray_index, _, gray_new) in
-            GrayIterator::new(params.get_n(), params.get_m()).ok_or(ProofError::InvalidParameter)?
-        {
-            k_decomposed[gray_index] = gray_new;
-
-            // Set the initial coefficients using the first degree-one polynomial (`j = 0`)
-            let mut coefficients = Vec::new();
-            coefficients.resize(
-                (params.get_m() as usize)
-                    .checked_add(1)
-                    .ok_or(ProofError::InvalidParameter)?,
-                Scalar::ZERO,
-            );
-            coefficients[0] = a[0][k_decomposed[0] as usize];
-            coefficients[1] = sigma[0][k_decomposed[0] as usize];
-
-            // Use convolution against each remaining degree-one polynomial
-            for j in 1..params.get_m() {
-                // For the degree-zero portion, simply multiply each coefficient accordingly
-                let degree_0_portion = coefficients
-                    .iter()
-                    .map(|c| a[j as usize][k_decomposed[j as usize] as usize] * c)
-                    .collect::<Vec<Scalar>>();
-
-                // For the degree-one portion, we also need to increase each exponent by one
-                // Rotating the coefficients is fine here since the highest is always zero!
-                let mut shifted_coefficients = coefficients.clone();
-                shifted_coefficients.rotate_right(1);
-                let degree_1_portion = shifted_coefficients
-                    .iter()
-                    .map(|c| sigma[j as usize][k_decomposed[j as usize] as usize] * c)
-                    .collect::<Vec<Scalar>>();
-
-                coefficients = degree_0_portion
-                    .iter()
-                    .zip(degree_1_portion.iter())
-                    .map(|(x, y)| x + y)
-                    .collect::<Vec<Scalar>>();
-            }
-
-            p.push(coefficients);
-        }
-
-        // Compute `X` vector
-        let X = rho
-            .iter()
-            .enumerate()
-            .map(|(j, rho)| {
-                let X_points = M.iter().chain(once(params.get_G()));
-                let X_scalars = p.iter().map(|p| &p[j]).chain(once(rho));
-
-                match timing {
-                    OperationTiming::Constant => RistrettoPoint::multiscalar_mul(X_scalars, X_points),
-                    OperationTiming::Variable => RistrettoPoint::vartime_multiscalar_mul(X_scalars, X_points),
-                }
-            })
-            .collect::<Vec<RistrettoPoint>>();
-
-        // Compute `Y` vector
-        let Y = rho.iter().map(|rho| rho * J).collect::<Vec<RistrettoPoint>>();
-
-        // Run the Fiat-Shamir commitment phase to get the challenge powers
-        let xi_powers = transcript.commit(params, &A, &B, &C, &D, &X, &Y)?;
-
-        // Compute the `f` matrix
-        let f = (0..params.get_m())
-            .map(|j| {
-                (1..params.get_n())
-                    .map(|i| sigma[j as usize][i as usize] * xi_powers[1] + a[j as usize][i as usize])
+        // Commit to the index decomposition
+        let DecompositionCommitments {
+            A,
+            B,
+            C,
+            D,
+            a,
+            sigma,
+            r_A,
+            r_B,
+            r_C,
+            r_D,
+        } = DecompositionCommitments::new(params, l, transcript.as_mut_rng(), timing)?;
+
+        // Random masks
+        let rho = Zeroizing::new(
+            (0..params.get_m())
+                .map(|_| Scalar::random(transcript.as_mut_rng()))
+                .collect::<Vec<Scalar>>(),
+        );
+        #[cfg(feature = "instrument")]
+        crate::instrument::record_random_scalars(rho.len());
+
+        // Compute `p` polynomial vector coefficients
+        let p = compute_p(params, &a, &sigma)?;
+
+        // Compute `X` vector
+        let X = rho
+            .iter()
+            .enumerate()
+            .map(|(j, rho)| {
+                let X_points = M.clone().chain(once(params.base_generator()));
+                let X_scalars = p.iter().map(|p| &p[j]).chain(once(rho));
+                #[cfg(feature = "instrument")]
+                crate::instrument::record_multiscalar_mul(M.len().saturating_add(1));
+
+                match timing {
+                    OperationTiming::Constant => RistrettoPoint::multiscalar_mul(X_scalars, X_points),
+                    OperationTiming::Variable => RistrettoPoint::vartime_multiscalar_mul(X_scalars, X_points),
+                }
+            })
+            .collect::<Vec<RistrettoPoint>>();
+
+        // Compute `Y` vector
+        let Y = rho.iter().map(|rho| rho * J).collect::<Vec<RistrettoPoint>>();
+        #[cfg(feature = "instrument")]
+        crate::instrument::record_scalar_muls(Y.len());
+
+        // Run the Fiat-Shamir commitment phase to get the challenge powers
+        let xi_powers = transcript.commit(params, &A, &B, &C, &D, &X, &Y)?;
+
+        // Compute the `f` matrix, which uses only scalar arithmetic on the `B` matrix and so doesn't depend on the
+        // index in any other way
+        let f = (0..params.get_m())
+            .map(|j| {
+                (1..params.get_n())
+                    .map(|i| sigma[j as usize][i as usize] * xi_powers[1] + a[j as usize][i as usize])
                     .collect::<Vec<Scalar>>()
             })
             .collect::<Vec<Vec<Scalar>>>();
@@ -356,6 +799,9 @@ impl Proof {
                 .map(|(rho, xi_power)| rho * xi_power)
                 .sum::<Scalar>();
 
+        // Bind the responses so the transcript ends in the same state as the verifier's
+        transcript.bind_response(&f, &z_A, &z_C, &z);
+
         Ok(Self {
             A,
             B,
@@ -375,15 +821,235 @@ impl Proof {
     /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
     ///
     /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
-    pub fn verify(&self, statement: &Statement, transcript: &mut Transcript) -> Result<(), ProofError> {
+    ///
+    /// Verification is deterministic. It combines several equations using weights that are derived from the
+    /// `transcript` and the proof, rather than drawn from a random number generator, so a given proof, statement, and
+    /// transcript produce the same result on any machine. This makes it safe to replay a verification failure when
+    /// debugging.
+    pub fn verify<T: FiatShamirTranscript>(&self, statement: &Statement, transcript: &mut T) -> Result<(), ProofError> {
+        self.verify_with_tuning(statement, transcript, VerifyTuning::default())
+    }
+
+    /// Verify a Triptych [`Proof`], returning the statement's linking tag on success.
+    ///
+    /// This is a convenience for the common pattern of verifying a proof and then recording its linking tag, such as
+    /// for double-spend detection. The linking tag is returned only if verification succeeds.
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_and_tag<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+    ) -> Result<RistrettoPoint, ProofError> {
+        self.verify(statement, transcript)?;
+
+        Ok(*statement.get_J())
+    }
+
+    /// Verify a Triptych [`Proof`] using verifier weights that also depend on a shared nonce.
+    ///
+    /// Verification combines several equations using weights that are derived deterministically from the transcript.
+    /// This function additionally binds `shared_nonce` into the weight derivation, which is useful when several
+    /// verifiers must agree on the weights without trusting any single party's random number generator: all verifiers
+    /// using the same nonce derive the same weights and reach the same result.
+    ///
+    /// The nonce should be generated jointly, for example using a coin-flipping protocol, and must be unpredictable to
+    /// the prover at the time the proof was generated.
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_with_shared_nonce<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+        shared_nonce: &[u8],
+    ) -> Result<(), ProofError> {
         // Verify as a trivial batch
-        Self::verify_batch(
+        Self::verify_batch_internal(
+            slice::from_ref(statement),
+            slice::from_ref(self),
+            slice::from_mut(transcript),
+            VerifyTuning::default(),
+            Some(shared_nonce),
+            None,
+            None,
+        )
+    }
+
+    /// Verify a Triptych [`Proof`], additionally requiring that the input set contain certain verification keys.
+    ///
+    /// This is useful for enforcing a policy on input set composition, such as requiring that keys from trusted
+    /// sources always be present. If any key in `required` is not in the statement's input set, returns
+    /// [`ProofError::MissingRequiredMember`] with the index of the first such key, without verifying the proof.
+    ///
+    /// Checking membership is linear in the input set size for each required key.
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    #[allow(non_snake_case)]
+    pub fn verify_requiring_members<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        required: &[RistrettoPoint],
+        transcript: &mut T,
+    ) -> Result<(), ProofError> {
+        // Check that all required keys are present before doing any expensive verification
+        let M = statement.get_input_set();
+        if let Some(index) = required.iter().position(|key| !M.keys().any(|item| item == key)) {
+            return Err(ProofError::MissingRequiredMember { index });
+        }
+
+        self.verify(statement, transcript)
+    }
+
+    /// Verify a Triptych [`Proof`] that binds a sequence of labeled auxiliary public data.
+    ///
+    /// Verification requires that the `statement`, `aux` entries, and `transcript` match those used when the proof was
+    /// generated, with the entries in the same order.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_with_aux<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        aux: &[(&[u8], &[u8])],
+        transcript: &mut T,
+    ) -> Result<(), ProofError> {
+        Self::bind_aux(transcript, aux);
+
+        self.verify(statement, transcript)
+    }
+
+    /// Verify a Triptych [`Proof`] whose message was bound using a custom hash-to-scalar function.
+    ///
+    /// Verification requires that the `statement`, `message`, and `transcript` match those used when the proof was
+    /// generated, and that `message_hasher` is the same function used by the prover.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_with_message_hasher<T: FiatShamirTranscript, H: Fn(&[u8]) -> Scalar>(
+        &self,
+        statement: &Statement,
+        message: &[u8],
+        message_hasher: H,
+        transcript: &mut T,
+    ) -> Result<(), ProofError> {
+        Self::bind_message_hash(transcript, message, message_hasher);
+
+        self.verify(statement, transcript)
+    }
+
+    /// Verify a Triptych [`Proof`] whose message was bound using a precomputed digest.
+    ///
+    /// Verification requires that the `statement`, `message_digest`, and `transcript` match those used when the proof
+    /// was generated, and that the digest was computed in the same way as the prover's.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_with_message_digest<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        message_digest: &[u8; 32],
+        transcript: &mut T,
+    ) -> Result<(), ProofError> {
+        append_message(transcript, b"message digest", message_digest);
+
+        self.verify(statement, transcript)
+    }
+
+    /// Verify a Triptych [`Proof`], returning the verification result alongside [`RingInfo`] about its ring.
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    /// The ring information is reported regardless of the verification result, and indicates whether the ring size is
+    /// equal to the configured maximum ring size `max_N`.
+    ///
+    /// If the verification requirement is not met, or if the proof is invalid, the result is a [`ProofError`].
+    #[allow(non_snake_case)]
+    pub fn verify_with_ring_info<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+        max_N: u64,
+    ) -> (Result<(), ProofError>, RingInfo) {
+        let params = statement.get_params();
+        let ring_info = RingInfo {
+            n: params.get_n(),
+            m: params.get_m(),
+            N: params.get_N(),
+            is_max: params.get_N() == max_N,
+        };
+
+        (self.verify(statement, transcript), ring_info)
+    }
+
+    /// Verify a Triptych [`Proof`] using [`VerifyTuning`] options.
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    ///
+    /// If this requirement is not met, if the `tuning` options are invalid, or if the proof is invalid, returns a
+    /// [`ProofError`].
+    pub fn verify_with_tuning<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+        tuning: VerifyTuning,
+    ) -> Result<(), ProofError> {
+        // Verify as a trivial batch
+        Self::verify_batch_with_tuning(
+            slice::from_ref(statement),
+            slice::from_ref(self),
+            slice::from_mut(transcript),
+            tuning,
+        )
+    }
+
+    /// Verify a Triptych [`Proof`] using a reusable [`VerifyScratch`] space.
+    ///
+    /// This is equivalent to [`Proof::verify`], but reuses the buffers in `scratch` rather than allocating new ones.
+    /// Reusing the same scratch space across many verifications reduces allocations.
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_in<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+        scratch: &mut VerifyScratch,
+    ) -> Result<(), ProofError> {
+        // Verify as a trivial batch
+        Self::verify_batch_internal(
             slice::from_ref(statement),
             slice::from_ref(self),
             slice::from_mut(transcript),
+            VerifyTuning::default(),
+            None,
+            None,
+            Some(scratch),
         )
     }
 
+    /// Verify a Triptych [`Proof`], processing verification keys in chunks of size `chunk_size` to bound memory use.
+    ///
+    /// This is equivalent to [`Proof::verify_with_tuning`] with [`VerifyTuning::chunk_size`] set, and gives the same
+    /// result as [`Proof::verify`] regardless of the chunk size.
+    ///
+    /// Verification requires that the `statement` and `transcript` match those used when the proof was generated.
+    ///
+    /// If this requirement is not met, if `chunk_size` is zero, or if the proof is invalid, returns a [`ProofError`].
+    pub fn verify_chunked<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+        chunk_size: usize,
+    ) -> Result<(), ProofError> {
+        self.verify_with_tuning(statement, transcript, VerifyTuning {
+            chunk_size: Some(chunk_size),
+            ..VerifyTuning::default()
+        })
+    }
+
     /// Verify a batch of Triptych [`Proofs`](`Proof`), identifying a single invalid proof if verification fails.
     ///
     /// An empty batch is valid by definition.
@@ -397,11 +1063,14 @@ impl Proof {
     /// If any of the above requirements are not met, returns a [`ProofError`].
     /// If any batch in the proof is invalid, returns a [`ProofError`] containing the index of an invalid proof.
     /// It is not guaranteed that this index represents the _only_ invalid proof in the batch.
-    pub fn verify_batch_with_single_blame(
+    pub fn verify_batch_with_single_blame<T: FiatShamirTranscript>(
         statements: &[Statement],
         proofs: &[Proof],
-        transcripts: &mut [Transcript],
+        transcripts: &mut [T],
     ) -> Result<(), ProofError> {
+        // An inconsistent batch can't be searched, and no proof is to blame
+        Self::check_batch(statements, proofs, transcripts)?;
+
         // Try to verify the full batch
         if Self::verify_batch(statements, proofs, &mut transcripts.to_vec()).is_ok() {
             return Ok(());
@@ -422,7 +1091,7 @@ impl Proof {
 
             #[allow(clippy::arithmetic_side_effects)]
             // This cannot underflow since `left < right`
-            let mid = if (right - left) % 2 == 0 {
+            let mid = if (right - left).is_multiple_of(2) {
                 average
             } else {
                 average
@@ -479,11 +1148,14 @@ impl Proof {
     ///
     /// If any of the above requirements are not met, returns a [`ProofError`].
     /// If any batch in the proof is invalid, returns a [`ProofError`] containing the indexes of all invalid proofs.
-    pub fn verify_batch_with_full_blame(
+    pub fn verify_batch_with_full_blame<T: FiatShamirTranscript>(
         statements: &[Statement],
         proofs: &[Proof],
-        transcripts: &mut [Transcript],
+        transcripts: &mut [T],
     ) -> Result<(), ProofError> {
+        // An inconsistent batch can't be checked proof by proof, and no proof is to blame
+        Self::check_batch(statements, proofs, transcripts)?;
+
         // Try to verify the full batch
         if Self::verify_batch(statements, proofs, &mut transcripts.to_vec()).is_ok() {
             return Ok(());
@@ -502,86 +1174,264 @@ impl Proof {
 
     /// Verify a batch of Triptych [`Proofs`](`Proof`).
     ///
+    /// An empty batch is valid by definition, and is accepted without doing any group operations.
+    ///
+    /// Verification requires that the `statements` and `transcripts` match those used when the `proofs` were generated,
+    /// and that they share a common [`InputSet`](`crate::statement::InputSet`) and
+    /// [`Parameters`](`crate::parameters::Parameters`). If the numbers of statements, proofs, and transcripts differ,
+    /// returns [`ProofError::InvalidParameter`]; this is also the case for the other batch verification functions,
+    /// which never attribute such an inconsistency to a particular proof.
+    ///
+    /// Each proof has its own transcript, so any message bound into a proof's transcript when it was generated must be
+    /// bound into the corresponding verifier transcript in the same way. Proofs with different messages, or with no
+    /// message, can be verified together in the same batch.
+    ///
+    /// If any of the above requirements are not met, or if any proof is invalid, returns a [`ProofError`].
+    pub fn verify_batch<T: FiatShamirTranscript>(
+        statements: &[Statement],
+        proofs: &[Proof],
+        transcripts: &mut [T],
+    ) -> Result<(), ProofError> {
+        Self::verify_batch_with_tuning(statements, proofs, transcripts, VerifyTuning::default())
+    }
+
+    /// Verify a batch of Triptych [`Proofs`](`Proof`) using a [`BatchMode`].
+    ///
     /// An empty batch is valid by definition.
     ///
+    /// In [`BatchMode::Full`] mode, this is equivalent to [`Proof::verify_batch`].
+    /// In [`BatchMode::FailFast`] mode, proofs are verified individually in order, stopping at the first invalid proof.
+    ///
     /// Verification requires that the `statements` and `transcripts` match those used when the `proofs` were generated,
     /// and that they share a common [`InputSet`](`crate::statement::InputSet`) and
     /// [`Parameters`](`crate::parameters::Parameters`).
     ///
-    /// If any of the above requirements are not met, or if any proof is invalid, returns a [`ProofError`].
-    #[allow(clippy::too_many_lines, non_snake_case)]
-    pub fn verify_batch(
+    /// If any of the above requirements are not met, returns a [`ProofError`].
+    /// If any proof is invalid in [`BatchMode::Full`] mode, returns a [`ProofError`].
+    /// If any proof is invalid in [`BatchMode::FailFast`] mode, returns a [`ProofError`] containing the index of the
+    /// first invalid proof.
+    pub fn verify_batch_with_mode<T: FiatShamirTranscript>(
         statements: &[Statement],
         proofs: &[Proof],
-        transcripts: &mut [Transcript],
+        transcripts: &mut [T],
+        mode: BatchMode,
     ) -> Result<(), ProofError> {
-        // Check that we have the same number of statements, proofs, and transcripts
-        if statements.len() != proofs.len() {
-            return Err(ProofError::InvalidParameter);
-        }
-        if statements.len() != transcripts.len() {
-            return Err(ProofError::InvalidParameter);
+        match mode {
+            BatchMode::Full => Self::verify_batch(statements, proofs, transcripts),
+            BatchMode::FailFast => {
+                Self::check_batch(statements, proofs, transcripts)?;
+
+                for (index, (statement, proof, transcript)) in
+                    izip!(statements, proofs, transcripts.iter_mut()).enumerate()
+                {
+                    if proof.verify(statement, transcript).is_err() {
+                        return Err(ProofError::FailedBatchVerificationWithSingleBlame { index: Some(index) });
+                    }
+                }
+
+                Ok(())
+            },
         }
+    }
 
-        // An empty batch is considered trivially valid
-        let first_statement = match statements.first() {
-            Some(statement) => statement,
-            None => return Ok(()),
-        };
+    /// Verify a batch of Triptych [`Proofs`](`Proof`), first checking a random sample of them individually.
+    ///
+    /// An empty batch is valid by definition.
+    ///
+    /// Up to `sample_size` proofs are chosen uniformly at random using `rng` and verified individually, stopping at the
+    /// first invalid one. The remaining proofs are then verified together as in [`Proof::verify_batch`]. Every proof is
+    /// verified exactly once, so this accepts exactly the same batches as [`Proof::verify_batch`], and randomness only
+    /// affects how quickly an invalid batch is rejected. Sampling more proofs makes it more likely that a batch with
+    /// a few invalid proofs is rejected early, at the cost of accepting a valid batch more slowly.
+    ///
+    /// Verification requires that the `statements` and `transcripts` match those used when the `proofs` were generated,
+    /// and that they share a common [`InputSet`](`crate::statement::InputSet`) and
+    /// [`Parameters`](`crate::parameters::Parameters`).
+    ///
+    /// If any of the above requirements are not met, returns a [`ProofError`].
+    /// If a sampled proof is invalid, returns a [`ProofError`] containing its index.
+    /// If any other proof is invalid, returns a [`ProofError`].
+    pub fn verify_batch_sampled<T: FiatShamirTranscript, R: CryptoRngCore>(
+        statements: &[Statement],
+        proofs: &[Proof],
+        transcripts: &mut [T],
+        sample_size: usize,
+        rng: &mut R,
+    ) -> Result<(), ProofError> {
+        Self::check_batch(statements, proofs, transcripts)?;
+
+        // Choose the sample using a partial Fisher-Yates shuffle; the modular bias is negligible for any batch size
+        let mut indexes = (0..proofs.len()).collect::<Vec<usize>>();
+        let sample_size = sample_size.min(proofs.len());
+        for i in 0..sample_size {
+            let remaining = u64::try_from(proofs.len().saturating_sub(i)).map_err(|_| ProofError::InvalidParameter)?;
+            let offset = usize::try_from(
+                rng.next_u64()
+                    .checked_rem(remaining)
+                    .ok_or(ProofError::InvalidParameter)?,
+            )
+            .map_err(|_| ProofError::InvalidParameter)?;
+            indexes.swap(i, i.saturating_add(offset));
+        }
+        let (sample, rest) = indexes.split_at(sample_size);
 
-        // Each statement must use the same input set (checked using the hash for efficiency)
-        if !statements.iter().map(|s| s.get_input_set().get_hash()).all_equal() {
-            return Err(ProofError::InvalidParameter);
+        // Verify the sample individually
+        for &index in sample {
+            if proofs[index]
+                .verify(&statements[index], &mut transcripts[index])
+                .is_err()
+            {
+                return Err(ProofError::FailedBatchVerificationWithSingleBlame { index: Some(index) });
+            }
         }
 
-        // Each statement must use the same parameters (checked using the hash for efficiency)
-        if !statements.iter().map(|s| s.get_params().get_hash()).all_equal() {
-            return Err(ProofError::InvalidParameter);
+        // Verify the rest together, keeping their transcripts updated
+        let mut rest_transcripts = rest.iter().map(|&index| transcripts[index].clone()).collect::<Vec<T>>();
+        Self::verify_batch(
+            &rest
+                .iter()
+                .map(|&index| statements[index].clone())
+                .collect::<Vec<Statement>>(),
+            &rest.iter().map(|&index| proofs[index].clone()).collect::<Vec<Proof>>(),
+            &mut rest_transcripts,
+        )?;
+        for (&index, transcript) in rest.iter().zip(rest_transcripts) {
+            transcripts[index] = transcript;
         }
 
-        // Extract common values for convenience
-        let M = first_statement.get_input_set().get_keys();
-        let params = first_statement.get_params();
+        Ok(())
+    }
 
-        // Check that all proof semantics are valid for the statement
-        for proof in proofs {
-            if proof.X.len() != params.get_m() as usize {
-                return Err(ProofError::InvalidParameter);
-            }
-            if proof.Y.len() != params.get_m() as usize {
-                return Err(ProofError::InvalidParameter);
-            }
-            if proof.f.len() != params.get_m() as usize {
-                return Err(ProofError::InvalidParameter);
-            }
-            for f_row in &proof.f {
-                if f_row.len() != params.get_n().checked_sub(1).ok_or(ProofError::InvalidParameter)? as usize {
-                    return Err(ProofError::InvalidParameter);
-                }
-            }
+    /// Lazily verify a stream of Triptych [`Proofs`](`Proof`), yielding whether each is valid.
+    ///
+    /// Each item consists of a [`Statement`], a [`Proof`], and the [`Transcript`] used when the proof was generated.
+    /// Items are verified in batches of up to `batch_size` items. If a batch fails, its items are verified individually
+    /// so that each result is accurate. Batching is fastest when consecutive items share an
+    /// [`InputSet`](`crate::statement::InputSet`) and [`Parameters`], and when most proofs are valid. A `batch_size` of
+    /// zero is treated as one.
+    ///
+    /// The results are yielded in the same order as the items. Items are only consumed when a result is needed.
+    pub fn verify_stream<I, T>(items: I, batch_size: usize) -> VerifyStream<I::IntoIter>
+    where
+        I: IntoIterator<Item = (Statement, Proof, T)>,
+        T: FiatShamirTranscript,
+    {
+        VerifyStream {
+            items: items.into_iter(),
+            batch_size: batch_size.max(1),
+            results: VecDeque::new(),
         }
+    }
 
-        // Determine the size of the final check vector, which must not overflow `usize`
-        let batch_size = u32::try_from(proofs.len()).map_err(|_| ProofError::InvalidParameter)?;
+    /// Verify a batch of Triptych [`Proofs`](`Proof`) whose linking tags are supplied in compressed form.
+    ///
+    /// This is a convenience function for batches that share [`Parameters`] `params` and an [`InputSet`] `input_set`,
+    /// where each proof has its own linking tag. The compressed linking `tags` are decompressed together before any
+    /// statements are constructed, and correspond to the `proofs` and `transcripts` in order.
+    ///
+    /// If any linking tag is not a canonical point encoding, returns [`ProofError::FailedDeserialization`].
+    /// If the resulting statements are invalid, or if any proof is invalid, returns a [`ProofError`].
+    #[allow(non_snake_case)]
+    pub fn verify_batch_with_compressed_tags<T: FiatShamirTranscript>(
+        params: &Arc<Parameters>,
+        input_set: &Arc<InputSet>,
+        tags: &[CompressedRistretto],
+        proofs: &[Proof],
+        transcripts: &mut [T],
+    ) -> Result<(), ProofError> {
+        // Check that we have the same number of tags and proofs
+        if tags.len() != proofs.len() {
+            return Err(ProofError::InvalidParameter);
+        }
 
-        // This is unlikely to overflow; even if it does, the only effect is unnecessary reallocation
-        #[allow(clippy::arithmetic_side_effects)]
+        // Decompress all tags, rejecting the batch if any is invalid
+        let J = tags
+            .iter()
+            .map(|tag| tag.decompress())
+            .collect::<Option<Vec<RistrettoPoint>>>()
+            .ok_or(ProofError::FailedDeserialization)?;
+
+        // Build the statements
+        let statements = J
+            .iter()
+            .map(|J| Statement::new(params, input_set, J))
+            .collect::<Result<Vec<Statement>, _>>()
+            .map_err(|_| ProofError::InvalidParameter)?;
+
+        Self::verify_batch(&statements, proofs, transcripts)
+    }
+
+    /// Verify a batch of Triptych [`Proofs`](`Proof`) using [`VerifyTuning`] options.
+    ///
+    /// An empty batch is valid by definition.
+    ///
+    /// Verification requires that the `statements` and `transcripts` match those used when the `proofs` were generated,
+    /// and that they share a common [`InputSet`](`crate::statement::InputSet`) and
+    /// [`Parameters`](`crate::parameters::Parameters`).
+    ///
+    /// If any of the above requirements are not met, if the `tuning` options are invalid, or if any proof is invalid,
+    /// returns a [`ProofError`].
+    pub fn verify_batch_with_tuning<T: FiatShamirTranscript>(
+        statements: &[Statement],
+        proofs: &[Proof],
+        transcripts: &mut [T],
+        tuning: VerifyTuning,
+    ) -> Result<(), ProofError> {
+        Self::verify_batch_internal(statements, proofs, transcripts, tuning, None, None, None)
+    }
+
+    /// The actual batch verifier functionality.
+    ///
+    /// If a `shared_nonce` is provided, it is bound into the transcript used to derive verifier weights.
+    /// If a `precomputation` is provided, it must be built from [`Parameters::fixed_generators`] for the statements'
+    /// parameters, and the final multiscalar multiplication uses it in place of the `msm_window` tuning option.
+    /// If a `scratch` space is provided, its buffers are used instead of allocating new ones.
+    #[allow(clippy::too_many_lines, non_snake_case)]
+    pub(crate) fn verify_batch_internal<T: FiatShamirTranscript>(
+        statements: &[Statement],
+        proofs: &[Proof],
+        transcripts: &mut [T],
+        tuning: VerifyTuning,
+        shared_nonce: Option<&[u8]>,
+        precomputation: Option<&VartimeRistrettoPrecomputation>,
+        scratch: Option<&mut VerifyScratch>,
+    ) -> Result<(), ProofError> {
+        Self::check_batch(statements, proofs, transcripts)?;
+
+        // An empty batch is considered trivially valid
+        let first_statement = match statements.first() {
+            Some(statement) => statement,
+            None => return Ok(()),
+        };
+
+        // Extract common values for convenience
+        let mut M = first_statement.get_input_set().keys();
+        let params = first_statement.get_params();
+
+        // Check that all proof semantics are valid for the statement; this must happen before any indexing
+        if !proofs.iter().all(|proof| proof.is_structurally_valid(params)) {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Determine how many verification keys to process at once
+        let chunk_size = match tuning.chunk_size {
+            None => M.len(),
+            Some(0) => return Err(ProofError::InvalidParameter),
+            Some(chunk_size) => chunk_size.min(M.len()),
+        };
+
+        // Determine the size of the final check vector, which includes only one chunk of verification keys
         let final_size = usize::try_from(
-            1 // G
-            + params.get_n() * params.get_m() // CommitmentG
-            + 1 // CommitmentH
-            + params.get_N() // M
-            + 1 // U
-            + batch_size * (
-                4 // A, B, C, D
-                + 1 // J
-                + 2 * params.get_m() // X, Y
-            ),
+            params
+                .verify_batch_cost(proofs.len())
+                .msm_terms
+                .saturating_sub(params.get_N())
+                .saturating_add(u64::try_from(chunk_size).map_err(|_| ProofError::InvalidParameter)?),
         )
         .map_err(|_| ProofError::InvalidParameter)?;
 
         // Set up the point vector for the final check
-        let points = proofs
+        let mut points = proofs
             .iter()
             .zip(statements.iter())
             .flat_map(|(p, s)| {
@@ -593,25 +1443,26 @@ impl Proof {
                     .chain(p.X.iter())
                     .chain(p.Y.iter())
             })
-            .chain(once(params.get_G()))
-            .chain(params.get_CommitmentG().iter())
-            .chain(once(params.get_CommitmentH()))
-            .chain(M.iter())
-            .chain(once(params.get_U()))
             .collect::<Vec<&RistrettoPoint>>();
 
-        // Start the scalar vector, putting the common elements last
-        let mut scalars = Vec::with_capacity(final_size);
+        // Start the scalar vector, putting the common elements last; use the scratch space if we have one, so its
+        // buffers are reused across verifications
+        let mut local_scratch = VerifyScratch::default();
+        let VerifyScratch { scalars, M_scalars } = scratch.unwrap_or(&mut local_scratch);
+        scalars.clear();
+        scalars.reserve(final_size);
 
         // Set up common scalars
         let mut G_scalar = Scalar::ZERO;
-        let mut CommitmentG_scalars = vec![Scalar::ZERO; params.get_CommitmentG().len()];
+        let mut CommitmentG_scalars = vec![Scalar::ZERO; params.commitment_generators().len()];
         let mut CommitmentH_scalar = Scalar::ZERO;
-        let mut M_scalars = vec![Scalar::ZERO; M.len()];
         let mut U_scalar = Scalar::ZERO;
 
         // Set up a transcript generator for use in weighting
         let mut transcript_weights = Transcript::new(b"Triptych verifier weights");
+        if let Some(shared_nonce) = shared_nonce {
+            transcript_weights.append_message(b"shared nonce", shared_nonce);
+        }
 
         let mut null_rng = NullRng;
 
@@ -633,6 +1484,7 @@ impl Proof {
         let mut transcript_weights_rng = transcript_weights.build_rng().finalize(&mut null_rng);
 
         // Process each proof
+        let mut key_scalar_streams = Vec::with_capacity(proofs.len());
         for (proof, xi_powers) in proofs.iter().zip(xi_powers_all.iter()) {
             // Reconstruct the remaining `f` terms
             let f = (0..params.get_m())
@@ -705,96 +1557,269 @@ impl Proof {
                 scalars.push(-w4 * xi_power);
             }
 
-            // Set up the initial `f` product and Gray iterator
-            let mut f_product = f.iter().map(|f_row| f_row[0]).product::<Scalar>();
-            let gray_iterator =
-                GrayIterator::new(params.get_n(), params.get_m()).ok_or(ProofError::InvalidParameter)?;
-
-            // Invert each element of `f` for efficiency
-            let mut f_inverse_flat = f.iter().flatten().copied().collect::<Vec<Scalar>>();
-            Scalar::batch_invert(&mut f_inverse_flat);
-            let f_inverse = f_inverse_flat
-                .chunks_exact(params.get_n() as usize)
-                .collect::<Vec<&[Scalar]>>();
-
-            // M
-            let mut U_scalar_proof = Scalar::ZERO;
-            for (M_scalar, (gray_index, gray_old, gray_new)) in M_scalars.iter_mut().zip(gray_iterator) {
-                // Update the `f` product
-                f_product *= f_inverse[gray_index][gray_old as usize] * f[gray_index][gray_new as usize];
-
-                *M_scalar += w3 * f_product;
-                U_scalar_proof += f_product;
-            }
-
-            // U
-            U_scalar += w4 * U_scalar_proof;
+            // M and U, whose scalars are computed later
+            key_scalar_streams.push(KeyScalarStream::new(params, f, w3, w4)?);
         }
 
-        // Add all common elements to the scalar vector
-        scalars.push(G_scalar);
-        scalars.extend(CommitmentG_scalars);
-        scalars.push(CommitmentH_scalar);
-        scalars.extend(M_scalars);
-        scalars.push(U_scalar);
+        // Perform the final check in chunks of verification keys; this can be done in variable time since it holds no
+        // secrets
+        let msm = |scalars: &[Scalar], points: &[&RistrettoPoint]| match tuning.msm_window {
+            None => Ok(RistrettoPoint::vartime_multiscalar_mul(scalars, points.iter().copied())),
+            Some(window) => vartime_multiscalar_mul_windowed(scalars, points.iter().copied(), window)
+                .ok_or(ProofError::InvalidParameter),
+        };
+        let mut result = RistrettoPoint::identity();
+        // Verification keys are read through the input set, so overrides are applied without copying the whole set
+        while M.len() > 0 {
+            let M_chunk = M.by_ref().take(chunk_size).collect::<Vec<&RistrettoPoint>>();
+            if M.len() > 0 {
+                M_scalars.clear();
+                M_scalars.resize(M_chunk.len(), Scalar::ZERO);
+                for stream in &mut key_scalar_streams {
+                    U_scalar += stream.next_chunk(M_scalars)?;
+                }
 
-        // Perform the final check; this can be done in variable time since it holds no secrets
-        if RistrettoPoint::vartime_multiscalar_mul(scalars.iter(), points) == RistrettoPoint::identity() {
+                result += msm(M_scalars, &M_chunk)?;
+            } else {
+                // The `U` scalar is only known after the last chunk, so all remaining elements are included here
+                let M_start = scalars.len();
+                scalars.resize(
+                    M_start.checked_add(M_chunk.len()).ok_or(ProofError::InvalidParameter)?,
+                    Scalar::ZERO,
+                );
+                for stream in &mut key_scalar_streams {
+                    U_scalar += stream.next_chunk(&mut scalars[M_start..])?;
+                }
+                points.extend(M_chunk);
+                let fixed_scalars = once(G_scalar)
+                    .chain(CommitmentG_scalars.iter().copied())
+                    .chain(once(CommitmentH_scalar))
+                    .chain(once(U_scalar));
+                result += match precomputation {
+                    Some(precomputation) => precomputation.vartime_mixed_multiscalar_mul(
+                        fixed_scalars,
+                        scalars.iter(),
+                        points.iter().copied(),
+                    ),
+                    None => {
+                        scalars.extend(fixed_scalars);
+                        points.extend(params.fixed_generators());
+                        msm(scalars, &points)?
+                    },
+                };
+            }
+        }
+        if result == RistrettoPoint::identity() {
             Ok(())
         } else {
             Err(ProofError::FailedVerification)
         }
     }
 
+    /// Check that a batch has the same number of statements, proofs, and transcripts, and that its statements share an
+    /// input set and parameters.
+    fn check_batch<T: FiatShamirTranscript>(
+        statements: &[Statement],
+        proofs: &[Proof],
+        transcripts: &[T],
+    ) -> Result<(), ProofError> {
+        // Check that we have the same number of statements, proofs, and transcripts
+        if statements.len() != proofs.len() {
+            return Err(ProofError::InvalidParameter);
+        }
+        if statements.len() != transcripts.len() {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Each statement must use the same input set (checked using the hash for efficiency)
+        if !statements.iter().map(|s| s.get_input_set().get_hash()).all_equal() {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Each statement must use the same parameters (checked using the hash for efficiency)
+        if !statements.iter().map(|s| s.get_params().get_hash()).all_equal() {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        Ok(())
+    }
+
+    /// Check that this [`Proof`] is structurally valid for [`Parameters`] `params`.
+    ///
+    /// This checks only that the proof's vector and matrix dimensions match those specified by `params`, and performs
+    /// no group operations. It is useful for cheaply rejecting malformed proofs, such as those deserialized from
+    /// untrusted sources. A structurally-valid proof may still fail verification.
+    #[must_use = "validity result must be checked"]
+    pub fn is_structurally_valid(&self, params: &Parameters) -> bool {
+        let m = params.get_m() as usize;
+        let n_minus_1 = match params.get_n().checked_sub(1) {
+            Some(n_minus_1) => n_minus_1 as usize,
+            None => return false,
+        };
+
+        self.X.len() == m &&
+            self.Y.len() == m &&
+            self.f.len() == m &&
+            self.f.iter().all(|f_row| f_row.len() == n_minus_1)
+    }
+
+    /// Get the Fiat-Shamir challenge `xi` for this [`Proof`].
+    ///
+    /// This is an intermediate value of verification, and is useful for comparing against another implementation when
+    /// generating or checking test vectors. It does not verify the proof.
+    ///
+    /// The challenge depends on the `statement` and `transcript`, which should match those used when the proof was
+    /// generated. If the proof is not valid for the statement's [`Parameters`], returns a [`ProofError`].
+    #[cfg(feature = "test-vectors")]
+    pub fn challenge<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+    ) -> Result<Scalar, ProofError> {
+        self.challenge_powers(statement, transcript)?
+            .get(1)
+            .copied()
+            .ok_or(ProofError::InvalidParameter)
+    }
+
+    /// Get the powers `[xi^0, xi^1, ..., xi^m]` of the Fiat-Shamir challenge `xi` for this [`Proof`].
+    ///
+    /// This is the same vector derived during proving and verification. It is useful when an outer protocol needs to
+    /// bind to the challenge, and avoids recomputing it. It does not verify the proof.
+    ///
+    /// The challenge depends on the `statement` and `transcript`, which should match those used when the proof was
+    /// generated. If the proof is not valid for the statement's [`Parameters`], returns a [`ProofError`].
+    pub fn challenge_powers<T: FiatShamirTranscript>(
+        &self,
+        statement: &Statement,
+        transcript: &mut T,
+    ) -> Result<Vec<Scalar>, ProofError> {
+        let params = statement.get_params();
+        if !self.is_structurally_valid(params) {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        let mut null_rng = NullRng;
+        let mut transcript = ProofTranscript::new(transcript, statement, &mut null_rng, None);
+
+        transcript.commit(params, &self.A, &self.B, &self.C, &self.D, &self.X, &self.Y)
+    }
+
+    /// Get the dimensions `(m, n)` this [`Proof`] was built for, inferred from its structure.
+    ///
+    /// This is useful for choosing [`Parameters`] to verify against, such as when a proof comes from an untrusted
+    /// source. Since it doesn't check the proof against any [`Parameters`], verification may still fail.
+    ///
+    /// If the proof's internal vectors are inconsistent, or if they imply a base or exponent less than 2, returns
+    /// `None`.
+    pub fn dimensions(&self) -> Option<(u32, u32)> {
+        let m = self.X.len();
+        if self.Y.len() != m || self.f.len() != m {
+            return None;
+        }
+        let n_minus_1 = self.f.first()?.len();
+        if !self.f.iter().all(|f_row| f_row.len() == n_minus_1) {
+            return None;
+        }
+
+        let m = u32::try_from(m).ok()?;
+        let n = u32::try_from(n_minus_1).ok()?.checked_add(1)?;
+        if m < 2 || n < 2 {
+            return None;
+        }
+
+        Some((m, n))
+    }
+
     /// Serialize a [`Proof`] to a canonical byte vector.
-    #[allow(non_snake_case)]
+    ///
+    /// The encoding begins with the protocol version as an 8-byte little-endian integer.
     pub fn to_bytes(&self) -> Vec<u8> {
-        // This cannot overflow
+        // This cannot overflow, since it counts elements that are already in memory; a proof deserialized using `serde`
+        // is not checked, so this can't assume that the rows of `f` have the same length
         #[allow(clippy::arithmetic_side_effects)]
         let mut result = Vec::with_capacity(
-            8 // `n - 1`, `m`
+            8 // version
+            + 8 // `n - 1`, `m`
             + SERIALIZED_BYTES * (
                 4 // `A, B, C, D`
                 + self.X.len()
                 + self.Y.len()
                 + 3 // `z_A, z_C, z`
-                + self.f.len() * self.f[0].len()
+                + self.f.iter().map(Vec::len).sum::<usize>()
             ),
         );
+        self.encode(&mut |bytes| {
+            result.extend_from_slice(bytes);
+            Ok::<(), Infallible>(())
+        })
+        .unwrap_or_else(|never| match never {});
+
+        result
+    }
+
+    /// Serialize a [`Proof`] to a `writer`, using the canonical encoding from [`Proof::to_bytes`].
+    ///
+    /// The encoding is written one element at a time without building the full encoding in memory, so you should
+    /// usually use a buffered writer like [`BufWriter`](`std::io::BufWriter`). If the writer fails, returns its error.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.encode(&mut |bytes| writer.write_all(bytes))
+    }
+
+    /// Pass the canonical encoding of this [`Proof`] to `emit` in pieces, stopping at the first error.
+    #[allow(non_snake_case)]
+    fn encode<E>(&self, emit: &mut impl FnMut(&[u8]) -> Result<(), E>) -> Result<(), E> {
         #[allow(clippy::cast_possible_truncation)]
-        let n_minus_1 = self.f[0].len() as u32;
+        let n_minus_1 = self.f.first().map_or(0, Vec::len) as u32;
         #[allow(clippy::cast_possible_truncation)]
         let m = self.f.len() as u32;
-        result.extend(n_minus_1.to_le_bytes());
-        result.extend(m.to_le_bytes());
-
-        result.extend_from_slice(self.A.compress().as_bytes());
-        result.extend_from_slice(self.B.compress().as_bytes());
-        result.extend_from_slice(self.C.compress().as_bytes());
-        result.extend_from_slice(self.D.compress().as_bytes());
-        result.extend_from_slice(self.z_A.as_bytes());
-        result.extend_from_slice(self.z_C.as_bytes());
-        result.extend_from_slice(self.z.as_bytes());
+        emit(&VERSION.to_le_bytes())?;
+        emit(&n_minus_1.to_le_bytes())?;
+        emit(&m.to_le_bytes())?;
+
+        emit(self.A.compress().as_bytes())?;
+        emit(self.B.compress().as_bytes())?;
+        emit(self.C.compress().as_bytes())?;
+        emit(self.D.compress().as_bytes())?;
+        emit(self.z_A.as_bytes())?;
+        emit(self.z_C.as_bytes())?;
+        emit(self.z.as_bytes())?;
         for X in &self.X {
-            result.extend_from_slice(X.compress().as_bytes());
+            emit(X.compress().as_bytes())?;
         }
         for Y in &self.Y {
-            result.extend_from_slice(Y.compress().as_bytes());
+            emit(Y.compress().as_bytes())?;
         }
         for f_row in &self.f {
             for f in f_row {
-                result.extend_from_slice(f.as_bytes());
+                emit(f.as_bytes())?;
             }
         }
 
-        result
+        Ok(())
     }
 
     /// Deserialize a [`Proof`] from a canonical byte slice.
     ///
-    /// If `bytes` does not represent a canonical encoding, returns a [`ProofError`].
-    #[allow(non_snake_case)]
+    /// If `bytes` encodes an unsupported protocol version, returns [`ProofError::UnsupportedVersion`].
+    /// If `bytes` does not otherwise represent a canonical encoding, returns a [`ProofError`].
+    ///
+    /// Memory use is bounded by the length of `bytes`. To further restrict the dimensions of proofs that can be
+    /// deserialized, use [`Proof::from_bytes_with_limits`].
     pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProofError> {
+        Self::from_bytes_with_limits(bytes, DeserializeLimits::default())
+    }
+
+    /// Deserialize a [`Proof`] from a canonical byte slice, rejecting proofs whose dimensions exceed `limits`.
+    ///
+    /// The encoded dimensions are checked against `limits` and the length of `bytes` before anything is allocated.
+    ///
+    /// If `bytes` encodes an unsupported protocol version, returns [`ProofError::UnsupportedVersion`].
+    /// If `bytes` does not otherwise represent a canonical encoding, or if its dimensions exceed `limits`, returns a
+    /// [`ProofError`].
+    #[allow(non_snake_case)]
+    pub fn from_bytes_with_limits(bytes: &[u8], limits: DeserializeLimits) -> Result<Self, ProofError> {
         // Helper to parse a `u32` from a `u8` iterator
         let parse_u32 = |iter: &mut dyn Iterator<Item = &u8>| {
             // Get the next four bytes
@@ -808,97 +1833,102 @@ impl Proof {
             Ok(u32::from_le_bytes(array))
         };
 
-        // Helper to parse a scalar from a chunk iterator
-        let parse_scalar = |chunks: &mut ChunksExact<'_, u8>| -> Result<Scalar, ProofError> {
-            chunks
-                .next()
-                .ok_or(ProofError::FailedDeserialization)
-                .and_then(|slice| {
-                    let bytes: [u8; SERIALIZED_BYTES] =
-                        slice.try_into().map_err(|_| ProofError::FailedDeserialization)?;
-                    Option::<Scalar>::from(Scalar::from_canonical_bytes(bytes)).ok_or(ProofError::FailedDeserialization)
-                })
-        };
-
-        // Helper to parse a compressed point from a chunk iterator
-        let parse_point = |chunks: &mut ChunksExact<'_, u8>| -> Result<RistrettoPoint, ProofError> {
-            chunks
-                .next()
-                .ok_or(ProofError::FailedDeserialization)
-                .and_then(|slice| {
-                    let bytes: [u8; SERIALIZED_BYTES] =
-                        slice.try_into().map_err(|_| ProofError::FailedDeserialization)?;
-
-                    CompressedRistretto::from_slice(&bytes)
-                        .map_err(|_| ProofError::FailedDeserialization)?
-                        .decompress()
-                        .ok_or(ProofError::FailedDeserialization)
-                })
-        };
+        // Parse the version and check that we support it
+        let (version_bytes, bytes) = bytes
+            .split_first_chunk::<8>()
+            .ok_or(ProofError::FailedDeserialization)?;
+        let version = u64::from_le_bytes(*version_bytes);
+        if version != VERSION {
+            return Err(ProofError::UnsupportedVersion { version });
+        }
 
         // Set up the slice iterator
         let mut iter = bytes.iter();
 
-        // Parse the encoded vector dimensions and check that `n, m > 1` and that they do not overflow
+        // Parse the encoded vector dimensions and check that `n, m > 1`, that they do not overflow, and that they are
+        // within the limits
         let n_minus_1 = parse_u32(&mut iter)?;
-        if n_minus_1.checked_add(1).ok_or(ProofError::FailedDeserialization)? < 2 {
+        let n = n_minus_1.checked_add(1).ok_or(ProofError::FailedDeserialization)?;
+        if n < 2 || n > limits.max_n {
             return Err(ProofError::FailedDeserialization);
         }
         let m = parse_u32(&mut iter)?;
-        if m < 2 {
+        if m < 2 || m > limits.max_m {
+            return Err(ProofError::FailedDeserialization);
+        }
+
+        // Check that the length is consistent with the dimensions before parsing any proof elements
+        let expected_len = Self::encoded_elements_len(n_minus_1, m).ok_or(ProofError::FailedDeserialization)?;
+        if iter.as_slice().len() != expected_len {
             return Err(ProofError::FailedDeserialization);
         }
 
         // The rest of the serialization is of encoded proof elements
         let mut chunks = iter.as_slice().chunks_exact(SERIALIZED_BYTES);
+        let proof = Self::parse_elements(n_minus_1, m, || {
+            chunks
+                .next()
+                .and_then(|chunk| chunk.try_into().ok())
+                .ok_or(ProofError::FailedDeserialization)
+        })?;
+
+        // Ensure no data is left over
+        if !chunks.remainder().is_empty() {
+            return Err(ProofError::FailedDeserialization);
+        }
+        if chunks.next().is_some() {
+            return Err(ProofError::FailedDeserialization);
+        }
+
+        Ok(proof)
+    }
+
+    /// Parse the proof elements that follow the header of a canonical encoding with dimensions `n_minus_1` and `m`,
+    /// getting each encoded element from `next` in order.
+    #[allow(non_snake_case)]
+    fn parse_elements<F: FnMut() -> Result<[u8; SERIALIZED_BYTES], ProofError>>(
+        n_minus_1: u32,
+        m: u32,
+        mut next: F,
+    ) -> Result<Self, ProofError> {
+        // Helper to parse a canonical scalar
+        let parse_scalar = |next: &mut F| -> Result<Scalar, ProofError> {
+            Option::<Scalar>::from(Scalar::from_canonical_bytes(next()?)).ok_or(ProofError::FailedDeserialization)
+        };
+
+        // Helper to parse a compressed point
+        let parse_point = |next: &mut F| -> Result<RistrettoPoint, ProofError> {
+            CompressedRistretto(next()?)
+                .decompress()
+                .ok_or(ProofError::FailedDeserialization)
+        };
 
         // Extract the fixed proof elements
-        let A = parse_point(&mut chunks)?;
-        let B = parse_point(&mut chunks)?;
-        let C = parse_point(&mut chunks)?;
-        let D = parse_point(&mut chunks)?;
-        let z_A = parse_scalar(&mut chunks)?;
-        let z_C = parse_scalar(&mut chunks)?;
-        let z = parse_scalar(&mut chunks)?;
+        let A = parse_point(&mut next)?;
+        let B = parse_point(&mut next)?;
+        let C = parse_point(&mut next)?;
+        let D = parse_point(&mut next)?;
+        let z_A = parse_scalar(&mut next)?;
+        let z_C = parse_scalar(&mut next)?;
+        let z = parse_scalar(&mut next)?;
 
         // Extract the `X` and `Y` vectors
         let X = (0..m)
-            .map(|_| parse_point(&mut chunks))
+            .map(|_| parse_point(&mut next))
             .collect::<Result<Vec<RistrettoPoint>, ProofError>>()?;
         let Y = (0..m)
-            .map(|_| parse_point(&mut chunks))
+            .map(|_| parse_point(&mut next))
             .collect::<Result<Vec<RistrettoPoint>, ProofError>>()?;
 
         // Extract the `f` matrix
         let f = (0..m)
             .map(|_| {
                 (0..n_minus_1)
-                    .map(|_| parse_scalar(&mut chunks))
+                    .map(|_| parse_scalar(&mut next))
                     .collect::<Result<Vec<Scalar>, ProofError>>()
             })
             .collect::<Result<Vec<Vec<Scalar>>, ProofError>>()?;
 
-        // Ensure no data is left over
-        if !chunks.remainder().is_empty() {
-            return Err(ProofError::FailedDeserialization);
-        }
-        if chunks.next().is_some() {
-            return Err(ProofError::FailedDeserialization);
-        }
-
-        // Perform a sanity check on all vectors
-        if X.len() != m as usize || Y.len() != m as usize {
-            return Err(ProofError::FailedDeserialization);
-        }
-        if f.len() != m as usize {
-            return Err(ProofError::FailedDeserialization);
-        }
-        for f_row in &f {
-            if f_row.len() != n_minus_1 as usize {
-                return Err(ProofError::FailedDeserialization);
-            }
-        }
-
         Ok(Proof {
             A,
             B,
@@ -912,72 +1942,683 @@ impl Proof {
             z,
         })
     }
-}
 
-#[cfg(test)]
-mod test {
-    use alloc::{sync::Arc, vec::Vec};
+    /// Deserialize a [`Proof`] from a `reader`, using the canonical encoding from [`Proof::to_bytes`].
+    ///
+    /// The encoding records the proof's dimensions, so this reads exactly the bytes of one proof and leaves anything
+    /// after it in the reader. This means you can read several proofs written to one stream by [`Proof::write_to`].
+    /// Each 32-byte proof element is parsed as soon as it is read, without buffering the encoding, so you should
+    /// usually use a buffered reader like [`BufReader`](`std::io::BufReader`).
+    ///
+    /// Memory use is bounded by the number of bytes actually read. To further restrict the dimensions of proofs that
+    /// can be read, use [`Proof::read_from_with_limits`].
+    ///
+    /// If the reader ends before the proof does, returns [`ProofError::Truncated`]. If the reader otherwise fails,
+    /// returns [`ProofError::FailedRead`]. If the data is not a canonical encoding, returns another [`ProofError`] as
+    /// with [`Proof::from_bytes`]; since elements are parsed as they are read, this stops reading at the first invalid
+    /// element.
+    #[cfg(feature = "std")]
+    pub fn read_from<R: Read>(reader: &mut R) -> Result<Self, ProofError> {
+        Self::read_from_with_limits(reader, DeserializeLimits::default())
+    }
 
-    use curve25519_dalek::{traits::Identity, RistrettoPoint, Scalar};
-    use itertools::izip;
-    use rand_chacha::ChaCha12Rng;
-    use rand_core::{CryptoRngCore, SeedableRng};
+    /// Deserialize a [`Proof`] from a `reader`, rejecting proofs whose dimensions exceed `limits`.
+    ///
+    /// The encoded dimensions are checked against `limits` before anything after them is read, so a proof that is too
+    /// large is rejected without reading or allocating for its elements. In that case, the rest of the proof is left
+    /// in the reader. Otherwise, this behaves like [`Proof::read_from`].
+    #[cfg(feature = "std")]
+    pub fn read_from_with_limits<R: Read>(reader: &mut R, limits: DeserializeLimits) -> Result<Self, ProofError> {
+        let read_error = |error: io::Error| match error.kind() {
+            ErrorKind::UnexpectedEof => ProofError::Truncated,
+            _ => ProofError::FailedRead,
+        };
 
-    use crate::{
-        proof::{ProofError, SERIALIZED_BYTES},
-        InputSet,
-        Parameters,
-        Proof,
-        Statement,
-        Transcript,
-        Witness,
-    };
+        // Read and check the header
+        let mut header = [0u8; HEADER_BYTES];
+        reader.read_exact(&mut header).map_err(read_error)?;
+        let (version_bytes, dimension_bytes) = header
+            .split_first_chunk::<8>()
+            .ok_or(ProofError::FailedDeserialization)?;
+        let (n_minus_1_bytes, m_bytes) = dimension_bytes
+            .split_first_chunk::<4>()
+            .ok_or(ProofError::FailedDeserialization)?;
+        let m_bytes = m_bytes.first_chunk::<4>().ok_or(ProofError::FailedDeserialization)?;
+        let version = u64::from_le_bytes(*version_bytes);
+        if version != VERSION {
+            return Err(ProofError::UnsupportedVersion { version });
+        }
+        let n_minus_1 = u32::from_le_bytes(*n_minus_1_bytes);
+        let m = u32::from_le_bytes(*m_bytes);
+        let n = n_minus_1.checked_add(1).ok_or(ProofError::FailedDeserialization)?;
+        if n < 2 || n > limits.max_n || m < 2 || m > limits.max_m {
+            return Err(ProofError::FailedDeserialization);
+        }
 
-    // Check that the serialized proof element size constant is correct
-    #[test]
-    fn test_serialized_bytes() {
-        // Check the scalar encoding size
-        assert_eq!(Scalar::ZERO.as_bytes().len(), SERIALIZED_BYTES);
+        // Read and parse each proof element in turn
+        Self::parse_elements(n_minus_1, m, || {
+            let mut bytes = [0u8; SERIALIZED_BYTES];
+            reader.read_exact(&mut bytes).map_err(read_error)?;
 
-        // Check the group element encoding size
-        assert_eq!(RistrettoPoint::identity().compress().as_bytes().len(), SERIALIZED_BYTES);
+            Ok(bytes)
+        })
     }
 
-    // Generate a batch of witnesses, statements, and transcripts
-    #[allow(non_snake_case)]
-    #[allow(clippy::arithmetic_side_effects)]
-    fn generate_data<R: CryptoRngCore>(
-        n: u32,
-        m: u32,
-        b: usize,
-        rng: &mut R,
-    ) -> (Vec<Witness>, Vec<Statement>, Vec<Transcript>) {
-        // Generate parameters
-        let params = Arc::new(Parameters::new(n, m).unwrap());
+    /// Get the length in bytes of the encoded proof elements that follow the header of a canonical encoding with
+    /// dimensions `n_minus_1` and `m`, or `None` if it overflows.
+    fn encoded_elements_len(n_minus_1: u32, m: u32) -> Option<usize> {
+        // `A`, `B`, `C`, `D`, `z_A`, `z_C`, `z`, `X`, `Y`, and `f`
+        usize::try_from(n_minus_1)
+            .ok()
+            .and_then(|n_minus_1| n_minus_1.checked_add(2))
+            .and_then(|row_len| row_len.checked_mul(usize::try_from(m).ok()?))
+            .and_then(|len| len.checked_add(7))
+            .and_then(|len| len.checked_mul(SERIALIZED_BYTES))
+    }
 
-        // Generate witnesses; for this test, we use adjacent indexes for simplicity
-        // This means the batch size must not exceed the input set size!
-        assert!(b <= params.get_N() as usize);
-        let mut witnesses = Vec::with_capacity(b);
-        witnesses.push(Witness::random(&params, rng));
-        for _ in 1..b {
-            let r = Scalar::random(rng);
-            let l = (witnesses.last().unwrap().get_l() + 1) % params.get_N();
-            witnesses.push(Witness::new(&params, l, &r).unwrap());
-        }
+    /// Serialize a [`Proof`] to a framed byte vector.
+    ///
+    /// The frame consists of the dimensions `n` and `m` as 4-byte little-endian integers and an 8-byte checksum of the
+    /// body, followed by the body itself, which is the canonical encoding from [`Proof::to_bytes`]. Use
+    /// [`Proof::from_framed_bytes`] to deserialize it.
+    ///
+    /// The frame makes it possible to distinguish an encoding that was cut short, such as by an interrupted write to a
+    /// log, from one that is corrupt.
+    #[cfg(feature = "framed")]
+    pub fn to_framed_bytes(&self) -> Vec<u8> {
+        let body = self.to_bytes();
 
-        // Generate input set from all witnesses
-        let mut M = (0..params.get_N())
-            .map(|_| RistrettoPoint::random(rng))
-            .collect::<Vec<RistrettoPoint>>();
-        for witness in &witnesses {
-            M[witness.get_l() as usize] = witness.compute_verification_key();
-        }
-        let input_set = Arc::new(InputSet::new(&M));
+        // A proof deserialized using `serde` is not checked, so its dimensions may be invalid
+        #[allow(clippy::cast_possible_truncation)]
+        let n = (self.f.first().map_or(0, Vec::len) as u32).saturating_add(1);
+        #[allow(clippy::cast_possible_truncation)]
+        let m = self.f.len() as u32;
 
-        // Generate statements
-        let mut statements = Vec::with_capacity(b);
-        for witness in &witnesses {
+        let mut result = Vec::with_capacity(FRAME_HEADER_BYTES.saturating_add(body.len()));
+        result.extend(n.to_le_bytes());
+        result.extend(m.to_le_bytes());
+        result.extend(Self::frame_checksum(&body));
+        result.extend(body);
+
+        result
+    }
+
+    /// Deserialize a [`Proof`] from a framed byte slice produced by [`Proof::to_framed_bytes`].
+    ///
+    /// If `bytes` is shorter than its frame requires, returns [`ProofError::Truncated`]. If the checksum does not
+    /// match, the frame is inconsistent with the body, or the body is not a canonical encoding, returns another
+    /// [`ProofError`].
+    ///
+    /// The frame dimensions are checked against `limits` before anything is allocated.
+    #[cfg(feature = "framed")]
+    pub fn from_framed_bytes(bytes: &[u8], limits: DeserializeLimits) -> Result<Self, ProofError> {
+        // Parse the frame header
+        let (header, body) = bytes
+            .split_first_chunk::<FRAME_HEADER_BYTES>()
+            .ok_or(ProofError::Truncated)?;
+        let (n_bytes, rest) = header
+            .split_first_chunk::<4>()
+            .ok_or(ProofError::FailedDeserialization)?;
+        let (m_bytes, checksum) = rest.split_first_chunk::<4>().ok_or(ProofError::FailedDeserialization)?;
+        let n = u32::from_le_bytes(*n_bytes);
+        let m = u32::from_le_bytes(*m_bytes);
+        if n < 2 || n > limits.max_n || m < 2 || m > limits.max_m {
+            return Err(ProofError::FailedDeserialization);
+        }
+
+        // Check the body length implied by the dimensions
+        let expected_len = usize::try_from(n)
+            .ok()
+            .and_then(|n| n.checked_add(1))
+            .and_then(|row_len| row_len.checked_mul(usize::try_from(m).ok()?))
+            .and_then(|len| len.checked_add(7))
+            .and_then(|len| len.checked_mul(SERIALIZED_BYTES))
+            .and_then(|len| len.checked_add(16))
+            .ok_or(ProofError::FailedDeserialization)?;
+        if body.len() < expected_len {
+            return Err(ProofError::Truncated);
+        }
+        if body.len() > expected_len {
+            return Err(ProofError::FailedDeserialization);
+        }
+
+        // Check the body against the checksum and frame
+        if Self::frame_checksum(body).ct_eq(checksum).unwrap_u8() == 0 {
+            return Err(ProofError::FailedDeserialization);
+        }
+        let proof = Self::from_bytes_with_limits(body, limits)?;
+        if proof.dimensions() != Some((m, n)) {
+            return Err(ProofError::FailedDeserialization);
+        }
+
+        Ok(proof)
+    }
+
+    // Compute the checksum of a framed proof body
+    #[cfg(feature = "framed")]
+    fn frame_checksum(body: &[u8]) -> [u8; 8] {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"Triptych framed proof");
+        hasher.update(body);
+
+        let mut checksum = [0u8; 8];
+        hasher.finalize_xof().fill(&mut checksum);
+
+        checksum
+    }
+}
+
+impl ConstantTimeEq for Proof {
+    /// Determine if two [`Proof`]s are equal in constant time.
+    ///
+    /// This compares the canonical serializations of the proofs, so it does not reveal which components differ.
+    /// It does reveal whether the proofs have the same dimensions.
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.to_bytes().ct_eq(&other.to_bytes())
+    }
+}
+
+// Hash the canonical serialization, which is the same for equal proofs and contains only public data
+impl Hash for Proof {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.to_bytes().hash(state);
+    }
+}
+
+impl TryFrom<&[u8]> for Proof {
+    type Error = ProofError;
+
+    /// Deserialize a [`Proof`] from a canonical byte slice.
+    ///
+    /// This is equivalent to [`Proof::from_bytes`].
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_bytes(bytes)
+    }
+}
+
+/// The state needed to compute a proof's verification key scalars incrementally during verification.
+struct KeyScalarStream {
+    f: Vec<Vec<Scalar>>,
+    f_inverse: Vec<Vec<Scalar>>,
+    f_product: Scalar,
+    gray_iterator: GrayIterator,
+    w3: Scalar,
+    w4: Scalar,
+}
+
+impl KeyScalarStream {
+    /// Set up the stream using the reconstructed `f` matrix and the proof's verification weights.
+    fn new(params: &Parameters, f: Vec<Vec<Scalar>>, w3: Scalar, w4: Scalar) -> Result<Self, ProofError> {
+        // Set up the initial `f` product and Gray iterator
+        let f_product = f.iter().map(|f_row| f_row[0]).product::<Scalar>();
+        let gray_iterator = GrayIterator::new(params.get_n(), params.get_m()).ok_or(ProofError::InvalidParameter)?;
+
+        // Invert each element of `f` for efficiency
+        let mut f_inverse_flat = f.iter().flatten().copied().collect::<Vec<Scalar>>();
+        Scalar::batch_invert(&mut f_inverse_flat);
+        let f_inverse = f_inverse_flat
+            .chunks_exact(params.get_n() as usize)
+            .map(<[Scalar]>::to_vec)
+            .collect::<Vec<Vec<Scalar>>>();
+
+        Ok(Self {
+            f,
+            f_inverse,
+            f_product,
+            gray_iterator,
+            w3,
+            w4,
+        })
+    }
+
+    /// Add the next verification key scalars into `M_scalars`, returning the corresponding contribution to the `U`
+    /// scalar.
+    #[allow(non_snake_case)]
+    fn next_chunk(&mut self, M_scalars: &mut [Scalar]) -> Result<Scalar, ProofError> {
+        let mut U_scalar = Scalar::ZERO;
+        for M_scalar in M_scalars {
+            // Update the `f` product
+            let (gray_index, gray_old, gray_new) = self.gray_iterator.next().ok_or(ProofError::InvalidParameter)?;
+            self.f_product *= self.f_inverse[gray_index][gray_old as usize] * self.f[gray_index][gray_new as usize];
+
+            *M_scalar += self.w3 * self.f_product;
+            U_scalar += self.f_product;
+        }
+
+        Ok(self.w4 * U_scalar)
+    }
+}
+
+/// Commitments to the decomposition of a signing index, along with their openings.
+#[cfg(feature = "prover")]
+#[allow(non_snake_case)]
+pub(crate) struct DecompositionCommitments {
+    pub(crate) A: RistrettoPoint,
+    pub(crate) B: RistrettoPoint,
+    pub(crate) C: RistrettoPoint,
+    pub(crate) D: RistrettoPoint,
+    pub(crate) a: Zeroizing<Vec<Vec<Scalar>>>,
+    pub(crate) sigma: Zeroizing<Vec<Vec<Scalar>>>,
+    pub(crate) r_A: Scalar,
+    pub(crate) r_B: Scalar,
+    pub(crate) r_C: Scalar,
+    pub(crate) r_D: Scalar,
+}
+
+#[cfg(feature = "prover")]
+impl DecompositionCommitments {
+    /// Commit to the decomposition of the index `l`, using `rng` for all masks.
+    #[allow(non_snake_case)]
+    pub(crate) fn new<R: CryptoRngCore>(
+        params: &Parameters,
+        l: u64,
+        rng: &mut R,
+        timing: OperationTiming,
+    ) -> Result<Self, ProofError> {
+        // Compute the `A` matrix commitment
+        let r_A = Scalar::random(rng);
+        let mut a = Zeroizing::new(
+            (0..params.get_m())
+                .map(|_| {
+                    (0..params.get_n())
+                        .map(|_| Scalar::random(rng))
+                        .collect::<Vec<Scalar>>()
+                })
+                .collect::<Vec<Vec<Scalar>>>(),
+        );
+        for j in (0..params.get_m()).map(|j| j as usize) {
+            a[j][0] = -a[j][1..].iter().sum::<Scalar>();
+        }
+        // This includes the four masks
+        #[cfg(feature = "instrument")]
+        crate::instrument::record_random_scalars(a.iter().map(Vec::len).sum::<usize>().saturating_add(4));
+        let A = params
+            .commit_matrix(&a, &r_A, timing)
+            .map_err(|_| ProofError::InvalidParameter)?;
+
+        // Compute the `B` matrix commitment, which encodes the index; in constant time, each entry is chosen using a
+        // constant-time comparison of a digit against the column, so no branch or memory access depends on the index
+        let r_B = Scalar::random(rng);
+        let l_decomposed = Zeroizing::new(match timing {
+            OperationTiming::Constant => {
+                GrayIterator::decompose(params.get_n(), params.get_m(), l).ok_or(ProofError::InvalidParameter)?
+            },
+            OperationTiming::Variable => GrayIterator::decompose_vartime(params.get_n(), params.get_m(), l)
+                .ok_or(ProofError::InvalidParameter)?,
+        });
+        let sigma = Zeroizing::new(
+            (0..params.get_m())
+                .map(|j| {
+                    (0..params.get_n())
+                        .map(|i| delta(l_decomposed[j as usize], i, timing))
+                        .collect::<Vec<Scalar>>()
+                })
+                .collect::<Vec<Vec<Scalar>>>(),
+        );
+        let B = params
+            .commit_matrix(&sigma, &r_B, timing)
+            .map_err(|_| ProofError::InvalidParameter)?;
+
+        // Compute the `C` matrix commitment
+        let two = Scalar::from(2u32);
+        let r_C = Scalar::random(rng);
+        let a_sigma = Zeroizing::new(
+            (0..params.get_m())
+                .map(|j| {
+                    (0..params.get_n())
+                        .map(|i| a[j as usize][i as usize] * (Scalar::ONE - two * sigma[j as usize][i as usize]))
+                        .collect::<Vec<Scalar>>()
+                })
+                .collect::<Vec<Vec<Scalar>>>(),
+        );
+        let C = params
+            .commit_matrix(&a_sigma, &r_C, timing)
+            .map_err(|_| ProofError::InvalidParameter)?;
+
+        // Compute the `D` matrix commitment
+        let r_D = Scalar::random(rng);
+        let a_square = Zeroizing::new(
+            (0..params.get_m())
+                .map(|j| {
+                    (0..params.get_n())
+                        .map(|i| -a[j as usize][i as usize] * a[j as usize][i as usize])
+                        .collect::<Vec<Scalar>>()
+                })
+                .collect::<Vec<Vec<Scalar>>>(),
+        );
+        let D = params
+            .commit_matrix(&a_square, &r_D, timing)
+            .map_err(|_| ProofError::InvalidParameter)?;
+
+        Ok(Self {
+            A,
+            B,
+            C,
+            D,
+            a,
+            sigma,
+            r_A,
+            r_B,
+            r_C,
+            r_D,
+        })
+    }
+}
+
+/// Compute the `p` polynomial vector coefficients from the decomposition matrices `a` and `sigma`.
+///
+/// Since the coefficients depend on the witness index, they and all intermediate values are zeroized on drop.
+#[cfg(feature = "prover")]
+pub(crate) fn compute_p(
+    params: &Parameters,
+    a: &[Vec<Scalar>],
+    sigma: &[Vec<Scalar>],
+) -> Result<Zeroizing<Vec<Vec<Scalar>>>, ProofError> {
+    // Use repeated convolution
+    let mut p = Zeroizing::new(Vec::<Vec<Scalar>>::with_capacity(
+        usize::try_from(params.get_N()).map_err(|_| ProofError::InvalidParameter)?,
+    ));
+    let mut k_decomposed = vec![0; params.get_m() as usize];
+    for (gray_index, _, gray_new) in
+        GrayIterator::new(params.get_n(), params.get_m()).ok_or(ProofError::InvalidParameter)?
+    {
+        k_decomposed[gray_index] = gray_new;
+
+        // Set the initial coefficients using the first degree-one polynomial (`j = 0`)
+        let mut coefficients = Zeroizing::new(Vec::new());
+        coefficients.resize(
+            (params.get_m() as usize)
+                .checked_add(1)
+                .ok_or(ProofError::InvalidParameter)?,
+            Scalar::ZERO,
+        );
+        coefficients[0] = a[0][k_decomposed[0] as usize];
+        coefficients[1] = sigma[0][k_decomposed[0] as usize];
+
+        // Use convolution against each remaining degree-one polynomial
+        for j in 1..params.get_m() {
+            // For the degree-zero portion, simply multiply each coefficient accordingly
+            let degree_0_portion = Zeroizing::new(
+                coefficients
+                    .iter()
+                    .map(|c| a[j as usize][k_decomposed[j as usize] as usize] * c)
+                    .collect::<Vec<Scalar>>(),
+            );
+
+            // For the degree-one portion, we also need to increase each exponent by one
+            // Rotating the coefficients is fine here since the highest is always zero!
+            let mut shifted_coefficients = coefficients.clone();
+            shifted_coefficients.rotate_right(1);
+            let degree_1_portion = Zeroizing::new(
+                shifted_coefficients
+                    .iter()
+                    .map(|c| sigma[j as usize][k_decomposed[j as usize] as usize] * c)
+                    .collect::<Vec<Scalar>>(),
+            );
+
+            coefficients = Zeroizing::new(
+                degree_0_portion
+                    .iter()
+                    .zip(degree_1_portion.iter())
+                    .map(|(x, y)| x + y)
+                    .collect::<Vec<Scalar>>(),
+            );
+        }
+
+        p.push(coefficients.to_vec());
+    }
+
+    Ok(p)
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use alloc::{format, string::ToString, sync::Arc, vec, vec::Vec};
+
+    use curve25519_dalek::{ristretto::CompressedRistretto, traits::Identity, RistrettoPoint, Scalar};
+    use itertools::izip;
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::{CryptoRng, CryptoRngCore, RngCore, SeedableRng};
+    use subtle::ConstantTimeEq;
+    use zeroize::Zeroize;
+
+    use crate::{
+        proof::{
+            compute_p,
+            BatchMode,
+            DecompositionCommitments,
+            DeserializeLimits,
+            ProofError,
+            ProvingMode,
+            RingInfo,
+            VerifyScratch,
+            VerifyTuning,
+            SERIALIZED_BYTES,
+        },
+        transcript::VERSION,
+        util::OperationTiming,
+        InputSet,
+        Parameters,
+        Proof,
+        Statement,
+        Transcript,
+        Witness,
+    };
+
+    // Check that the serialized proof element size constant is correct
+    #[test]
+    fn test_serialized_bytes() {
+        // Check the scalar encoding size
+        assert_eq!(Scalar::ZERO.as_bytes().len(), SERIALIZED_BYTES);
+
+        // Check the group element encoding size
+        assert_eq!(RistrettoPoint::identity().compress().as_bytes().len(), SERIALIZED_BYTES);
+    }
+
+    #[test]
+    #[cfg(feature = "test-vectors")]
+    #[allow(non_snake_case)]
+    fn test_challenge() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(2, 4, 2, &mut rng);
+        let proof =
+            Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
+
+        // The challenge is nonzero and deterministic
+        let xi = proof.challenge(&statements[0], &mut transcripts[0].clone()).unwrap();
+        assert_ne!(xi, Scalar::ZERO);
+        assert_eq!(
+            proof.challenge(&statements[0], &mut transcripts[0].clone()).unwrap(),
+            xi
+        );
+
+        // The challenge depends on the statement and transcript
+        assert_ne!(
+            proof.challenge(&statements[1], &mut transcripts[0].clone()).unwrap(),
+            xi
+        );
+        assert_ne!(
+            proof
+                .challenge(&statements[0], &mut Transcript::new(b"Evil transcript"))
+                .unwrap(),
+            xi
+        );
+
+        // The proof must be valid for the parameters
+        let mut evil_proof = proof;
+        evil_proof.X.pop();
+        assert!(evil_proof
+            .challenge(&statements[0], &mut transcripts[0].clone())
+            .is_err());
+    }
+
+    #[test]
+    fn test_challenge_powers() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(2, 4, 2, &mut rng);
+        let proof =
+            Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
+
+        // There is a power for each digit, starting with one
+        let xi_powers = proof
+            .challenge_powers(&statements[0], &mut transcripts[0].clone())
+            .unwrap();
+        assert_eq!(xi_powers.len(), 5);
+        assert_eq!(xi_powers[0], Scalar::ONE);
+        assert_ne!(xi_powers[1], Scalar::ZERO);
+        for pair in xi_powers.windows(2) {
+            assert_eq!(pair[1], pair[0] * xi_powers[1]);
+        }
+
+        // The powers are deterministic, and depend on the statement and transcript
+        assert_eq!(
+            proof
+                .challenge_powers(&statements[0], &mut transcripts[0].clone())
+                .unwrap(),
+            xi_powers
+        );
+        assert_ne!(
+            proof
+                .challenge_powers(&statements[1], &mut transcripts[0].clone())
+                .unwrap(),
+            xi_powers
+        );
+        assert_ne!(
+            proof
+                .challenge_powers(&statements[0], &mut Transcript::new(b"Evil transcript"))
+                .unwrap(),
+            xi_powers
+        );
+
+        // The proof must be valid for the parameters
+        let mut evil_proof = proof;
+        evil_proof.f.pop();
+        assert!(evil_proof
+            .challenge_powers(&statements[0], &mut transcripts[0].clone())
+            .is_err());
+    }
+
+    #[test]
+    fn test_dimensions() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // The dimensions match the parameters used to generate the proof
+        for (n, m) in [(2, 2), (2, 4), (3, 2), (4, 3)] {
+            let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+            let proof =
+                Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
+            assert_eq!(proof.dimensions(), Some((m, n)));
+        }
+
+        let (witnesses, statements, transcripts) = generate_data(2, 4, 1, &mut rng);
+        let proof =
+            Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
+
+        // Inconsistent vector lengths have no dimensions
+        let mut evil_proof = proof.clone();
+        evil_proof.X.pop();
+        assert!(evil_proof.dimensions().is_none());
+
+        let mut evil_proof = proof.clone();
+        evil_proof.Y.pop();
+        assert!(evil_proof.dimensions().is_none());
+
+        let mut evil_proof = proof.clone();
+        evil_proof.f[1].push(Scalar::ONE);
+        assert!(evil_proof.dimensions().is_none());
+
+        // Degenerate and empty vectors have no dimensions
+        let mut evil_proof = proof.clone();
+        evil_proof.f.iter_mut().for_each(Vec::clear);
+        assert!(evil_proof.dimensions().is_none());
+
+        let mut evil_proof = proof;
+        evil_proof.X.clear();
+        evil_proof.Y.clear();
+        evil_proof.f.clear();
+        assert!(evil_proof.dimensions().is_none());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_single_digit() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        for n in [2, 3, 4] {
+            let (witnesses, statements, transcripts) = generate_data(n, 2, 1, &mut rng);
+            let mut proof =
+                Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
+
+            // Truncate the proof to a single digit
+            proof.X.truncate(1);
+            proof.Y.truncate(1);
+            proof.f.truncate(1);
+
+            // Single-digit proofs can't be deserialized or verified, since there are no such parameters
+            assert!(matches!(
+                Proof::from_bytes(&proof.to_bytes()),
+                Err(ProofError::FailedDeserialization)
+            ));
+            assert!(!proof.is_structurally_valid(statements[0].get_params()));
+            assert!(matches!(
+                proof.verify(&statements[0], &mut transcripts[0].clone()),
+                Err(ProofError::InvalidParameter)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_zeroize_prover_secrets() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Parameters::new(2, 4).unwrap();
+
+        // Secret-dependent prover matrices must be cleared on drop, so they must be zeroizable
+        let mut commitments = DecompositionCommitments::new(&params, 5, &mut rng, OperationTiming::Constant).unwrap();
+        let mut p = compute_p(&params, &commitments.a, &commitments.sigma).unwrap();
+        assert_eq!(p.len(), usize::try_from(params.get_N()).unwrap());
+
+        for matrix in [&mut commitments.a, &mut commitments.sigma, &mut p] {
+            assert!(!matrix.is_empty());
+            matrix.zeroize();
+            assert!(matrix.is_empty());
+        }
+    }
+
+    // Generate a batch of witnesses, statements, and transcripts
+    #[allow(non_snake_case)]
+    #[allow(clippy::arithmetic_side_effects)]
+    fn generate_data<R: CryptoRngCore>(
+        n: u32,
+        m: u32,
+        b: usize,
+        rng: &mut R,
+    ) -> (Vec<Witness>, Vec<Statement>, Vec<Transcript>) {
+        // Generate parameters
+        let params = Arc::new(Parameters::new(n, m).unwrap());
+
+        // Generate witnesses; for this test, we use adjacent indexes for simplicity
+        // This means the batch size must not exceed the input set size!
+        assert!(b <= usize::try_from(params.get_N()).unwrap());
+        let mut witnesses = Vec::with_capacity(b);
+        witnesses.push(Witness::random(&params, rng));
+        for _ in 1..b {
+            let r = Scalar::random(rng);
+            let l = (witnesses.last().unwrap().get_l() + 1) % params.get_N();
+            witnesses.push(Witness::new(&params, l, &r).unwrap());
+        }
+
+        // Generate input set from all witnesses
+        let mut M = (0..params.get_N())
+            .map(|_| RistrettoPoint::random(rng))
+            .collect::<Vec<RistrettoPoint>>();
+        for witness in &witnesses {
+            M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        }
+        let input_set = Arc::new(InputSet::new(&M));
+
+        // Generate statements
+        let mut statements = Vec::with_capacity(b);
+        for witness in &witnesses {
             let J = witness.compute_linking_tag();
             statements.push(Statement::new(&params, &input_set, &J).unwrap());
         }
@@ -996,270 +2637,1670 @@ mod test {
     }
 
     #[test]
-    #[cfg(feature = "rand")]
+    #[cfg(feature = "rand")]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate and verify a proof
+        let proof = Proof::prove(&witnesses[0], &statements[0], &mut transcripts[0].clone()).unwrap();
+        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_with_rng() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate and verify a proof
+        let proof =
+            Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
+        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "rand")]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_vartime() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate and verify a proof
+        let proof = Proof::prove_vartime(&witnesses[0], &statements[0], &mut transcripts[0].clone()).unwrap();
+        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_vartime_with_rng() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate and verify a proof
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_serialize_deserialize() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate and verify a proof
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+
+        // Serialize the proof
+        let serialized = proof.to_bytes();
+
+        // Deserialize the proof
+        let deserialized = Proof::from_bytes(&serialized).unwrap();
+        assert_eq!(deserialized, proof);
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_ct_eq() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a proof
+        let proof =
+            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
+        assert!(bool::from(proof.ct_eq(&proof.clone())));
+
+        // Proofs differing in any component are not equal
+        let mut evil_proof = proof.clone();
+        evil_proof.A = RistrettoPoint::random(&mut rng);
+        assert!(!bool::from(proof.ct_eq(&evil_proof)));
+
+        let mut evil_proof = proof.clone();
+        evil_proof.z += Scalar::ONE;
+        assert!(!bool::from(proof.ct_eq(&evil_proof)));
+
+        let mut evil_proof = proof.clone();
+        evil_proof.f[0][0] += Scalar::ONE;
+        assert!(!bool::from(proof.ct_eq(&evil_proof)));
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_proof_hash() {
+        extern crate std;
+        use std::collections::HashSet;
+
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // A deserialized proof is equal to the original and hashes identically
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+        let same = Proof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(same, proof);
+
+        // Proofs differing in any component are distinct
+        let mut evil_A = proof.clone();
+        evil_A.A = RistrettoPoint::random(&mut rng);
+        let mut evil_z = proof.clone();
+        evil_z.z += Scalar::ONE;
+        let mut evil_f = proof.clone();
+        evil_f.f[0][0] += Scalar::ONE;
+        let other = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+
+        // A verification cache deduplicates equal proofs
+        let mut set = HashSet::new();
+        assert!(set.insert(proof));
+        assert!(!set.insert(same));
+        for other in [evil_A, evil_z, evil_f, other] {
+            assert!(set.insert(other));
+        }
+        assert_eq!(set.len(), 5);
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_try_from_bytes() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // A valid serialization round-trips
+        let proof =
+            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
+        let serialized = proof.to_bytes();
+        assert_eq!(Proof::try_from(serialized.as_slice()).unwrap(), proof);
+
+        // Any truncation or extension is rejected
+        for len in 0..serialized.len() {
+            assert!(Proof::try_from(&serialized[..len]).is_err());
+        }
+        let mut extended = serialized.clone();
+        extended.extend_from_slice(&[0u8; SERIALIZED_BYTES]);
+        assert!(Proof::try_from(extended.as_slice()).is_err());
+
+        // Random data, with or without a valid header, is rejected without panicking
+        let header = &serialized[..16];
+        for len in (0..serialized.len().checked_mul(2).unwrap()).step_by(7) {
+            let mut bytes = vec![0u8; len];
+            rng.fill_bytes(&mut bytes);
+            assert!(Proof::try_from(bytes.as_slice()).is_err());
+
+            if len >= header.len() {
+                bytes[..header.len()].copy_from_slice(header);
+                assert!(Proof::try_from(bytes.as_slice()).is_err());
+            }
+        }
+
+        // Dimensions whose implied length overflows are rejected
+        let mut evil_header = header.to_vec();
+        evil_header[8..].copy_from_slice(&[u8::MAX; 8]);
+        assert!(Proof::try_from(evil_header.as_slice()).is_err());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_malformed_proof() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+
+        // Proofs with malformed dimensions, such as from unchecked `serde` deserialization, are rejected without
+        // panicking
+        let mut empty = proof.clone();
+        empty.f.clear();
+        let mut ragged = proof.clone();
+        ragged.f[0].clear();
+        let mut short = proof.clone();
+        short.X.pop();
+        for evil_proof in [empty, ragged, short] {
+            assert!(Proof::from_bytes(&evil_proof.to_bytes()).is_err());
+            assert!(!bool::from(evil_proof.ct_eq(&proof)));
+            assert!(evil_proof.dimensions().is_none());
+            assert!(evil_proof.verify(&statements[0], &mut transcripts[0].clone()).is_err());
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_from_bytes_with_limits() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+        let proof =
+            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
+        let serialized = proof.to_bytes();
+
+        // Limits that admit the dimensions don't change the result
+        for limits in [DeserializeLimits::default(), DeserializeLimits { max_n: n, max_m: m }] {
+            assert_eq!(Proof::from_bytes_with_limits(&serialized, limits).unwrap(), proof);
+        }
+
+        // Limits that exclude either dimension reject the proof
+        for limits in [DeserializeLimits { max_n: n - 1, max_m: m }, DeserializeLimits {
+            max_n: n,
+            max_m: m - 1,
+        }] {
+            assert!(matches!(
+                Proof::from_bytes_with_limits(&serialized, limits),
+                Err(ProofError::FailedDeserialization)
+            ));
+        }
+
+        // A huge encoded dimension with no data is rejected without allocating for it
+        let mut evil_header = serialized[..16].to_vec();
+        evil_header[12..].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(Proof::from_bytes(&evil_header).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "framed")]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_framed_bytes() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+        let proof =
+            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
+        let framed = proof.to_framed_bytes();
+        let limits = DeserializeLimits::default();
+
+        // The framed encoding round trips, and wraps the canonical encoding
+        assert_eq!(Proof::from_framed_bytes(&framed, limits).unwrap(), proof);
+        assert_eq!(&framed[16..], proof.to_bytes().as_slice());
+
+        // Any truncation is reported as such
+        for len in 0..framed.len() {
+            assert!(matches!(
+                Proof::from_framed_bytes(&framed[..len], limits),
+                Err(ProofError::Truncated)
+            ));
+        }
+
+        // Extra data is not truncation
+        let mut extended = framed.clone();
+        extended.push(0);
+        assert!(matches!(
+            Proof::from_framed_bytes(&extended, limits),
+            Err(ProofError::FailedDeserialization)
+        ));
+
+        // A corrupted body fails the checksum
+        let mut corrupted = framed.clone();
+        corrupted[framed.len() - 1] ^= 1;
+        assert!(matches!(
+            Proof::from_framed_bytes(&corrupted, limits),
+            Err(ProofError::FailedDeserialization)
+        ));
+
+        // A smaller proof can't pass for a truncated larger one, since the frame records the dimensions
+        let (small_witnesses, small_statements, mut small_transcripts) = generate_data(n, m - 1, 1, &mut rng);
+        let small_proof = Proof::prove_with_rng_vartime(
+            &small_witnesses[0],
+            &small_statements[0],
+            &mut rng,
+            &mut small_transcripts[0],
+        )
+        .unwrap();
+        let mut evil_framed = small_proof.to_framed_bytes();
+        evil_framed[4..8].copy_from_slice(&m.to_le_bytes());
+        assert!(matches!(
+            Proof::from_framed_bytes(&evil_framed, limits),
+            Err(ProofError::Truncated)
+        ));
+
+        // Limits apply to the frame dimensions
+        assert!(matches!(
+            Proof::from_framed_bytes(&framed, DeserializeLimits { max_n: n, max_m: m - 1 }),
+            Err(ProofError::FailedDeserialization)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    #[allow(clippy::arithmetic_side_effects, non_snake_case, non_upper_case_globals)]
+    fn test_read_write() {
+        extern crate std;
+        use std::io::{self, Cursor, Read};
+
+        // A reader that always fails
+        struct FailingReader;
+        impl Read for FailingReader {
+            fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+                Err(io::ErrorKind::Other.into())
+            }
+        }
+
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        const batch: usize = 2;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, batch, &mut rng);
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
+
+        // Writing produces the canonical encoding
+        let mut stream = Vec::new();
+        for proof in &proofs {
+            proof.write_to(&mut stream).unwrap();
+        }
+        assert_eq!(stream, [proofs[0].to_bytes(), proofs[1].to_bytes()].concat());
+
+        // Reading consumes exactly one proof at a time
+        let mut reader = Cursor::new(&stream);
+        for proof in &proofs {
+            assert_eq!(&Proof::read_from(&mut reader).unwrap(), proof);
+        }
+        assert_eq!(usize::try_from(reader.position()).unwrap(), stream.len());
+        assert!(matches!(Proof::read_from(&mut reader), Err(ProofError::Truncated)));
+
+        // Any truncation is reported as such
+        let serialized = proofs[0].to_bytes();
+        for len in 0..serialized.len() {
+            assert!(matches!(
+                Proof::read_from(&mut &serialized[..len]),
+                Err(ProofError::Truncated)
+            ));
+        }
+
+        // Limits are checked before reading any proof elements
+        let mut reader = Cursor::new(&serialized);
+        assert!(matches!(
+            Proof::read_from_with_limits(&mut reader, DeserializeLimits { max_n: n, max_m: m - 1 }),
+            Err(ProofError::FailedDeserialization)
+        ));
+        assert_eq!(reader.position(), 16);
+
+        // A huge encoded dimension with no data is reported as truncated without allocating for it
+        let mut evil_header = serialized[..16].to_vec();
+        evil_header[12..].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(matches!(
+            Proof::read_from(&mut evil_header.as_slice()),
+            Err(ProofError::Truncated)
+        ));
+
+        // Other encoding errors match those of deserializing from bytes
+        let mut unsupported = serialized.clone();
+        unsupported[0] ^= 1;
+        assert!(matches!(
+            Proof::read_from(&mut unsupported.as_slice()),
+            Err(ProofError::UnsupportedVersion { .. })
+        ));
+        let mut noncanonical = serialized.clone();
+        noncanonical[serialized.len() - 1] = 0xFF;
+        assert!(matches!(
+            Proof::read_from(&mut noncanonical.as_slice()),
+            Err(ProofError::FailedDeserialization)
+        ));
+
+        // Elements are parsed as they are read, so reading stops after the first invalid one
+        let mut invalid = serialized.clone();
+        invalid[16..48].fill(0xFF);
+        let mut reader = Cursor::new(&invalid);
+        assert!(matches!(
+            Proof::read_from(&mut reader),
+            Err(ProofError::FailedDeserialization)
+        ));
+        assert_eq!(reader.position(), 48);
+
+        // Reader failures are reported
+        assert!(matches!(
+            Proof::read_from(&mut FailingReader),
+            Err(ProofError::FailedRead)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_std_error() {
+        use alloc::boxed::Box;
+        use core::error::Error;
+
+        use crate::{parameters::ParameterError, statement::StatementError, witness::WitnessError};
+
+        // All error types can be propagated into a boxed error
+        fn propagate<E: Error + 'static>(error: E) -> Result<(), Box<dyn Error>> {
+            Err(error)?
+        }
+
+        for error in [
+            propagate(ProofError::InvalidParameter),
+            propagate(ParameterError::InvalidParameter),
+            propagate(StatementError::InvalidParameter),
+            propagate(WitnessError::InvalidParameter),
+        ] {
+            assert_eq!(error.unwrap_err().to_string(), "An invalid parameter was provided");
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_witness_key_mismatch() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Use a witness with the right index but a different signing key
+        let evil_witness = Witness::new(
+            witnesses[0].get_params(),
+            witnesses[0].get_l(),
+            &Scalar::random(&mut rng),
+        )
+        .unwrap();
+
+        // Proving fails in both timing modes with a precise error
+        for result in [
+            Proof::prove_with_rng(&evil_witness, &statements[0], &mut rng, &mut transcripts[0].clone()),
+            Proof::prove_with_rng_vartime(&evil_witness, &statements[0], &mut rng, &mut transcripts[0]),
+        ] {
+            let error = result.unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "Witness secret does not match ring key at index {}",
+                    witnesses[0].get_l()
+                )
+            );
+            assert!(matches!(error, ProofError::WitnessKeyMismatch { index } if index == witnesses[0].get_l()));
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_invalid_witness() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // A statement whose linking tag doesn't match the witness is a witness problem
+        let evil_statement = Statement::new(
+            statements[0].get_params(),
+            statements[0].get_input_set(),
+            &RistrettoPoint::random(&mut rng),
+        )
+        .unwrap();
+        for result in [
+            Proof::prove_with_rng(&witnesses[0], &evil_statement, &mut rng, &mut transcripts[0].clone()),
+            Proof::prove_with_rng_vartime(&witnesses[0], &evil_statement, &mut rng, &mut transcripts[0].clone()),
+        ] {
+            assert!(matches!(result, Err(ProofError::InvalidWitness)));
+        }
+
+        // A signing key that doesn't match its verification key is reported precisely
+        let evil_witness = Witness::new(
+            witnesses[0].get_params(),
+            witnesses[0].get_l(),
+            &Scalar::random(&mut rng),
+        )
+        .unwrap();
+        assert!(matches!(
+            Proof::prove_with_rng(&evil_witness, &statements[0], &mut rng, &mut transcripts[0].clone()),
+            Err(ProofError::WitnessKeyMismatch { .. })
+        ));
+
+        // Mismatched parameters are a configuration problem
+        let evil_params = Arc::new(Parameters::new(n, m + 1).unwrap());
+        let evil_witness = Witness::new(&evil_params, witnesses[0].get_l(), witnesses[0].get_r()).unwrap();
+        assert!(matches!(
+            Proof::prove_with_rng(&evil_witness, &statements[0], &mut rng, &mut transcripts[0].clone()),
+            Err(ProofError::InvalidParameter)
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verify_deterministic() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a valid proof and an invalid one
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+        let mut evil_proof = proof.clone();
+        evil_proof.z += Scalar::ONE;
+
+        // Replaying verification gives the same result and leaves the transcript in the same state
+        for proof in [&proof, &evil_proof] {
+            let mut transcript = transcripts[0].clone();
+            let result = proof.verify(&statements[0], &mut transcript).is_ok();
+            let mut challenge = [0u8; 32];
+            transcript.challenge_bytes(b"replay", &mut challenge);
+
+            for _ in 0..2 {
+                let mut replay_transcript = transcripts[0].clone();
+                assert_eq!(proof.verify(&statements[0], &mut replay_transcript).is_ok(), result);
+                let mut replay_challenge = [0u8; 32];
+                replay_transcript.challenge_bytes(b"replay", &mut replay_challenge);
+                assert_eq!(replay_challenge, challenge);
+            }
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verify_and_tag() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // A valid proof yields the linking tag
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+        assert_eq!(
+            proof
+                .verify_and_tag(&statements[0], &mut transcripts[0].clone())
+                .unwrap(),
+            witnesses[0].compute_linking_tag()
+        );
+
+        // An invalid proof yields an error instead
+        let mut evil_proof = proof;
+        evil_proof.z += Scalar::ONE;
+        assert!(evil_proof
+            .verify_and_tag(&statements[0], &mut transcripts[0].clone())
+            .is_err());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verify_with_shared_nonce() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a valid proof and an invalid one
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+        let mut evil_proof = proof.clone();
+        evil_proof.z += Scalar::ONE;
+
+        // Independent verifiers using the same nonce agree, as do verifiers using a different nonce
+        for nonce in [b"shared nonce".as_slice(), b"other nonce".as_slice(), &[]] {
+            for _ in 0..2 {
+                assert!(proof
+                    .verify_with_shared_nonce(&statements[0], &mut transcripts[0].clone(), nonce)
+                    .is_ok());
+                assert!(evil_proof
+                    .verify_with_shared_nonce(&statements[0], &mut transcripts[0].clone(), nonce)
+                    .is_err());
+            }
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verify_requiring_members() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a proof
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+        let M = statements[0].get_input_set().get_keys();
+
+        // The proof verifies if all required keys are present
+        for required in [&[][..], &M[..1], &[M[3], M[1], M[3]], &M] {
+            assert!(proof
+                .verify_requiring_members(&statements[0], required, &mut transcripts[0].clone())
+                .is_ok());
+        }
+
+        // The proof is rejected if any required key is missing
+        let missing = RistrettoPoint::random(&mut rng);
+        assert!(matches!(
+            proof.verify_requiring_members(&statements[0], &[M[0], missing, M[1]], &mut transcripts[0].clone()),
+            Err(ProofError::MissingRequiredMember { index: 1 })
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_unsupported_version() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a proof
+        let proof =
+            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
+        let mut serialized = proof.to_bytes();
+        assert_eq!(serialized[..8], VERSION.to_le_bytes());
+
+        // Change the version, which must be rejected
+        let evil_version = VERSION + 1;
+        serialized[..8].copy_from_slice(&evil_version.to_le_bytes());
+        assert!(matches!(
+            Proof::from_bytes(&serialized),
+            Err(ProofError::UnsupportedVersion { version }) if version == evil_version
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_batch() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        const batch: usize = 3; // batch size
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
+
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
+
+        // Verify the batch with and without blame
+        assert!(Proof::verify_batch(&statements, &proofs, &mut transcripts.clone()).is_ok());
+        assert!(Proof::verify_batch_with_single_blame(&statements, &proofs, &mut transcripts.clone()).is_ok());
+        assert!(Proof::verify_batch_with_full_blame(&statements, &proofs, &mut transcripts).is_ok());
+    }
+
+    #[test]
+    fn test_prove_verify_empty_batch() {
+        // An empty batch is valid by definition
+        assert!(Proof::verify_batch::<Transcript>(&[], &[], &mut []).is_ok());
+        assert!(Proof::verify_batch_with_single_blame::<Transcript>(&[], &[], &mut []).is_ok());
+        assert!(Proof::verify_batch_with_full_blame::<Transcript>(&[], &[], &mut []).is_ok());
+
+        // Sampling an empty batch doesn't use the generator
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let rng_before = rng.clone();
+        assert!(Proof::verify_batch_sampled::<Transcript, _>(&[], &[], &mut [], 4, &mut rng).is_ok());
+        assert_eq!(rng, rng_before);
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_mismatched_batch() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        const batch: usize = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, batch, &mut rng);
+
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
+
+        // Each way of mismatching the batch lengths is rejected by every batch verifier, without blaming a proof
+        for (statements, proofs, transcripts) in [
+            (&statements[..2], &proofs[..], &transcripts[..]),
+            (&statements[..], &proofs[..2], &transcripts[..]),
+            (&statements[..], &proofs[..], &transcripts[..2]),
+            (&statements[..0], &proofs[..], &transcripts[..0]),
+        ] {
+            let transcripts = &mut transcripts.to_vec();
+            for result in [
+                Proof::verify_batch(statements, proofs, transcripts),
+                Proof::verify_batch_with_single_blame(statements, proofs, transcripts),
+                Proof::verify_batch_with_full_blame(statements, proofs, transcripts),
+                Proof::verify_batch_with_mode(statements, proofs, transcripts, BatchMode::FailFast),
+                Proof::verify_batch_sampled(statements, proofs, transcripts, 1, &mut rng),
+            ] {
+                assert!(matches!(result, Err(ProofError::InvalidParameter)));
+            }
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_invalid_batch() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        const batch: usize = 3; // batch size
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
+
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
+
+        // Manipulate a transcript so the corresponding proof is invalid
+        transcripts[0] = Transcript::new(b"Evil transcript");
+
+        // Verification should fail
+        assert!(Proof::verify_batch(&statements, &proofs, &mut transcripts).is_err());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_invalid_batch_single_blame() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+
+        // Test against batches of even and odd size
+        for batch in [4, 5] {
+            let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+            let (witnesses, statements, transcripts) = generate_data(n, m, batch, &mut rng);
+
+            // Generate the proofs
+            let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+                .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+                .collect::<Vec<Proof>>();
+
+            // Iteratively manipulate each transcript to make the corresponding proof invalid
+            for i in 0..proofs.len() {
+                let mut evil_transcripts = transcripts.clone();
+                evil_transcripts[i] = Transcript::new(b"Evil transcript");
+
+                // Verification should fail and blame the correct proof
+                let error =
+                    Proof::verify_batch_with_single_blame(&statements, &proofs, &mut evil_transcripts).unwrap_err();
+                if let ProofError::FailedBatchVerificationWithSingleBlame { index: Some(index) } = error {
+                    assert_eq!(index, i);
+                } else {
+                    panic!();
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify_invalid_batch_full_blame() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        const batch: usize = 4;
+        const failures: [usize; 2] = [1, 3];
+
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
+
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
+
+        // Manipulate some of the transcripts to make the corresponding proofs invalid
+        for i in failures {
+            transcripts[i] = Transcript::new(b"Evil transcript");
+        }
+
+        // Verification should fail and blame the correct proof
+        let error = Proof::verify_batch_with_full_blame(&statements, &proofs, &mut transcripts).unwrap_err();
+        if let ProofError::FailedBatchVerificationWithFullBlame { indexes } = error {
+            assert_eq!(indexes, failures);
+        } else {
+            panic!();
+        }
+    }
+
+    #[test]
+    #[allow(clippy::arithmetic_side_effects, non_snake_case, non_upper_case_globals)]
+    fn test_verify_batch_sampled() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        const batch: usize = 3;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, batch, &mut rng);
+
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
+
+        // A valid batch is accepted for any sample size, and the transcripts are updated as in batch verification
+        let mut batch_transcripts = transcripts.clone();
+        assert!(Proof::verify_batch(&statements, &proofs, &mut batch_transcripts).is_ok());
+        for sample_size in [0, 1, batch, batch + 1] {
+            let mut sampled_transcripts = transcripts.clone();
+            assert!(
+                Proof::verify_batch_sampled(&statements, &proofs, &mut sampled_transcripts, sample_size, &mut rng)
+                    .is_ok()
+            );
+            for (sampled, batched) in sampled_transcripts.iter_mut().zip(batch_transcripts.clone().iter_mut()) {
+                let mut sampled_bytes = [0u8; 32];
+                let mut batched_bytes = [0u8; 32];
+                sampled.challenge_bytes(b"test", &mut sampled_bytes);
+                batched.challenge_bytes(b"test", &mut batched_bytes);
+                assert_eq!(sampled_bytes, batched_bytes);
+            }
+        }
+        assert!(Proof::verify_batch_sampled::<Transcript, _>(&[], &[], &mut [], 1, &mut rng).is_ok());
+
+        // An invalid proof is rejected for any sample size
+        let mut evil_transcripts = transcripts.clone();
+        evil_transcripts[1] = Transcript::new(b"Evil transcript");
+        for sample_size in [0, 1, batch] {
+            assert!(Proof::verify_batch_sampled(
+                &statements,
+                &proofs,
+                &mut evil_transcripts.clone(),
+                sample_size,
+                &mut rng
+            )
+            .is_err());
+        }
+
+        // When every proof is sampled, the invalid one is identified
+        assert!(matches!(
+            Proof::verify_batch_sampled(&statements, &proofs, &mut evil_transcripts.clone(), batch, &mut rng),
+            Err(ProofError::FailedBatchVerificationWithSingleBlame { index: Some(1) })
+        ));
+
+        // Mismatched lengths are rejected
+        assert!(matches!(
+            Proof::verify_batch_sampled(&statements[1..], &proofs, &mut transcripts.clone(), 1, &mut rng),
+            Err(ProofError::InvalidParameter)
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::arithmetic_side_effects, non_snake_case, non_upper_case_globals)]
+    fn test_verify_stream() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Use two input sets, so some batches mix them
+        let mut items = Vec::new();
+        for _ in 0..2 {
+            let (witnesses, statements, transcripts) = generate_data(n, m, 3, &mut rng);
+            for (witness, statement, transcript) in izip!(witnesses, statements, transcripts) {
+                let proof =
+                    Proof::prove_with_rng_vartime(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+                items.push((statement, proof, transcript));
+            }
+        }
+
+        // Make some proofs invalid
+        let expected = [true, false, true, true, true, false];
+        for (item, valid) in items.iter_mut().zip(expected) {
+            if !valid {
+                item.2 = Transcript::new(b"Evil transcript");
+            }
+        }
+
+        // Each batch size gives the correct result for each item
+        for batch_size in [0, 1, 2, 3, 4, 10] {
+            assert_eq!(
+                Proof::verify_stream(items.clone(), batch_size).collect::<Vec<bool>>(),
+                expected
+            );
+        }
+        assert_eq!(
+            Proof::verify_stream(Vec::<(Statement, Proof, Transcript)>::new(), 4).next(),
+            None
+        );
+
+        // Items are only consumed as results are needed
+        let mut consumed = 0;
+        let mut stream = Proof::verify_stream(items.iter().cloned().inspect(|_| consumed += 1), 2);
+        assert_eq!(stream.next(), Some(true));
+        assert_eq!(stream.next(), Some(false));
+        assert_eq!(stream.next(), Some(true));
+        drop(stream);
+        assert_eq!(consumed, 4);
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verify_in() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 2, &mut rng);
+        let proofs = witnesses
+            .iter()
+            .zip(statements.iter())
+            .zip(transcripts.iter())
+            .map(|((witness, statement), transcript)| {
+                Proof::prove_with_rng(witness, statement, &mut rng, &mut transcript.clone()).unwrap()
+            })
+            .collect::<Vec<Proof>>();
+
+        // A single scratch space can be reused, and its buffers keep their capacity
+        let mut scratch = VerifyScratch::new();
+        for _ in 0..2 {
+            for (proof, statement, transcript) in izip!(proofs.iter(), statements.iter(), transcripts.iter()) {
+                assert!(proof
+                    .verify_in(statement, &mut transcript.clone(), &mut scratch)
+                    .is_ok());
+                assert!(proof
+                    .verify_in(statement, &mut Transcript::new(b"Evil transcript"), &mut scratch)
+                    .is_err());
+            }
+        }
+        assert!(scratch.scalars.capacity() >= usize::try_from(Parameters::new(n, m).unwrap().get_N()).unwrap());
+
+        // A failed verification doesn't affect later ones
+        assert!(proofs[0]
+            .verify_in(&statements[1], &mut transcripts[1].clone(), &mut scratch)
+            .is_err());
+        assert!(proofs[1]
+            .verify_in(&statements[1], &mut transcripts[1].clone(), &mut scratch)
+            .is_ok());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_transcript_composition() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, _) = generate_data(n, m, 2, &mut rng);
+
+        // An outer protocol absorbs its own data before the sub-proofs
+        let mut outer = Transcript::new(b"Outer protocol");
+        outer.append_message(b"outer data", b"some data");
+
+        // Generate both proofs under one transcript
+        let mut prover_transcript = outer.clone();
+        let proofs = witnesses
+            .iter()
+            .zip(statements.iter())
+            .map(|(witness, statement)| {
+                Proof::prove_with_rng(witness, statement, &mut rng, &mut prover_transcript).unwrap()
+            })
+            .collect::<Vec<Proof>>();
+
+        // Verify both proofs in the same order
+        let mut verifier_transcript = outer.clone();
+        for (proof, statement) in proofs.iter().zip(statements.iter()) {
+            assert!(proof.verify(statement, &mut verifier_transcript).is_ok());
+        }
+
+        // The outer protocol derives the same challenge on both sides
+        let mut prover_challenge = [0u8; 32];
+        let mut verifier_challenge = [0u8; 32];
+        prover_transcript.challenge_bytes(b"outer challenge", &mut prover_challenge);
+        verifier_transcript.challenge_bytes(b"outer challenge", &mut verifier_challenge);
+        assert_eq!(prover_challenge, verifier_challenge);
+
+        // The sub-proofs must be verified in order, under the outer transcript
+        let mut evil_transcript = outer;
+        assert!(proofs[1].verify(&statements[1], &mut evil_transcript).is_err());
+        assert!(proofs[0]
+            .verify(&statements[0], &mut Transcript::new(b"Outer protocol"))
+            .is_err());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verify_batch_with_mode() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        const batch: usize = 4;
+        const failures: [usize; 2] = [1, 3];
+
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
+
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
+
+        // Valid and empty batches verify in both modes
+        for mode in [BatchMode::Full, BatchMode::FailFast] {
+            assert!(Proof::verify_batch_with_mode(&statements, &proofs, &mut transcripts.clone(), mode).is_ok());
+            assert!(Proof::verify_batch_with_mode::<Transcript>(&[], &[], &mut [], mode).is_ok());
+
+            // Mismatched batches are rejected before any verification
+            assert!(matches!(
+                Proof::verify_batch_with_mode(&statements, &proofs[1..], &mut transcripts.clone(), mode),
+                Err(ProofError::InvalidParameter)
+            ));
+        }
+
+        // Manipulate some of the transcripts to make the corresponding proofs invalid
+        for i in failures {
+            transcripts[i] = Transcript::new(b"Evil transcript");
+        }
+
+        // Full mode fails, and fail-fast mode blames the first invalid proof
+        assert!(matches!(
+            Proof::verify_batch_with_mode(&statements, &proofs, &mut transcripts.clone(), BatchMode::Full),
+            Err(ProofError::FailedVerification)
+        ));
+        assert!(matches!(
+            Proof::verify_batch_with_mode(&statements, &proofs, &mut transcripts, BatchMode::FailFast),
+            Err(ProofError::FailedBatchVerificationWithSingleBlame { index: Some(1) })
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_evil_message() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a proof
+        let proof =
+            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
+
+        // Generate a modified transcript
+        let mut evil_transcript = Transcript::new(b"Evil transcript");
+
+        // Attempt to verify the proof against the new statement, which should fail
+        assert!(proof.verify(&statements[0], &mut evil_transcript).is_err());
+    }
+
+    #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify() {
+    fn test_evil_input_set() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
         let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
 
-        // Generate and verify a proof
-        let proof = Proof::prove(&witnesses[0], &statements[0], &mut transcripts[0].clone()).unwrap();
-        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+        // Generate a proof
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+
+        // Generate a statement with a modified input set
+        let mut M = statements[0].get_input_set().get_keys().to_vec();
+        let index = usize::try_from((witnesses[0].get_l() + 1) % witnesses[0].get_params().get_N()).unwrap();
+        M[index] = RistrettoPoint::random(&mut rng);
+        let evil_input_set = Arc::new(InputSet::new(&M));
+        let evil_statement =
+            Statement::new(statements[0].get_params(), &evil_input_set, statements[0].get_J()).unwrap();
+
+        // Attempt to verify the proof against the new statement, which should fail
+        assert!(proof.verify(&evil_statement, &mut transcripts[0]).is_err());
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify_with_rng() {
+    fn test_evil_linking_tag() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
         let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
 
-        // Generate and verify a proof
-        let proof =
-            Proof::prove_with_rng(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone()).unwrap();
-        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+        // Generate a proof
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+
+        // Generate a statement with a modified linking tag
+        let evil_statement = Statement::new(
+            statements[0].get_params(),
+            statements[0].get_input_set(),
+            &RistrettoPoint::random(&mut rng),
+        )
+        .unwrap();
+
+        // Attempt to verify the proof against the new statement, which should fail
+        assert!(proof.verify(&evil_statement, &mut transcripts[0]).is_err());
     }
 
     #[test]
-    #[cfg(feature = "rand")]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify_vartime() {
+    fn test_structurally_valid() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
         let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+        let params = statements[0].get_params();
 
-        // Generate and verify a proof
-        let proof = Proof::prove_vartime(&witnesses[0], &statements[0], &mut transcripts[0].clone()).unwrap();
-        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
+        // Generate a proof, which is structurally valid
+        let proof =
+            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
+        assert!(proof.is_structurally_valid(params));
+
+        // The proof is not structurally valid for different parameters
+        assert!(!proof.is_structurally_valid(&Parameters::new(n + 1, m).unwrap()));
+        assert!(!proof.is_structurally_valid(&Parameters::new(n, m + 1).unwrap()));
+
+        // Truncate each vector in turn
+        let mut evil_proof = proof.clone();
+        evil_proof.X.pop();
+        assert!(!evil_proof.is_structurally_valid(params));
+
+        let mut evil_proof = proof.clone();
+        evil_proof.Y.pop();
+        assert!(!evil_proof.is_structurally_valid(params));
+
+        let mut evil_proof = proof.clone();
+        evil_proof.f.pop();
+        assert!(!evil_proof.is_structurally_valid(params));
+
+        let mut evil_proof = proof.clone();
+        evil_proof.f[1].push(Scalar::ONE);
+        assert!(!evil_proof.is_structurally_valid(params));
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify_vartime_with_rng() {
+    fn test_evil_f_dimensions() {
         // Generate data
-        const n: u32 = 2;
-        const m: u32 = 4;
+        const n: u32 = 3;
+        const m: u32 = 2;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
         let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
 
-        // Generate and verify a proof
+        // Generate a proof
         let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
             .unwrap();
+
+        // Truncate each `f` row; verification must fail cleanly
+        let mut evil_proof = proof.clone();
+        for f_row in &mut evil_proof.f {
+            f_row.truncate(1);
+        }
+        assert!(matches!(
+            evil_proof.verify(&statements[0], &mut transcripts[0].clone()),
+            Err(ProofError::InvalidParameter)
+        ));
+
+        // Remove all `f` rows; verification must fail cleanly
+        let mut evil_proof = proof.clone();
+        evil_proof.f.clear();
+        assert!(matches!(
+            evil_proof.verify(&statements[0], &mut transcripts[0].clone()),
+            Err(ProofError::InvalidParameter)
+        ));
+
+        // Extend an `f` row; batch verification must also fail cleanly
+        let mut evil_proof = proof.clone();
+        evil_proof.f[0].push(Scalar::ONE);
+        assert!(matches!(
+            Proof::verify_batch(&statements, &[evil_proof], &mut transcripts.clone()),
+            Err(ProofError::InvalidParameter)
+        ));
+
+        // The original proof is still valid
         assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_serialize_deserialize() {
+    fn test_verify_with_tuning() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
 
-        // Generate and verify a proof
+        // Generate a proof
         let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
             .unwrap();
-        assert!(proof.verify(&statements[0], &mut transcripts[0]).is_ok());
 
-        // Serialize the proof
-        let serialized = proof.to_bytes();
+        // All window sizes must agree
+        for msm_window in [None, Some(1), Some(4), Some(8), Some(16)] {
+            let tuning = VerifyTuning {
+                msm_window,
+                ..VerifyTuning::default()
+            };
 
-        // Deserialize the proof
-        let deserialized = Proof::from_bytes(&serialized).unwrap();
-        assert_eq!(deserialized, proof);
+            assert!(proof
+                .verify_with_tuning(&statements[0], &mut transcripts[0].clone(), tuning)
+                .is_ok());
+            assert!(proof
+                .verify_with_tuning(&statements[0], &mut Transcript::new(b"Evil transcript"), tuning)
+                .is_err());
+        }
+
+        // Invalid window sizes are rejected
+        for msm_window in [Some(0), Some(17)] {
+            let tuning = VerifyTuning {
+                msm_window,
+                ..VerifyTuning::default()
+            };
+
+            assert!(proof
+                .verify_with_tuning(&statements[0], &mut transcripts[0].clone(), tuning)
+                .is_err());
+        }
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify_batch() {
+    fn test_verify_chunked() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
-        const batch: usize = 3; // batch size
+        const batch: usize = 3;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
+        let (witnesses, statements, transcripts) = generate_data(n, m, batch, &mut rng);
 
-        // Generate the proofs
-        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
-            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+        // Generate proofs, one of which is invalid
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.iter())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, &mut t.clone()).unwrap())
             .collect::<Vec<Proof>>();
+        let mut evil_proofs = proofs.clone();
+        evil_proofs[1].z += Scalar::ONE;
+
+        // All chunk sizes must agree with unchunked verification, including those that do not divide the input set size
+        // and those larger than it; the largest must be clamped rather than used to size the final check vector
+        for chunk_size in [1, 3, 5, 15, 16, 17, 1000, usize::MAX] {
+            assert!(proofs[0]
+                .verify_chunked(&statements[0], &mut transcripts[0].clone(), chunk_size)
+                .is_ok());
+            assert!(proofs[0]
+                .verify_chunked(&statements[0], &mut Transcript::new(b"Evil transcript"), chunk_size)
+                .is_err());
+
+            // Batches can also be chunked
+            for msm_window in [None, Some(4)] {
+                let tuning = VerifyTuning {
+                    msm_window,
+                    chunk_size: Some(chunk_size),
+                };
+                assert!(
+                    Proof::verify_batch_with_tuning(&statements, &proofs, &mut transcripts.clone(), tuning).is_ok()
+                );
+                assert!(
+                    Proof::verify_batch_with_tuning(&statements, &evil_proofs, &mut transcripts.clone(), tuning)
+                        .is_err()
+                );
+            }
+        }
 
-        // Verify the batch with and without blame
-        assert!(Proof::verify_batch(&statements, &proofs, &mut transcripts.clone()).is_ok());
-        assert!(Proof::verify_batch_with_single_blame(&statements, &proofs, &mut transcripts.clone()).is_ok());
-        assert!(Proof::verify_batch_with_full_blame(&statements, &proofs, &mut transcripts).is_ok());
+        // The chunk size must be nonzero
+        assert!(proofs[0]
+            .verify_chunked(&statements[0], &mut transcripts[0].clone(), 0)
+            .is_err());
     }
 
     #[test]
-    fn test_prove_verify_empty_batch() {
-        // An empty batch is valid by definition
-        assert!(Proof::verify_batch(&[], &[], &mut []).is_ok());
-        assert!(Proof::verify_batch_with_single_blame(&[], &[], &mut []).is_ok());
-        assert!(Proof::verify_batch_with_full_blame(&[], &[], &mut []).is_ok());
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_verify_with_ring_info() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a proof
+        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
+            .unwrap();
+
+        // The ring hits the cap
+        let (result, ring_info) = proof.verify_with_ring_info(&statements[0], &mut transcripts[0].clone(), 16);
+        assert!(result.is_ok());
+        assert_eq!(ring_info, RingInfo {
+            n,
+            m,
+            N: 16,
+            is_max: true
+        });
+
+        // The ring does not hit the cap
+        let (result, ring_info) = proof.verify_with_ring_info(&statements[0], &mut transcripts[0].clone(), 32);
+        assert!(result.is_ok());
+        assert!(!ring_info.is_max);
+
+        // Ring information is reported even if verification fails
+        let (result, ring_info) =
+            proof.verify_with_ring_info(&statements[0], &mut Transcript::new(b"Evil transcript"), 16);
+        assert!(result.is_err());
+        assert!(ring_info.is_max);
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify_invalid_batch() {
+    fn test_message_hasher() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
-        const batch: usize = 3; // batch size
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
-
-        // Generate the proofs
-        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
-            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
-            .collect::<Vec<Proof>>();
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Hash-to-scalar functions using different domains
+        let hasher = |message: &[u8]| {
+            let mut bytes = [0u8; 64];
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(b"Test hasher");
+            hasher.update(message);
+            hasher.finalize_xof().fill(&mut bytes);
+            Scalar::from_bytes_mod_order_wide(&bytes)
+        };
+        let evil_hasher = |message: &[u8]| {
+            let mut bytes = [0u8; 64];
+            let mut hasher = blake3::Hasher::new();
+            hasher.update(b"Evil hasher");
+            hasher.update(message);
+            hasher.finalize_xof().fill(&mut bytes);
+            Scalar::from_bytes_mod_order_wide(&bytes)
+        };
 
-        // Manipulate a transcript so the corresponding proof is invalid
-        transcripts[0] = Transcript::new(b"Evil transcript");
+        // Generate a proof
+        let message = b"Test message";
+        let proof = Proof::prove_with_rng_and_message_hasher(
+            &witnesses[0],
+            &statements[0],
+            message,
+            hasher,
+            &mut rng,
+            &mut transcripts[0].clone(),
+        )
+        .unwrap();
 
-        // Verification should fail
-        assert!(Proof::verify_batch(&statements, &proofs, &mut transcripts).is_err());
+        // The proof verifies only with the same hasher and message
+        assert!(proof
+            .verify_with_message_hasher(&statements[0], message, hasher, &mut transcripts[0].clone())
+            .is_ok());
+        assert!(proof
+            .verify_with_message_hasher(&statements[0], message, evil_hasher, &mut transcripts[0].clone())
+            .is_err());
+        assert!(proof
+            .verify_with_message_hasher(&statements[0], b"Evil message", hasher, &mut transcripts[0].clone())
+            .is_err());
+        assert!(proof.verify(&statements[0], &mut transcripts[0].clone()).is_err());
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify_invalid_batch_single_blame() {
+    fn test_message_digest() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Generate a proof binding the digest of a message
+        let digest = *blake3::hash(b"Test message").as_bytes();
+        let proof = Proof::prove_with_rng_and_message_digest(
+            &witnesses[0],
+            &statements[0],
+            &digest,
+            &mut rng,
+            &mut transcripts[0].clone(),
+        )
+        .unwrap();
 
-        // Test against batches of even and odd size
-        for batch in [4, 5] {
-            let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-            let (witnesses, statements, transcripts) = generate_data(n, m, batch, &mut rng);
-
-            // Generate the proofs
-            let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
-                .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
-                .collect::<Vec<Proof>>();
+        // The proof verifies only with the same digest
+        assert!(proof
+            .verify_with_message_digest(&statements[0], &digest, &mut transcripts[0].clone())
+            .is_ok());
+        assert!(proof
+            .verify_with_message_digest(
+                &statements[0],
+                blake3::hash(b"Evil message").as_bytes(),
+                &mut transcripts[0].clone()
+            )
+            .is_err());
+        assert!(proof.verify(&statements[0], &mut transcripts[0].clone()).is_err());
 
-            // Iteratively manipulate each transcript to make the corresponding proof invalid
-            for i in 0..proofs.len() {
-                let mut evil_transcripts = transcripts.clone();
-                evil_transcripts[i] = Transcript::new(b"Evil transcript");
+        // The digest label is distinct from that used to bind a message directly
+        let mut message_transcript = transcripts[0].clone();
+        message_transcript.append_message(b"message", &digest);
+        assert!(proof.verify(&statements[0], &mut message_transcript).is_err());
+    }
 
-                // Verification should fail and blame the correct proof
-                let error =
-                    Proof::verify_batch_with_single_blame(&statements, &proofs, &mut evil_transcripts).unwrap_err();
-                if let ProofError::FailedBatchVerificationWithSingleBlame { index: Some(index) } = error {
-                    assert_eq!(index, i);
-                } else {
-                    panic!();
-                }
-            }
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_with_mode() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+
+        // Constant-time proving is the default
+        assert_eq!(ProvingMode::default(), ProvingMode::ConstantTime);
+
+        // Both modes produce valid proofs
+        for mode in [ProvingMode::ConstantTime, ProvingMode::Variable] {
+            let proof = Proof::prove_with_mode(
+                &witnesses[0],
+                &statements[0],
+                &mut rng,
+                &mut transcripts[0].clone(),
+                mode,
+            )
+            .unwrap();
+            assert!(proof.verify(&statements[0], &mut transcripts[0].clone()).is_ok());
         }
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_prove_verify_invalid_batch_full_blame() {
+    fn test_prove_from_entropy() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
-        const batch: usize = 4;
-        const failures: [usize; 2] = [1, 3];
-
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
 
-        // Generate the proofs
-        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
-            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
-            .collect::<Vec<Proof>>();
+        // Gather entropy
+        let mut entropy = [0u8; Proof::MIN_ENTROPY_BYTES];
+        rng.fill_bytes(&mut entropy);
 
-        // Manipulate some of the transcripts to make the corresponding proofs invalid
-        for i in failures {
-            transcripts[i] = Transcript::new(b"Evil transcript");
-        }
+        // The proof is valid and depends only on the entropy
+        let proof =
+            Proof::prove_from_entropy(&witnesses[0], &statements[0], &entropy, &mut transcripts[0].clone()).unwrap();
+        assert!(proof.verify(&statements[0], &mut transcripts[0].clone()).is_ok());
+        assert_eq!(
+            Proof::prove_from_entropy(&witnesses[0], &statements[0], &entropy, &mut transcripts[0].clone()).unwrap(),
+            proof
+        );
 
-        // Verification should fail and blame the correct proof
-        let error = Proof::verify_batch_with_full_blame(&statements, &proofs, &mut transcripts).unwrap_err();
-        if let ProofError::FailedBatchVerificationWithFullBlame { indexes } = error {
-            assert_eq!(indexes, failures);
-        } else {
-            panic!();
-        }
+        let mut other_entropy = entropy.to_vec();
+        other_entropy.push(0);
+        assert_ne!(
+            Proof::prove_from_entropy(
+                &witnesses[0],
+                &statements[0],
+                &other_entropy,
+                &mut transcripts[0].clone()
+            )
+            .unwrap(),
+            proof
+        );
+
+        // The entropy buffer must be long enough
+        assert!(matches!(
+            Proof::prove_from_entropy(
+                &witnesses[0],
+                &statements[0],
+                &entropy[1..],
+                &mut transcripts[0].clone()
+            ),
+            Err(ProofError::InvalidParameter)
+        ));
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_evil_message() {
+    fn test_aux() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+        let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
 
         // Generate a proof
-        let proof =
-            Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0]).unwrap();
-
-        // Generate a modified transcript
-        let mut evil_transcript = Transcript::new(b"Evil transcript");
+        let aux: &[(&[u8], &[u8])] = &[(b"amount", b"100"), (b"commitment", b"C"), (b"timestamp", b"12345")];
+        let proof = Proof::prove_with_rng_and_aux(
+            &witnesses[0],
+            &statements[0],
+            aux,
+            &mut rng,
+            &mut transcripts[0].clone(),
+        )
+        .unwrap();
 
-        // Attempt to verify the proof against the new statement, which should fail
-        assert!(proof.verify(&statements[0], &mut evil_transcript).is_err());
+        // The proof verifies only with the same entries in the same order
+        assert!(proof
+            .verify_with_aux(&statements[0], aux, &mut transcripts[0].clone())
+            .is_ok());
+        let evil_auxes: [&[(&[u8], &[u8])]; 5] = [
+            &[(b"commitment", b"C"), (b"amount", b"100"), (b"timestamp", b"12345")],
+            &[(b"amount", b"101"), (b"commitment", b"C"), (b"timestamp", b"12345")],
+            &[(b"amounts", b"100"), (b"commitment", b"C"), (b"timestamp", b"12345")],
+            &[(b"amount", b"100"), (b"commitment", b"C")],
+            &[(b"amount1", b"00"), (b"commitment", b"C"), (b"timestamp", b"12345")],
+        ];
+        for evil_aux in evil_auxes {
+            assert!(proof
+                .verify_with_aux(&statements[0], evil_aux, &mut transcripts[0].clone())
+                .is_err());
+        }
+        assert!(proof.verify(&statements[0], &mut transcripts[0].clone()).is_err());
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_evil_input_set() {
+    fn test_verify_batch_messages() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
+        const batch: usize = 3;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+        let (witnesses, statements, transcripts) = generate_data(n, m, batch, &mut rng);
+
+        // Bind a different message into each proof transcript, leaving one without a message
+        let messages: [Option<&[u8]>; batch] = [Some(b"first message"), None, Some(b"third message")];
+        let transcripts = transcripts
+            .into_iter()
+            .zip(messages.iter())
+            .map(|(mut transcript, message)| {
+                if let Some(message) = message {
+                    transcript.append_message(b"message", message);
+                }
+                transcript
+            })
+            .collect::<Vec<Transcript>>();
 
-        // Generate a proof
-        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
-            .unwrap();
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.iter())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, &mut t.clone()).unwrap())
+            .collect::<Vec<Proof>>();
 
-        // Generate a statement with a modified input set
-        let mut M = statements[0].get_input_set().get_keys().to_vec();
-        let index = ((witnesses[0].get_l() + 1) % witnesses[0].get_params().get_N()) as usize;
-        M[index] = RistrettoPoint::random(&mut rng);
-        let evil_input_set = Arc::new(InputSet::new(&M));
-        let evil_statement =
-            Statement::new(statements[0].get_params(), &evil_input_set, statements[0].get_J()).unwrap();
+        // The batch verifies with the same message bindings
+        assert!(Proof::verify_batch(&statements, &proofs, &mut transcripts.clone()).is_ok());
 
-        // Attempt to verify the proof against the new statement, which should fail
-        assert!(proof.verify(&evil_statement, &mut transcripts[0]).is_err());
+        // The batch fails if any message binding differs
+        let mut evil_transcripts = transcripts.clone();
+        evil_transcripts[1].append_message(b"message", b"evil message");
+        assert!(Proof::verify_batch(&statements, &proofs, &mut evil_transcripts).is_err());
     }
 
     #[test]
     #[allow(non_snake_case, non_upper_case_globals)]
-    fn test_evil_linking_tag() {
+    fn test_verify_batch_with_compressed_tags() {
         // Generate data
         const n: u32 = 2;
         const m: u32 = 4;
+        const batch: usize = 3;
         let mut rng = ChaCha12Rng::seed_from_u64(8675309);
-        let (witnesses, statements, mut transcripts) = generate_data(n, m, 1, &mut rng);
+        let (witnesses, statements, mut transcripts) = generate_data(n, m, batch, &mut rng);
+        let params = statements[0].get_params();
+        let input_set = statements[0].get_input_set();
 
-        // Generate a proof
-        let proof = Proof::prove_with_rng_vartime(&witnesses[0], &statements[0], &mut rng, &mut transcripts[0].clone())
-            .unwrap();
+        // Generate the proofs
+        let proofs = izip!(witnesses.iter(), statements.iter(), transcripts.clone().iter_mut())
+            .map(|(w, s, t)| Proof::prove_with_rng_vartime(w, s, &mut rng, t).unwrap())
+            .collect::<Vec<Proof>>();
 
-        // Generate a statement with a modified linking tag
-        let evil_statement = Statement::new(
-            statements[0].get_params(),
-            statements[0].get_input_set(),
-            &RistrettoPoint::random(&mut rng),
-        )
-        .unwrap();
+        // Compress the linking tags
+        let tags = statements
+            .iter()
+            .map(|statement| statement.get_J().compress())
+            .collect::<Vec<CompressedRistretto>>();
 
-        // Attempt to verify the proof against the new statement, which should fail
-        assert!(proof.verify(&evil_statement, &mut transcripts[0]).is_err());
+        // The batch verifies
+        assert!(
+            Proof::verify_batch_with_compressed_tags(params, input_set, &tags, &proofs, &mut transcripts.clone())
+                .is_ok()
+        );
+
+        // A non-canonical tag is rejected
+        let mut evil_tags = tags.clone();
+        evil_tags[1] = CompressedRistretto([0xFF; 32]);
+        assert!(matches!(
+            Proof::verify_batch_with_compressed_tags(params, input_set, &evil_tags, &proofs, &mut transcripts.clone()),
+            Err(ProofError::FailedDeserialization)
+        ));
+
+        // A mismatched number of tags is rejected
+        assert!(
+            Proof::verify_batch_with_compressed_tags(params, input_set, &tags[1..], &proofs, &mut transcripts).is_err()
+        );
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_entropy_bytes() {
+        // A generator that counts the bytes drawn from it
+        struct CountingRng<R: CryptoRngCore> {
+            rng: R,
+            count: usize,
+        }
+
+        impl<R: CryptoRngCore> RngCore for CountingRng<R> {
+            fn next_u32(&mut self) -> u32 {
+                self.count += 4;
+                self.rng.next_u32()
+            }
+
+            fn next_u64(&mut self) -> u64 {
+                self.count += 8;
+                self.rng.next_u64()
+            }
+
+            fn fill_bytes(&mut self, dest: &mut [u8]) {
+                self.count += dest.len();
+                self.rng.fill_bytes(dest);
+            }
+
+            fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+                self.count += dest.len();
+                self.rng.try_fill_bytes(dest)
+            }
+        }
+
+        impl<R: CryptoRngCore> CryptoRng for CountingRng<R> {}
+
+        // The count must match for both timing modes and a variety of parameters
+        for (n, m) in [(2, 2), (2, 4), (3, 3)] {
+            let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+            let (witnesses, statements, transcripts) = generate_data(n, m, 1, &mut rng);
+            let expected = statements[0].get_params().prove_entropy_bytes();
+
+            let mut counting_rng = CountingRng { rng, count: 0 };
+            Proof::prove_with_rng(
+                &witnesses[0],
+                &statements[0],
+                &mut counting_rng,
+                &mut transcripts[0].clone(),
+            )
+            .unwrap();
+            assert_eq!(counting_rng.count, expected);
+
+            counting_rng.count = 0;
+            Proof::prove_with_rng_vartime(
+                &witnesses[0],
+                &statements[0],
+                &mut counting_rng,
+                &mut transcripts[0].clone(),
+            )
+            .unwrap();
+            assert_eq!(counting_rng.count, expected);
+        }
     }
 }
@@ -0,0 +1,101 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+
+use crate::Parameters;
+
+/// A registry of [`Parameters`], keyed by their hash.
+///
+/// This is useful for a verifier that supports several configurations, and must find the right [`Parameters`] for each
+/// [`Statement`](`crate::Statement`) it receives. The hash is the first 32 bytes of the encoding produced by
+/// [`Statement::to_bytes`](`crate::Statement::to_bytes`), so a verifier can read it, look up the corresponding
+/// [`Parameters`], and use them to decode the rest of the statement.
+#[derive(Clone, Default)]
+pub struct ParametersRegistry {
+    parameters: BTreeMap<Vec<u8>, Arc<Parameters>>,
+}
+
+impl ParametersRegistry {
+    /// Generate a new empty [`ParametersRegistry`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register [`Parameters`] `params`, reporting whether they were newly added.
+    ///
+    /// Since [`Parameters`] with the same hash are equal, registering them again has no effect.
+    pub fn register(&mut self, params: &Arc<Parameters>) -> bool {
+        if self.parameters.contains_key(params.get_hash()) {
+            return false;
+        }
+
+        self.parameters.insert(params.get_hash().to_vec(), params.clone());
+        true
+    }
+
+    /// Look up the [`Parameters`] with a given `hash`, if they have been registered.
+    pub fn lookup(&self, hash: &[u8]) -> Option<&Arc<Parameters>> {
+        self.parameters.get(hash)
+    }
+
+    /// Get the number of [`Parameters`] in this [`ParametersRegistry`].
+    pub fn len(&self) -> usize {
+        self.parameters.len()
+    }
+
+    /// Check if this [`ParametersRegistry`] is empty.
+    pub fn is_empty(&self) -> bool {
+        self.parameters.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::RistrettoPoint;
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use crate::{InputSet, Parameters, ParametersRegistry, Statement};
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_registry() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Register parameters for several configurations
+        let configurations = [(2, 4), (3, 2), (4, 3)]
+            .iter()
+            .map(|(n, m)| Arc::new(Parameters::new(*n, *m).unwrap()))
+            .collect::<Vec<Arc<Parameters>>>();
+        let mut registry = ParametersRegistry::new();
+        assert!(registry.is_empty());
+        for params in &configurations {
+            assert!(registry.register(params));
+        }
+
+        // Registering equal parameters again has no effect
+        assert!(!registry.register(&Arc::new(Parameters::new(2, 4).unwrap())));
+        assert_eq!(registry.len(), configurations.len());
+
+        // The parameters for an encoded statement can be looked up and used to decode it
+        for params in &configurations {
+            let M = (0..params.get_N())
+                .map(|_| RistrettoPoint::random(&mut rng))
+                .collect::<Vec<RistrettoPoint>>();
+            let statement =
+                Statement::new(params, &Arc::new(InputSet::new(&M)), &RistrettoPoint::random(&mut rng)).unwrap();
+            let bytes = statement.to_bytes();
+
+            let found = registry.lookup(&bytes[..32]).unwrap();
+            assert!(Arc::ptr_eq(found, params));
+            assert!(Statement::from_bytes(&bytes, found).unwrap() == statement);
+        }
+
+        // Unknown hashes aren't found
+        assert!(registry.lookup(&[0u8; 32]).is_none());
+        assert!(registry.lookup(&[]).is_none());
+    }
+}
@@ -1,44 +1,107 @@
 // Copyright (c) 2024, The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
-use alloc::{sync::Arc, vec::Vec};
+use alloc::{borrow::Cow, sync::Arc, vec::Vec};
+use core::{
+    hash::{self, Hash},
+    ops::Range,
+    slice,
+};
 
 use blake3::Hasher;
-use curve25519_dalek::{traits::Identity, RistrettoPoint};
+use curve25519_dalek::{ristretto::CompressedRistretto, traits::Identity, RistrettoPoint};
 use snafu::prelude::*;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 
-use crate::Parameters;
+use crate::{Dimensions, Parameters};
 
 /// A Triptych input set.
 ///
 /// An input set is constructed from a vector of verification keys.
 /// Internally, it also contains cryptographic hash data to make proofs more efficient.
+///
+/// Two [`InputSet`]s are equal exactly when they have the same hash, which binds the verification keys and their
+/// order.
+///
+/// The hash is always computed from the verification keys when an [`InputSet`] is built, and is never taken from
+/// external data, so it can't be inconsistent with them. In particular, it's safe to use an [`InputSet`] decoded from
+/// untrusted data without revalidating it.
 #[allow(non_snake_case)]
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct InputSet {
-    M: Vec<RistrettoPoint>,
+    // Verification keys may be shared with other input sets, so only those in `range` belong to this one
+    M: Arc<Vec<RistrettoPoint>>,
+    range: Range<usize>,
+    // Keys that replace those of `M` at the given indexes, sorted by index
+    overrides: Vec<(usize, RistrettoPoint)>,
     hash: Vec<u8>,
+    hasher: Hasher,
+}
+
+/// An iterator over the verification keys of an [`InputSet`], with any overrides applied.
+#[derive(Clone)]
+struct Keys<'a> {
+    keys: core::iter::Zip<core::ops::RangeFrom<usize>, slice::Iter<'a, RistrettoPoint>>,
+    overrides: &'a [(usize, RistrettoPoint)],
+}
+
+impl<'a> Iterator for Keys<'a> {
+    type Item = &'a RistrettoPoint;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, key) = self.keys.next()?;
+        match self.overrides.split_first() {
+            Some(((override_index, override_key), rest)) if *override_index == index => {
+                self.overrides = rest;
+                Some(override_key)
+            },
+            _ => Some(key),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Keys<'_> {}
+
+// The hash binds the verification keys, so there's no need to compare them directly
+impl PartialEq for InputSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+    }
 }
 
+impl Eq for InputSet {}
+
 impl InputSet {
     // Version identifier used for hashing
     const VERSION: u64 = 0;
 
     /// Generate a new [`InputSet`] from a slice `M` of verification keys.
+    ///
+    /// If your verification keys come from an iterator, you can collect them directly into an [`InputSet`] instead.
     #[allow(non_snake_case)]
     pub fn new(M: &[RistrettoPoint]) -> Self {
-        // Use `BLAKE3` for the transcript hash
-        let mut hasher = Hasher::new();
-        hasher.update(b"Triptych InputSet");
-        hasher.update(&Self::VERSION.to_le_bytes());
-        for item in M {
-            hasher.update(item.compress().as_bytes());
-        }
+        M.iter().copied().collect()
+    }
 
-        Self {
-            M: M.to_vec(),
-            hash: hasher.finalize().as_bytes().to_vec(),
+    /// Generate a new [`InputSet`] from a slice `M` of verification keys, checking that they are distinct.
+    ///
+    /// Duplicate verification keys reduce the effective size of the anonymity set, and usually indicate a bug in how
+    /// the verification keys were chosen. Checking for them requires sorting, so this is slower than
+    /// [`InputSet::new`].
+    ///
+    /// If `M` contains duplicate verification keys, returns a [`StatementError`].
+    #[allow(non_snake_case)]
+    pub fn new_checked(M: &[RistrettoPoint]) -> Result<Self, StatementError> {
+        let input_set = Self::new(M);
+        if input_set.has_duplicates() {
+            return Err(StatementError::DuplicateVerificationKey);
         }
+
+        Ok(input_set)
     }
 
     /// Generate a new padded [`InputSet`] from a slice `M` of verification keys and [`Parameters`] `params`.
@@ -50,7 +113,8 @@ impl InputSet {
     #[allow(non_snake_case)]
     pub fn new_with_padding(M: &[RistrettoPoint], params: &Parameters) -> Result<Self, StatementError> {
         // We cannot have the vector be too long
-        if M.len() > params.get_N() as usize {
+        let N = usize::try_from(params.get_N()).map_err(|_| StatementError::InvalidParameter)?;
+        if M.len() > N {
             return Err(StatementError::InvalidParameter);
         }
 
@@ -59,31 +123,405 @@ impl InputSet {
 
         // Pad the vector with the last element
         let mut M_padded = M.to_vec();
-        M_padded.resize(params.get_N() as usize, *last);
+        M_padded.resize(N, *last);
         Ok(Self::new(&M_padded))
     }
 
+    /// Generate a new [`InputSet`] from a slice `M` of verification keys, sorted by their canonical encodings.
+    ///
+    /// This produces the same [`InputSet`] regardless of the order of `M`, which makes it easier for different parties
+    /// to agree on an input set. If you need to know where a particular verification key lands after sorting, use
+    /// [`InputSet::sorted_with_index`] instead.
+    #[allow(non_snake_case)]
+    pub fn new_sorted(M: &[RistrettoPoint]) -> Self {
+        Self::sorted_order(M.iter())
+            .iter()
+            .map(|(_, index)| M[*index])
+            .collect()
+    }
+
+    /// Generate a new [`InputSet`] from a slice `M` of verification keys, sorted by their canonical encodings, and
+    /// find the new index of the verification key at index `l`.
+    ///
+    /// This is useful for a prover that needs to update its [`Witness`](`crate::Witness`) index after sorting.
+    /// Sorting is stable, so if `M` contains duplicate verification keys, their relative order is preserved.
+    ///
+    /// If `l` is not a valid index into `M`, returns a [`StatementError`].
+    #[allow(non_snake_case)]
+    pub fn sorted_with_index(M: &[RistrettoPoint], l: u64) -> Result<(Self, u64), StatementError> {
+        let order = Self::sorted_order(M.iter());
+
+        // Find where the verification key landed
+        let l = usize::try_from(l).map_err(|_| StatementError::InvalidParameter)?;
+        let new_l = order
+            .iter()
+            .position(|(_, index)| *index == l)
+            .ok_or(StatementError::InvalidParameter)?;
+
+        Ok((
+            order.iter().map(|(_, index)| M[*index]).collect(),
+            u64::try_from(new_l).map_err(|_| StatementError::InvalidParameter)?,
+        ))
+    }
+
+    /// Determine if this [`InputSet`] contains any duplicate verification keys.
+    ///
+    /// Duplicate verification keys reduce the effective size of the anonymity set, which you can get using
+    /// [`InputSet::effective_size`]. This requires sorting the canonical encodings of the verification keys.
+    pub fn has_duplicates(&self) -> bool {
+        Self::sorted_order(self.keys())
+            .windows(2)
+            .any(|pair| pair[0].0 == pair[1].0)
+    }
+
+    /// Get the number of distinct verification keys in this [`InputSet`] that are not the identity.
+    ///
+    /// This is the size of the anonymity set actually achieved by a proof using this [`InputSet`], which is smaller
+    /// than its number of verification keys if any are duplicates or the identity. A [`Statement`] can't contain
+    /// the identity, but counting it here makes this useful for checking an input set before building a statement.
+    /// This requires sorting the canonical encodings of the verification keys.
+    pub fn effective_size(&self) -> u64 {
+        let identity = RistrettoPoint::identity().compress();
+        let mut encodings = self
+            .keys()
+            .map(RistrettoPoint::compress)
+            .filter(|bytes| *bytes != identity)
+            .map(|bytes| bytes.to_bytes())
+            .collect::<Vec<[u8; 32]>>();
+        encodings.sort_unstable();
+        encodings.dedup();
+
+        // This can't fail, since the keys are in memory
+        u64::try_from(encodings.len()).unwrap_or(u64::MAX)
+    }
+
+    /// Get the indexes of verification keys in stable sorted order of their canonical encodings, along with the
+    /// encodings.
+    #[allow(non_snake_case)]
+    fn sorted_order<'a>(M: impl Iterator<Item = &'a RistrettoPoint>) -> Vec<([u8; 32], usize)> {
+        let mut order = M
+            .map(|item| item.compress().to_bytes())
+            .zip(0..)
+            .collect::<Vec<([u8; 32], usize)>>();
+        order.sort_by_key(|(bytes, _)| *bytes);
+
+        order
+    }
+
+    /// Generate a new [`InputSet`] by appending `additional` verification keys to this one.
+    ///
+    /// The result is identical to an [`InputSet`] built from all of the verification keys at once, but only the
+    /// appended keys are hashed. Since existing keys keep their positions, a [`Witness`](`crate::Witness`) index for
+    /// this [`InputSet`] remains valid for the extended one, as long as keys are only appended. The extended
+    /// [`InputSet`] must still match the size specified by the [`Parameters`] it is used with.
+    #[allow(non_snake_case)]
+    pub fn extended(&self, additional: &[RistrettoPoint]) -> Self {
+        let mut hasher = self.hasher.clone();
+        let mut M = Vec::with_capacity(self.keys().len().saturating_add(additional.len()));
+        M.extend(self.keys());
+        for item in additional {
+            hasher.update(item.compress().as_bytes());
+            M.push(*item);
+        }
+
+        Self::from_parts(Arc::new(M), hasher)
+    }
+
+    /// Generate a new [`InputSet`] from `len` verification keys of this one, starting at index `offset`.
+    ///
+    /// The verification keys and any overrides in the window are shared with this [`InputSet`] rather than applied, but
+    /// the result is otherwise identical to an [`InputSet`] built from only those keys, including its hash.
+    ///
+    /// If the window does not fit within this [`InputSet`], returns a [`StatementError`].
+    pub(crate) fn window(&self, offset: u64, len: u64) -> Result<Self, StatementError> {
+        let start = usize::try_from(offset)
+            .ok()
+            .and_then(|offset| offset.checked_add(self.range.start))
+            .ok_or(StatementError::InvalidParameter)?;
+        let end = usize::try_from(len)
+            .ok()
+            .and_then(|len| len.checked_add(start))
+            .ok_or(StatementError::InvalidParameter)?;
+        if end > self.range.end {
+            return Err(StatementError::InvalidParameter);
+        }
+
+        // Keep only the overrides within the window
+        let overrides = self
+            .overrides
+            .iter()
+            .filter(|(index, _)| (start..end).contains(index))
+            .copied()
+            .collect();
+
+        Ok(Self::from_view(self.M.clone(), start..end, overrides))
+    }
+
+    /// Generate a new [`InputSet`] by replacing some verification keys of this one.
+    ///
+    /// Each entry of `overrides` is an index into this [`InputSet`] and the verification key that replaces the one at
+    /// that index. The verification keys are shared with this [`InputSet`] rather than copied, and only the overrides
+    /// are stored; the result is otherwise identical to an [`InputSet`] built from the keys with the overrides applied,
+    /// including its hash, which is computed by streaming the keys with the overrides substituted.
+    ///
+    /// If any override index is out of range or appears more than once, returns a [`StatementError`].
+    pub(crate) fn with_overrides(&self, overrides: &[(u64, RistrettoPoint)]) -> Result<Self, StatementError> {
+        // Convert the indexes to refer to the shared keys, checking that each is in range
+        let mut new_overrides = overrides
+            .iter()
+            .map(|(index, key)| {
+                usize::try_from(*index)
+                    .ok()
+                    .and_then(|index| index.checked_add(self.range.start))
+                    .filter(|index| *index < self.range.end)
+                    .map(|index| (index, *key))
+                    .ok_or(StatementError::InvalidParameter)
+            })
+            .collect::<Result<Vec<(usize, RistrettoPoint)>, StatementError>>()?;
+        new_overrides.sort_by_key(|(index, _)| *index);
+        if new_overrides.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(StatementError::InvalidParameter);
+        }
+
+        // Merge with any existing overrides, which the new ones replace
+        let mut merged = Vec::with_capacity(self.overrides.len().saturating_add(new_overrides.len()));
+        let mut existing = self.overrides.iter().peekable();
+        for (index, key) in new_overrides {
+            while let Some(item) = existing.next_if(|(existing_index, _)| *existing_index < index) {
+                merged.push(*item);
+            }
+            existing.next_if(|(existing_index, _)| *existing_index == index);
+            merged.push((index, key));
+        }
+        merged.extend(existing);
+
+        Ok(Self::from_view(self.M.clone(), self.range.clone(), merged))
+    }
+
+    /// Split a slice of verification `keys` into overlapping windows, each of which is an [`InputSet`] of size
+    /// `N == n**m`.
+    ///
+    /// This is useful for building larger anonymity systems on top of fixed-size input sets, where different proofs
+    /// use different but overlapping windows of one large set of verification keys. Consecutive windows share
+    /// `overlap` verification keys, except that the last window always ends at the last verification key, so it may
+    /// share more with the window before it. This means every verification key appears in at least one window.
+    ///
+    /// The windows share a single copy of the verification keys, but each is otherwise identical to an [`InputSet`]
+    /// built with [`InputSet::new`] from only its keys, including its hash. Anyone who knows a window's keys can
+    /// therefore reproduce its hash independently of the other windows.
+    ///
+    /// If `n` and `m` are not valid [`Dimensions`], if `overlap` is not less than `N`, or if there are fewer than `N`
+    /// verification keys, returns a [`StatementError`].
+    #[allow(non_snake_case)]
+    pub fn windows(keys: &[RistrettoPoint], n: u32, m: u32, overlap: u32) -> Result<Vec<Self>, StatementError> {
+        let N = Dimensions::new(n, m)
+            .map_err(|_| StatementError::InvalidParameter)?
+            .get_N();
+        let N = usize::try_from(N).map_err(|_| StatementError::InvalidParameter)?;
+
+        // Consecutive windows must start at different keys
+        let stride = usize::try_from(overlap)
+            .ok()
+            .and_then(|overlap| N.checked_sub(overlap))
+            .filter(|stride| *stride > 0)
+            .ok_or(StatementError::InvalidParameter)?;
+        if keys.len() < N {
+            return Err(StatementError::InvalidParameter);
+        }
+
+        // Find where each window ends, making sure the last one ends with the last key
+        let mut ends = (N..=keys.len()).step_by(stride).collect::<Vec<usize>>();
+        if ends.last() != Some(&keys.len()) {
+            ends.push(keys.len());
+        }
+
+        let M = Arc::new(keys.to_vec());
+        Ok(ends
+            .into_iter()
+            .map(|end| Self::from_view(M.clone(), end.saturating_sub(N)..end, Vec::new()))
+            .collect())
+    }
+
     /// Get the verification keys for this [`InputSet`].
-    pub fn get_keys(&self) -> &[RistrettoPoint] {
-        &self.M
+    ///
+    /// If this [`InputSet`] was built with overrides, such as for a [`Statement::sparse`], the keys are not stored
+    /// contiguously, so this copies them with the overrides applied. Use [`InputSet::keys`] to iterate over them
+    /// without copying.
+    pub fn get_keys(&self) -> Cow<'_, [RistrettoPoint]> {
+        if self.overrides.is_empty() {
+            Cow::Borrowed(&self.M[self.range.clone()])
+        } else {
+            Cow::Owned(self.keys().copied().collect())
+        }
+    }
+
+    /// Iterate over the verification keys for this [`InputSet`], with any overrides applied, without copying them.
+    pub fn keys(&self) -> impl ExactSizeIterator<Item = &RistrettoPoint> + Clone {
+        Keys {
+            keys: (self.range.start..).zip(self.M[self.range.clone()].iter()),
+            overrides: &self.overrides,
+        }
+    }
+
+    /// Find the index of a verification `key` in this [`InputSet`].
+    ///
+    /// This is useful for building a [`Witness`](`crate::Witness`) for your verification key. If the key appears more
+    /// than once, returns the first index; if it does not appear, returns `None`.
+    ///
+    /// This scans the verification keys in variable time, and doesn't reveal anything that isn't public.
+    pub fn index_of(&self, key: &RistrettoPoint) -> Option<u64> {
+        (0u64..)
+            .zip(self.keys())
+            .find(|(_, item)| *item == key)
+            .map(|(index, _)| index)
+    }
+
+    /// Get the Merkle root of the verification keys in this [`InputSet`], using `BLAKE3`.
+    ///
+    /// Parties that agree on a Merkle root can check an input set received from an untrusted source using
+    /// [`Statement::new_from_root`]. See [`InputSet::merkle_root_with`] for details of the tree construction.
+    pub fn merkle_root(&self) -> [u8; 32] {
+        self.merkle_root_with(|bytes| *blake3::hash(bytes).as_bytes())
+    }
+
+    /// Get the Merkle root of the verification keys in this [`InputSet`], using a custom `hasher`.
+    ///
+    /// Each leaf is the hash of a `0` byte followed by the canonical encoding of a verification key, and each internal
+    /// node is the hash of a `1` byte followed by its two children. If a level has an odd number of nodes, the last
+    /// node is promoted to the next level unchanged. The hasher must be collision resistant.
+    ///
+    /// Note that verifying a proof still requires every verification key, so this does not reduce verification work.
+    pub fn merkle_root_with<H: Fn(&[u8]) -> [u8; 32]>(&self, hasher: H) -> [u8; 32] {
+        let mut level = self
+            .keys()
+            .map(|key| {
+                let mut leaf = [0u8; 33];
+                leaf[1..].copy_from_slice(key.compress().as_bytes());
+                hasher(&leaf)
+            })
+            .collect::<Vec<[u8; 32]>>();
+
+        // Hash pairs of nodes until only the root remains
+        while level.len() > 1 {
+            level = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => {
+                        let mut node = [1u8; 65];
+                        node[1..33].copy_from_slice(left);
+                        node[33..].copy_from_slice(right);
+                        hasher(&node)
+                    },
+                    _ => pair[0],
+                })
+                .collect();
+        }
+
+        // An empty input set has the hash of no data as its root
+        level.first().copied().unwrap_or_else(|| hasher(&[]))
     }
 
     /// Get a cryptographic hash representation of this [`InputSet`], suitable for transcripting.
     pub(crate) fn get_hash(&self) -> &[u8] {
         &self.hash
     }
+
+    /// Start a hasher for the verification keys of an [`InputSet`].
+    fn new_hasher() -> Hasher {
+        let mut hasher = Hasher::new();
+        hasher.update(b"Triptych InputSet");
+        hasher.update(&Self::VERSION.to_le_bytes());
+
+        hasher
+    }
+
+    /// Build an [`InputSet`] from the verification keys of `M` in `range` with `overrides` applied, all of which must
+    /// be valid.
+    #[allow(non_snake_case)]
+    fn from_view(M: Arc<Vec<RistrettoPoint>>, range: Range<usize>, overrides: Vec<(usize, RistrettoPoint)>) -> Self {
+        let mut input_set = Self {
+            M,
+            range,
+            overrides,
+            hash: Vec::new(),
+            hasher: Self::new_hasher(),
+        };
+
+        let mut hasher = Self::new_hasher();
+        for item in input_set.keys() {
+            hasher.update(item.compress().as_bytes());
+        }
+        input_set.hash = hasher.finalize().as_bytes().to_vec();
+        input_set.hasher = hasher;
+
+        input_set
+    }
+
+    /// Build an [`InputSet`] from all of the verification keys `M`, which have already been hashed by `hasher`.
+    #[allow(non_snake_case)]
+    fn from_parts(M: Arc<Vec<RistrettoPoint>>, hasher: Hasher) -> Self {
+        Self {
+            range: 0..M.len(),
+            M,
+            overrides: Vec::new(),
+            hash: hasher.finalize().as_bytes().to_vec(),
+            hasher,
+        }
+    }
+}
+
+impl FromIterator<RistrettoPoint> for InputSet {
+    /// Generate a new [`InputSet`] from an iterator of verification keys.
+    #[allow(non_snake_case)]
+    fn from_iter<I: IntoIterator<Item = RistrettoPoint>>(iter: I) -> Self {
+        // Use `BLAKE3` for the transcript hash, updating it as we go
+        let mut hasher = Self::new_hasher();
+        let M = iter
+            .into_iter()
+            .inspect(|item| {
+                hasher.update(item.compress().as_bytes());
+            })
+            .collect::<Vec<RistrettoPoint>>();
+
+        Self::from_parts(Arc::new(M), hasher)
+    }
+}
+
+impl TryFrom<Vec<CompressedRistretto>> for InputSet {
+    type Error = StatementError;
+
+    /// Generate a new [`InputSet`] from a vector of compressed verification keys.
+    ///
+    /// If any verification key is not a valid point encoding, returns a [`StatementError`].
+    fn try_from(keys: Vec<CompressedRistretto>) -> Result<Self, Self::Error> {
+        keys.iter()
+            .map(|key| key.decompress().ok_or(StatementError::InvalidEncoding))
+            .collect()
+    }
 }
 
 /// A Triptych proof statement.
 ///
 /// The statement consists of an [`InputSet`] of verification keys and a linking tag.
 /// It also contains [`Parameters`] that, among other things, enforce the size of the [`InputSet`].
+///
+/// A message can be bound to a proof in either of two ways. Usually, the caller appends it to the
+/// [`Transcript`](`crate::Transcript`) supplied to both the prover and verifier. Alternatively, the message can be
+/// made part of the statement using [`Statement::new_with_message`], in which case it is bound into the transcript
+/// automatically, so the statement is the single source of truth for it.
+///
+/// Two [`Statements`](`Statement`) are equal exactly when they have equal [`Parameters`] and [`InputSets`](`InputSet`),
+/// which are compared by hash, the same linking tag, and the same message. Hashing is consistent with equality, so
+/// statements can be used as keys in hashed collections, such as to detect the same statement being verified
+/// repeatedly. The message is included since it is bound into proofs, but a cached verification result must still
+/// account for the [`Proof`](`crate::Proof`) and [`Transcript`](`crate::Transcript`) it was computed from.
 #[allow(non_snake_case)]
 #[derive(Clone, Eq, PartialEq)]
 pub struct Statement {
     params: Arc<Parameters>,
     input_set: Arc<InputSet>,
     J: RistrettoPoint,
+    message: Option<Vec<u8>>,
 }
 
 /// Errors that can arise relating to [`Statement`].
@@ -92,6 +530,37 @@ pub enum StatementError {
     /// An invalid parameter was provided.
     #[snafu(display("An invalid parameter was provided"))]
     InvalidParameter,
+    /// A verification key is not a valid point encoding.
+    #[snafu(display("A verification key is not a valid point encoding"))]
+    InvalidEncoding,
+    /// The input set does not match the expected Merkle root.
+    #[snafu(display("The input set does not match the expected Merkle root"))]
+    MerkleRootMismatch,
+    /// The input set contains duplicate verification keys.
+    #[snafu(display("The input set contains duplicate verification keys"))]
+    DuplicateVerificationKey,
+    /// The input set contains the identity group element.
+    #[snafu(display("The input set contains the identity group element"))]
+    IdentityVerificationKey,
+    /// The linking tag is the identity group element.
+    #[snafu(display("The linking tag is the identity group element"))]
+    IdentityLinkingTag,
+    /// A statement could not be deserialized.
+    #[snafu(display("A statement could not be deserialized"))]
+    FailedDeserialization,
+    /// The parameters or input set do not match an encoded hash.
+    #[snafu(display("The parameters or input set do not match an encoded hash"))]
+    HashMismatch,
+}
+
+// Hash the same components used for equality, using the cached hashes and the canonical linking tag encoding
+impl Hash for Statement {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.params.get_hash().hash(state);
+        self.input_set.get_hash().hash(state);
+        self.J.compress().as_bytes().hash(state);
+        self.message.hash(state);
+    }
 }
 
 impl Statement {
@@ -103,7 +572,8 @@ impl Statement {
     ///
     /// The linking tag `J` is assumed to have been computed from
     /// [`Witness::compute_linking_tag`](`crate::witness::Witness::compute_linking_tag`) data or otherwise provided
-    /// externally.
+    /// externally. It must not be the identity group element, since no valid witness can produce it; if it is, returns
+    /// a [`StatementError`].
     #[allow(non_snake_case)]
     pub fn new(
         params: &Arc<Parameters>,
@@ -111,20 +581,116 @@ impl Statement {
         J: &RistrettoPoint,
     ) -> Result<Self, StatementError> {
         // Check that the input vector is valid against the parameters
-        if input_set.get_keys().len() != params.get_N() as usize {
+        if usize::try_from(params.get_N()).ok() != Some(input_set.keys().len()) {
             return Err(StatementError::InvalidParameter);
         }
-        if input_set.get_keys().contains(&RistrettoPoint::identity()) {
-            return Err(StatementError::InvalidParameter);
+        if input_set.keys().any(|key| key == &RistrettoPoint::identity()) {
+            return Err(StatementError::IdentityVerificationKey);
+        }
+
+        // The linking tag cannot be the identity group element
+        if J == &RistrettoPoint::identity() {
+            return Err(StatementError::IdentityLinkingTag);
         }
 
         Ok(Self {
             params: params.clone(),
             input_set: input_set.clone(),
             J: *J,
+            message: None,
         })
     }
 
+    /// Generate a new [`Statement`] that includes a `message`.
+    ///
+    /// The message is bound into the transcript when proving and verifying, so a proof generated using this statement
+    /// verifies only against a statement with the same message. A statement with an empty message is distinct from one
+    /// without a message.
+    ///
+    /// The same requirements as [`Statement::new`] apply to the input set and linking tag `J`.
+    #[allow(non_snake_case)]
+    pub fn new_with_message(
+        params: &Arc<Parameters>,
+        input_set: &Arc<InputSet>,
+        J: &RistrettoPoint,
+        message: &[u8],
+    ) -> Result<Self, StatementError> {
+        let mut statement = Self::new(params, input_set, J)?;
+        statement.message = Some(message.to_vec());
+
+        Ok(statement)
+    }
+
+    /// Generate a new [`Statement`], checking that the [`InputSet`] `input_set` has an expected Merkle `root`.
+    ///
+    /// This is useful when parties agree on a large input set by its Merkle root, and the verification keys themselves
+    /// come from an untrusted source. The root is computed using [`InputSet::merkle_root`]; if it does not match
+    /// `root`, returns a [`StatementError`].
+    ///
+    /// Otherwise, the same requirements as [`Statement::new`] apply to the input set and linking tag `J`.
+    #[allow(non_snake_case)]
+    pub fn new_from_root(
+        params: &Arc<Parameters>,
+        input_set: &Arc<InputSet>,
+        root: &[u8; 32],
+        J: &RistrettoPoint,
+    ) -> Result<Self, StatementError> {
+        if &input_set.merkle_root() != root {
+            return Err(StatementError::MerkleRootMismatch);
+        }
+
+        Self::new(params, input_set, J)
+    }
+
+    /// Generate a new [`Statement`] whose input set is a base [`InputSet`] with some verification keys replaced.
+    ///
+    /// This is useful when many statements share most of a large input set.
+    /// Each entry of `overrides` is an index into `base` and the verification key that replaces the one at that index.
+    /// The keys of `base` are shared rather than copied, and only the overrides are stored; proving and verification
+    /// read each key from `base` unless it is overridden. The resulting statement is otherwise identical to one built
+    /// with [`Statement::new`] from an input set with the overrides applied, including its hash, so proofs are
+    /// interchangeable between the two.
+    ///
+    /// If any override index is out of range or appears more than once, returns a [`StatementError`].
+    /// Otherwise, the same requirements as [`Statement::new`] apply to the resulting input set and linking tag `J`.
+    #[allow(non_snake_case)]
+    pub fn sparse(
+        params: &Arc<Parameters>,
+        base: &Arc<InputSet>,
+        overrides: &[(u64, RistrettoPoint)],
+        J: &RistrettoPoint,
+    ) -> Result<Self, StatementError> {
+        // Without overrides, we can share the base input set
+        if overrides.is_empty() {
+            return Self::new(params, base, J);
+        }
+
+        Self::new(params, &Arc::new(base.with_overrides(overrides)?), J)
+    }
+
+    /// Generate a new [`Statement`] whose input set is a window of a larger [`InputSet`] `full_set`.
+    ///
+    /// This is useful when many proofs use contiguous windows of one large set of verification keys. The window
+    /// consists of the `N` verification keys starting at index `offset`, where `N` is specified by the [`Parameters`]
+    /// `params`. The keys are shared with `full_set` rather than copied, but the resulting statement is identical to
+    /// one built with [`Statement::new`] from an input set containing only the window, including its hash. This
+    /// means a prover who has only the window can generate proofs that verify against this statement, and vice
+    /// versa.
+    ///
+    /// If the window does not fit within `full_set`, returns a [`StatementError`].
+    /// Otherwise, the same requirements as [`Statement::new`] apply to the window and linking tag `J`.
+    #[allow(non_snake_case)]
+    pub fn new_window(
+        params: &Arc<Parameters>,
+        full_set: &InputSet,
+        offset: u64,
+        J: &RistrettoPoint,
+    ) -> Result<Self, StatementError> {
+        let input_set = full_set.window(offset, params.get_N())?;
+
+        Self::new(params, &Arc::new(input_set), J)
+    }
+
     /// Get the parameters for this [`Statement`].
     pub fn get_params(&self) -> &Arc<Parameters> {
         &self.params
@@ -140,17 +706,181 @@ impl Statement {
     pub fn get_J(&self) -> &RistrettoPoint {
         &self.J
     }
+
+    /// Get the message for this [`Statement`], if it has one.
+    pub fn get_message(&self) -> Option<&[u8]> {
+        self.message.as_deref()
+    }
+
+    /// Find the index of a verification `key` in the [`InputSet`] for this [`Statement`].
+    ///
+    /// This is equivalent to [`InputSet::index_of`].
+    pub fn input_set_index_of(&self, key: &RistrettoPoint) -> Option<u64> {
+        self.input_set.index_of(key)
+    }
+
+    /// Find the first of several linking `tags` that matches the linking tag for this [`Statement`].
+    ///
+    /// Returns the index of the matching tag, or `None` if no tag matches.
+    /// This runs in variable time, and can leak which tag matched; if this is a concern, use
+    /// [`Statement::matches_any_tag_ct`] instead.
+    pub fn matches_any_tag(&self, tags: &[RistrettoPoint]) -> Option<usize> {
+        tags.iter().position(|tag| tag == &self.J)
+    }
+
+    /// Find the first of several linking `tags` that matches the linking tag for this [`Statement`], in constant time.
+    ///
+    /// Returns the index of the matching tag, or `None` if no tag matches.
+    /// This always compares against every tag, so its timing depends only on the number of tags and whether any tag
+    /// matches.
+    pub fn matches_any_tag_ct(&self, tags: &[RistrettoPoint]) -> Option<usize> {
+        let mut found = Choice::from(0);
+        let mut index = 0u64;
+        for (i, tag) in (0u64..).zip(tags.iter()) {
+            let is_match = tag.ct_eq(&self.J) & !found;
+            index.conditional_assign(&i, is_match);
+            found |= is_match;
+        }
+
+        if bool::from(found) {
+            usize::try_from(index).ok()
+        } else {
+            None
+        }
+    }
+
+    /// Get a stable fingerprint of the [`InputSet`] for this [`Statement`].
+    ///
+    /// The fingerprint is the cryptographic hash of the input set, so statements share a fingerprint exactly when
+    /// their input sets contain the same verification keys in the same order.
+    /// It does not depend on the [`Parameters`] or linking tag.
+    pub fn ring_fingerprint(&self) -> [u8; 32] {
+        let mut fingerprint = [0u8; 32];
+        fingerprint.copy_from_slice(self.input_set.get_hash());
+
+        fingerprint
+    }
+
+    /// Serialize a [`Statement`] to a canonical byte vector.
+    ///
+    /// The encoding includes the hash of the [`Parameters`], the compressed verification keys and hash of the
+    /// [`InputSet`], the linking tag, and any message. The parameters themselves are not included, since they must be
+    /// agreed on separately; see [`Statement::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let keys = self.input_set.keys();
+        let mut result = Vec::with_capacity(
+            keys.len()
+                .saturating_add(4)
+                .saturating_mul(32)
+                .saturating_add(17)
+                .saturating_add(self.message.as_ref().map_or(0, Vec::len)),
+        );
+
+        result.extend_from_slice(self.params.get_hash());
+        result.extend_from_slice(&(keys.len() as u64).to_le_bytes());
+        for key in keys {
+            result.extend_from_slice(key.compress().as_bytes());
+        }
+        result.extend_from_slice(self.input_set.get_hash());
+        result.extend_from_slice(self.J.compress().as_bytes());
+        match &self.message {
+            Some(message) => {
+                result.push(1);
+                result.extend_from_slice(&(message.len() as u64).to_le_bytes());
+                result.extend_from_slice(message);
+            },
+            None => result.push(0),
+        }
+
+        result
+    }
+
+    /// Deserialize a [`Statement`] from a canonical byte slice, using the [`Parameters`] `params`.
+    ///
+    /// The hashes of `params` and of the decoded [`InputSet`] are recomputed and checked against those in the encoding,
+    /// so any tampering with the verification keys is detected. If either hash does not match, returns
+    /// [`StatementError::HashMismatch`].
+    ///
+    /// If `bytes` does not otherwise represent a canonical encoding of a valid statement, returns a
+    /// [`StatementError`].
+    #[allow(non_snake_case)]
+    pub fn from_bytes(bytes: &[u8], params: &Arc<Parameters>) -> Result<Self, StatementError> {
+        // Helper to parse a fixed-size array from the front of a byte slice
+        fn parse<const L: usize>(bytes: &mut &[u8]) -> Result<[u8; L], StatementError> {
+            let (value, rest) = bytes
+                .split_first_chunk::<L>()
+                .ok_or(StatementError::FailedDeserialization)?;
+            *bytes = rest;
+
+            Ok(*value)
+        }
+        let mut bytes = bytes;
+
+        // Check the parameters before doing any other work
+        if parse::<32>(&mut bytes)?.as_slice() != params.get_hash() {
+            return Err(StatementError::HashMismatch);
+        }
+
+        // Check that the input set size matches the parameters and the available data before allocating
+        let size = u64::from_le_bytes(parse::<8>(&mut bytes)?);
+        if size != params.get_N() ||
+            usize::try_from(size)
+                .ok()
+                .and_then(|size| size.checked_mul(32))
+                .is_none_or(|len| len > bytes.len())
+        {
+            return Err(StatementError::FailedDeserialization);
+        }
+        let keys = (0..size)
+            .map(|_| parse::<32>(&mut bytes).map(CompressedRistretto))
+            .collect::<Result<Vec<CompressedRistretto>, StatementError>>()?;
+        let input_set = InputSet::try_from(keys)?;
+        if parse::<32>(&mut bytes)?.as_slice() != input_set.get_hash() {
+            return Err(StatementError::HashMismatch);
+        }
+
+        let J = CompressedRistretto(parse::<32>(&mut bytes)?)
+            .decompress()
+            .ok_or(StatementError::InvalidEncoding)?;
+
+        // Parse the message, which must use the rest of the data
+        let message = match parse::<1>(&mut bytes)? {
+            [0] => None,
+            [1] => {
+                let len = usize::try_from(u64::from_le_bytes(parse::<8>(&mut bytes)?))
+                    .map_err(|_| StatementError::FailedDeserialization)?;
+                if len != bytes.len() {
+                    return Err(StatementError::FailedDeserialization);
+                }
+                Some(core::mem::take(&mut bytes))
+            },
+            _ => return Err(StatementError::FailedDeserialization),
+        };
+        if !bytes.is_empty() {
+            return Err(StatementError::FailedDeserialization);
+        }
+
+        let input_set = Arc::new(input_set);
+        match message {
+            Some(message) => Self::new_with_message(params, &input_set, &J, message),
+            None => Self::new(params, &input_set, &J),
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use alloc::{borrow::ToOwned, vec::Vec};
+    use alloc::{borrow::ToOwned, sync::Arc, vec::Vec};
 
-    use curve25519_dalek::RistrettoPoint;
+    #[cfg(feature = "prover")]
+    use curve25519_dalek::Scalar;
+    use curve25519_dalek::{ristretto::CompressedRistretto, traits::Identity, RistrettoPoint};
     use rand_chacha::ChaCha12Rng;
     use rand_core::SeedableRng;
 
-    use crate::{InputSet, Parameters};
+    use crate::{parameters::ParametersBuilder, statement::StatementError, InputSet, Parameters, Statement};
+    #[cfg(feature = "prover")]
+    use crate::{proof::ProofError, Proof, Transcript, Witness};
 
     // Helper function to generate random vectors
     fn random_vector(size: usize) -> Vec<RistrettoPoint> {
@@ -161,12 +891,107 @@ mod test {
             .collect::<Vec<RistrettoPoint>>()
     }
 
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_input_set_equality() {
+        let M = random_vector(16);
+
+        // Independently constructed input sets with the same keys are equal
+        assert_eq!(InputSet::new(&M), M.iter().copied().collect::<InputSet>());
+
+        // Order matters
+        let mut reversed = M.clone();
+        reversed.reverse();
+        assert_ne!(InputSet::new(&reversed), InputSet::new(&M));
+
+        // Length matters
+        assert_ne!(InputSet::new(&M[1..]), InputSet::new(&M));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_statement_hash() {
+        extern crate std;
+        use std::collections::HashSet;
+
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let M = random_vector(16);
+        let J = RistrettoPoint::random(&mut rng);
+
+        // Independently constructed statements with the same components are equal and hash identically
+        let statement = Statement::new(&params, &Arc::new(InputSet::new(&M)), &J).unwrap();
+        let same = Statement::new(
+            &Arc::new(Parameters::new(2, 4).unwrap()),
+            &Arc::new(M.iter().copied().collect::<InputSet>()),
+            &J,
+        )
+        .unwrap();
+        assert!(statement == same);
+
+        // Each component distinguishes statements
+        let other_params = Arc::new(
+            ParametersBuilder::new()
+                .radix(2)
+                .digits(4)
+                .context(b"context")
+                .build()
+                .unwrap(),
+        );
+        let mut reversed = M.clone();
+        reversed.reverse();
+        let others = [
+            Statement::new(&other_params, &Arc::new(InputSet::new(&M)), &J).unwrap(),
+            Statement::new(&params, &Arc::new(InputSet::new(&reversed)), &J).unwrap(),
+            Statement::new(&params, &Arc::new(InputSet::new(&M)), &RistrettoPoint::random(&mut rng)).unwrap(),
+            Statement::new_with_message(&params, &Arc::new(InputSet::new(&M)), &J, b"message").unwrap(),
+        ];
+        assert!(others.iter().all(|other| other != &statement));
+
+        // Deduplication uses the same components
+        let mut set = HashSet::new();
+        assert!(set.insert(statement));
+        assert!(!set.insert(same));
+        for other in others {
+            assert!(set.insert(other));
+        }
+        assert_eq!(set.len(), 5);
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_matches_any_tag() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a statement
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let witness = Witness::random(&params, &mut rng);
+        let mut M = random_vector(usize::try_from(params.get_N()).unwrap());
+        M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        let J = witness.compute_linking_tag();
+        let statement = Statement::new(&params, &Arc::new(InputSet::new(&M)), &J).unwrap();
+
+        // Both variants find the first matching tag
+        let mut tags = random_vector(8);
+        for matches_any_tag in [Statement::matches_any_tag, Statement::matches_any_tag_ct] {
+            assert_eq!(matches_any_tag(&statement, &tags), None);
+            assert_eq!(matches_any_tag(&statement, &[]), None);
+        }
+        tags[5] = J;
+        tags[7] = J;
+        for matches_any_tag in [Statement::matches_any_tag, Statement::matches_any_tag_ct] {
+            assert_eq!(matches_any_tag(&statement, &tags), Some(5));
+            assert_eq!(matches_any_tag(&statement, &[J]), Some(0));
+        }
+    }
+
     #[test]
     #[allow(non_snake_case)]
     fn test_padding() {
         // Generate parameters
         let params = Parameters::new(2, 4).unwrap();
-        let N = params.get_N() as usize;
+        let N = usize::try_from(params.get_N()).unwrap();
 
         // Vector is empty
         assert!(InputSet::new_with_padding(&[], &params).is_err());
@@ -188,4 +1013,611 @@ mod test {
             InputSet::new(&M_padded)
         );
     }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_from_iter() {
+        let M = random_vector(16);
+
+        // Collecting from an iterator matches the slice constructor
+        assert_eq!(M.iter().copied().collect::<InputSet>(), InputSet::new(&M));
+
+        // Decompressing valid keys matches the slice constructor
+        let compressed = M
+            .iter()
+            .map(|item| item.compress())
+            .collect::<Vec<CompressedRistretto>>();
+        assert_eq!(InputSet::try_from(compressed.clone()).unwrap(), InputSet::new(&M));
+
+        // Any invalid key encoding is rejected
+        let mut evil_compressed = compressed;
+        evil_compressed[3] = CompressedRistretto([0xFF; 32]);
+        assert!(matches!(
+            InputSet::try_from(evil_compressed),
+            Err(StatementError::InvalidEncoding)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_input_set_size() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate parameters and a witness
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let N = usize::try_from(params.get_N()).unwrap();
+        let witness = Witness::new(&params, 0, &Scalar::random(&mut rng)).unwrap();
+        let J = witness.compute_linking_tag();
+
+        for size in [N - 1, N + 1] {
+            let mut M = random_vector(size);
+            M[0] = witness.compute_verification_key();
+            let input_set = Arc::new(InputSet::new(&M));
+
+            // The statement cannot be built from a mismatched input set
+            assert!(matches!(
+                Statement::new(&params, &input_set, &J),
+                Err(StatementError::InvalidParameter)
+            ));
+
+            // Even if a statement is built anyway, the prover rejects it
+            let statement = Statement {
+                params: params.clone(),
+                input_set,
+                J,
+                message: None,
+            };
+            assert!(matches!(
+                Proof::prove_with_rng(&witness, &statement, &mut rng, &mut Transcript::new(b"Test transcript")),
+                Err(ProofError::InvalidParameter)
+            ));
+            assert!(matches!(
+                Proof::prove_with_rng_vartime(&witness, &statement, &mut rng, &mut Transcript::new(b"Test transcript")),
+                Err(ProofError::InvalidParameter)
+            ));
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_new_checked() {
+        let M = random_vector(16);
+        assert_eq!(InputSet::new_checked(&M).unwrap(), InputSet::new(&M));
+
+        // Duplicates are rejected, whether adjacent or not
+        for index in [1, 15] {
+            let mut evil_M = M.clone();
+            evil_M[index] = M[0];
+            assert!(matches!(
+                InputSet::new_checked(&evil_M),
+                Err(StatementError::DuplicateVerificationKey)
+            ));
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_serialize_deserialize() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate statements with and without a message
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let M = random_vector(usize::try_from(params.get_N()).unwrap());
+        let input_set = Arc::new(InputSet::new(&M));
+        let J = RistrettoPoint::random(&mut rng);
+        let statements = [
+            Statement::new(&params, &input_set, &J).unwrap(),
+            Statement::new_with_message(&params, &input_set, &J, b"message").unwrap(),
+            Statement::new_with_message(&params, &input_set, &J, &[]).unwrap(),
+        ];
+
+        for statement in &statements {
+            // Serialization round-trips
+            let serialized = statement.to_bytes();
+            assert!(Statement::from_bytes(&serialized, &params).unwrap() == *statement);
+
+            // Any truncation or extension is rejected
+            for len in 0..serialized.len() {
+                assert!(Statement::from_bytes(&serialized[..len], &params).is_err());
+            }
+            let mut extended = serialized.clone();
+            extended.push(0);
+            assert!(Statement::from_bytes(&extended, &params).is_err());
+
+            // Different parameters are rejected
+            let other_params = Arc::new(
+                Parameters::new_with_generators(
+                    2,
+                    4,
+                    &RistrettoPoint::random(&mut rng),
+                    params.linkability_generator(),
+                )
+                .unwrap(),
+            );
+            assert!(matches!(
+                Statement::from_bytes(&serialized, &other_params),
+                Err(StatementError::HashMismatch)
+            ));
+
+            // Replacing a verification key with another valid point is detected by the hash
+            let mut evil_serialized = serialized.clone();
+            evil_serialized[40..72].copy_from_slice(RistrettoPoint::random(&mut rng).compress().as_bytes());
+            assert!(matches!(
+                Statement::from_bytes(&evil_serialized, &params),
+                Err(StatementError::HashMismatch)
+            ));
+
+            // Tampering with the encoded hash is detected
+            let hash_start = 40 + 32 * M.len();
+            let mut evil_serialized = serialized.clone();
+            evil_serialized[hash_start] ^= 1;
+            assert!(matches!(
+                Statement::from_bytes(&evil_serialized, &params),
+                Err(StatementError::HashMismatch)
+            ));
+        }
+
+        // A huge input set size is rejected without allocating for it
+        let mut evil_serialized = statements[0].to_bytes();
+        evil_serialized[32..40].copy_from_slice(&u64::MAX.to_le_bytes());
+        assert!(matches!(
+            Statement::from_bytes(&evil_serialized, &params),
+            Err(StatementError::FailedDeserialization)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_index_of() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate parameters and a witness whose key is in the input set
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let witness = Witness::random(&params, &mut rng);
+        let mut M = random_vector(usize::try_from(params.get_N()).unwrap());
+        M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        let input_set = Arc::new(InputSet::new(&M));
+        let statement = Statement::new(&params, &input_set, &witness.compute_linking_tag()).unwrap();
+
+        // The witness key is found at its index, and the index can be used to rebuild the witness
+        let key = witness.compute_verification_key();
+        assert_eq!(input_set.index_of(&key), Some(witness.get_l()));
+        assert_eq!(statement.input_set_index_of(&key), Some(witness.get_l()));
+        let rebuilt = Witness::new(&params, statement.input_set_index_of(&key).unwrap(), witness.get_r()).unwrap();
+        assert_eq!(rebuilt.get_l(), witness.get_l());
+
+        // Every key is found, and missing keys are not
+        for (l, item) in (0u64..).zip(M.iter()) {
+            assert_eq!(input_set.index_of(item), Some(l));
+        }
+        assert_eq!(input_set.index_of(&RistrettoPoint::random(&mut rng)), None);
+
+        // Duplicates return the first index
+        let duplicates = InputSet::new(&[M[0], M[1], M[0]]);
+        assert_eq!(duplicates.index_of(&M[0]), Some(0));
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_extended() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Start with a smaller input set containing the witness key
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let small_params = Arc::new(Parameters::new(2, 3).unwrap());
+        let witness = Witness::random(&small_params, &mut rng);
+        let mut M = random_vector(usize::try_from(params.get_N()).unwrap());
+        M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        let (head, tail) = M.split_at(usize::try_from(small_params.get_N()).unwrap());
+        let input_set = InputSet::new(head);
+
+        // Extending matches building from all keys at once, including in stages and with no keys
+        let extended = input_set.extended(tail);
+        assert!(extended == InputSet::new(&M));
+        assert_eq!(extended.get_keys(), M.as_slice());
+        assert!(input_set.extended(&tail[..3]).extended(&tail[3..]) == extended);
+        assert!(input_set.extended(&[]) == input_set);
+
+        // The witness index is still valid, so a proof with the extended input set verifies
+        let witness = Witness::new(&params, witness.get_l(), witness.get_r()).unwrap();
+        let statement = Statement::new(&params, &Arc::new(extended), &witness.compute_linking_tag()).unwrap();
+        let mut transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&statement, &mut transcript).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_new_window() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a large set of verification keys
+        let params = Arc::new(Parameters::new(2, 3).unwrap());
+        let N = usize::try_from(params.get_N()).unwrap();
+        let M = random_vector(4 * N);
+        let full_set = InputSet::new(&M);
+        let J = RistrettoPoint::random(&mut rng);
+
+        // Each window matches a statement built from only its keys, and shares the full set's keys
+        for offset in [0, 1, N, 3 * N] {
+            let statement = Statement::new_window(&params, &full_set, u64::try_from(offset).unwrap(), &J).unwrap();
+            let window = &M[offset..offset + N];
+            assert!(statement == Statement::new(&params, &Arc::new(InputSet::new(window)), &J).unwrap());
+            assert_eq!(statement.get_input_set().get_keys(), window);
+            assert!(core::ptr::eq(
+                statement.get_input_set().get_keys().as_ptr(),
+                full_set.get_keys()[offset..].as_ptr()
+            ));
+
+            // A window of a window uses offsets relative to the outer window
+            let nested = statement.get_input_set().window(1, 2).unwrap();
+            assert!(nested == InputSet::new(&window[1..3]));
+            assert!(nested.extended(&window[3..]) == InputSet::new(&window[1..]));
+        }
+
+        // A proof for the window verifies against the windowed statement
+        let witness = Witness::random(&params, &mut rng);
+        let mut keys = M.clone();
+        keys[N + usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        let statement = Statement::new_window(
+            &params,
+            &InputSet::new(&keys),
+            u64::try_from(N).unwrap(),
+            &witness.compute_linking_tag(),
+        )
+        .unwrap();
+        let window_statement = Statement::new(
+            &params,
+            &Arc::new(InputSet::new(&keys[N..2 * N])),
+            &witness.compute_linking_tag(),
+        )
+        .unwrap();
+        let mut transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &window_statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&statement, &mut transcript).is_ok());
+
+        // Windows must fit within the full set
+        for offset in [3 * N + 1, 4 * N] {
+            assert!(Statement::new_window(&params, &full_set, u64::try_from(offset).unwrap(), &J).is_err());
+        }
+        assert!(Statement::new_window(&params, &full_set, u64::MAX, &J).is_err());
+    }
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_windows() {
+        const n: u32 = 2;
+        const m: u32 = 2;
+        const N: usize = 4;
+        let M = random_vector(11);
+
+        // Windows advance by `N - overlap` keys, with the last window ending at the last key
+        for (overlap, starts) in [
+            (0, [0, 4, 7].as_slice()),
+            (1, &[0, 3, 6, 7]),
+            (2, &[0, 2, 4, 6, 7]),
+            (3, &[0, 1, 2, 3, 4, 5, 6, 7]),
+        ] {
+            let windows = InputSet::windows(&M, n, m, overlap).unwrap();
+            assert_eq!(windows.len(), starts.len());
+
+            // Each window is identical to an input set built from only its keys, and all windows share the keys
+            for (window, start) in windows.iter().zip(starts.iter()) {
+                assert_eq!(window.get_keys(), &M[*start..*start + N]);
+                assert!(*window == InputSet::new(&M[*start..*start + N]));
+                assert!(Arc::ptr_eq(&window.M, &windows[0].M));
+                assert_eq!(window.range, *start..*start + N);
+            }
+        }
+
+        // A single window is allowed
+        let windows = InputSet::windows(&M[..N], n, m, 0).unwrap();
+        assert_eq!(windows.len(), 1);
+        assert!(windows[0] == InputSet::new(&M[..N]));
+
+        // The overlap must be less than the window size
+        for overlap in [4, 5, u32::MAX] {
+            assert!(matches!(
+                InputSet::windows(&M, n, m, overlap),
+                Err(StatementError::InvalidParameter)
+            ));
+        }
+
+        // There must be enough keys for a window
+        assert!(matches!(
+            InputSet::windows(&M[..N - 1], n, m, 0),
+            Err(StatementError::InvalidParameter)
+        ));
+
+        // The dimensions must be valid
+        assert!(matches!(
+            InputSet::windows(&M, 1, m, 0),
+            Err(StatementError::InvalidParameter)
+        ));
+        assert!(matches!(
+            InputSet::windows(&M, n, u32::MAX, 0),
+            Err(StatementError::InvalidParameter)
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_effective_size() {
+        let M = random_vector(4);
+        assert_eq!(InputSet::new(&M).effective_size(), 4);
+        assert_eq!(InputSet::new(&[]).effective_size(), 0);
+
+        // Duplicates are only counted once, however many times they appear
+        let duplicates = [M[0], M[1], M[0], M[2], M[0], M[1]];
+        assert_eq!(InputSet::new(&duplicates).effective_size(), 3);
+
+        // The identity is never counted
+        let identity = RistrettoPoint::identity();
+        assert_eq!(InputSet::new(&[M[0], identity, M[1], identity]).effective_size(), 2);
+        assert_eq!(InputSet::new(&[identity; 4]).effective_size(), 0);
+
+        // Padding reduces the effective size
+        let params = Parameters::new(2, 2).unwrap();
+        assert_eq!(
+            InputSet::new_with_padding(&M[..2], &params).unwrap().effective_size(),
+            2
+        );
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_sorted() {
+        let M = random_vector(16);
+
+        // Sorting does not depend on the input order
+        let input_set = InputSet::new_sorted(&M);
+        let mut M_reversed = M.clone();
+        M_reversed.reverse();
+        assert_eq!(InputSet::new_sorted(&M_reversed), input_set);
+        assert!(input_set
+            .get_keys()
+            .windows(2)
+            .all(|pair| pair[0].compress().as_bytes() < pair[1].compress().as_bytes()));
+
+        // Each verification key can be located after sorting
+        for (l, key) in (0u64..).zip(M.iter()) {
+            let (sorted, new_l) = InputSet::sorted_with_index(&M, l).unwrap();
+            assert_eq!(sorted, input_set);
+            assert_eq!(&sorted.get_keys()[usize::try_from(new_l).unwrap()], key);
+        }
+        assert!(matches!(
+            InputSet::sorted_with_index(&M, 16),
+            Err(StatementError::InvalidParameter)
+        ));
+
+        // Duplicates are detected regardless of their positions
+        assert!(!input_set.has_duplicates());
+        let mut evil_M = M.clone();
+        evil_M[15] = M[0];
+        assert!(InputSet::new(&evil_M).has_duplicates());
+        assert!(InputSet::new_sorted(&evil_M).has_duplicates());
+
+        // Duplicates keep their relative order
+        let (_, new_l) = InputSet::sorted_with_index(&evil_M, 0).unwrap();
+        assert_eq!(InputSet::sorted_with_index(&evil_M, 15).unwrap().1, new_l + 1);
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_message() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate parameters, a witness, and an input set
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let witness = Witness::random(&params, &mut rng);
+        let mut M = random_vector(usize::try_from(params.get_N()).unwrap());
+        M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        let input_set = Arc::new(InputSet::new(&M));
+        let J = witness.compute_linking_tag();
+
+        // Generate statements with and without messages
+        let statement = Statement::new_with_message(&params, &input_set, &J, b"Test message").unwrap();
+        assert_eq!(statement.get_message(), Some(b"Test message".as_slice()));
+        let evil_statements = [
+            Statement::new(&params, &input_set, &J).unwrap(),
+            Statement::new_with_message(&params, &input_set, &J, b"Evil message").unwrap(),
+            Statement::new_with_message(&params, &input_set, &J, &[]).unwrap(),
+        ];
+        assert_eq!(evil_statements[0].get_message(), None);
+
+        // The proof verifies only against the same message
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&statement, &mut transcript.clone()).is_ok());
+        for evil_statement in &evil_statements {
+            assert!(proof.verify(evil_statement, &mut transcript.clone()).is_err());
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_merkle_root() {
+        // Roots depend on the verification keys and their order, for any input set size
+        for size in [1, 2, 9, 16] {
+            let M = random_vector(size);
+            let root = InputSet::new(&M).merkle_root();
+            assert_eq!(InputSet::new(&M).merkle_root(), root);
+
+            let mut M_reversed = M.clone();
+            M_reversed.reverse();
+            if size > 1 {
+                assert_ne!(InputSet::new(&M_reversed).merkle_root(), root);
+            }
+
+            let mut evil_M = M.clone();
+            evil_M[size - 1] = random_vector(size + 1)[size];
+            assert_ne!(InputSet::new(&evil_M).merkle_root(), root);
+        }
+
+        // A single verification key is its own leaf
+        let M = random_vector(1);
+        let mut leaf = [0u8; 33];
+        leaf[1..].copy_from_slice(M[0].compress().as_bytes());
+        assert_eq!(InputSet::new(&M).merkle_root(), *blake3::hash(&leaf).as_bytes());
+
+        // Roots depend on the hasher
+        let input_set = InputSet::new(&random_vector(9));
+        let evil_hasher = |bytes: &[u8]| *blake3::keyed_hash(&[1u8; 32], bytes).as_bytes();
+        assert_ne!(input_set.merkle_root_with(evil_hasher), input_set.merkle_root());
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_new_from_root() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate parameters, a witness, and an input set
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let witness = Witness::random(&params, &mut rng);
+        let mut M = random_vector(usize::try_from(params.get_N()).unwrap());
+        M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        let input_set = Arc::new(InputSet::new(&M));
+        let root = input_set.merkle_root();
+        let J = witness.compute_linking_tag();
+
+        // A statement from the matching root is the same as one built directly
+        let statement = Statement::new_from_root(&params, &input_set, &root, &J).unwrap();
+        assert!(statement == Statement::new(&params, &input_set, &J).unwrap());
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&statement, &mut transcript.clone()).is_ok());
+
+        // An input set with a different root is rejected
+        M.swap(0, 1);
+        let evil_input_set = Arc::new(InputSet::new(&M));
+        assert!(matches!(
+            Statement::new_from_root(&params, &evil_input_set, &root, &J),
+            Err(StatementError::MerkleRootMismatch)
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_identity_verification_key() {
+        // Generate parameters
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let N = usize::try_from(params.get_N()).unwrap();
+
+        // Place the identity group element into the input set
+        let mut M = random_vector(N);
+        M[1] = RistrettoPoint::identity();
+        let input_set = Arc::new(InputSet::new(&M));
+
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let J = RistrettoPoint::random(&mut rng);
+        assert!(matches!(
+            Statement::new(&params, &input_set, &J),
+            Err(StatementError::IdentityVerificationKey)
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_identity_linking_tag() {
+        // Generate parameters
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let N = usize::try_from(params.get_N()).unwrap();
+
+        // Use the identity group element as the linking tag
+        let input_set = Arc::new(InputSet::new(&random_vector(N)));
+        assert!(matches!(
+            Statement::new(&params, &input_set, &RistrettoPoint::identity()),
+            Err(StatementError::IdentityLinkingTag)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    fn test_sparse() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate parameters and a base input set
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let N = usize::try_from(params.get_N()).unwrap();
+        let base = Arc::new(InputSet::new(&random_vector(N)));
+
+        // Override some keys, including the witness verification key
+        let witness = Witness::random(&params, &mut rng);
+        let other = (witness.get_l() + 1) % params.get_N();
+        let overrides = [
+            (witness.get_l(), witness.compute_verification_key()),
+            (other, RistrettoPoint::random(&mut rng)),
+        ];
+        let J = witness.compute_linking_tag();
+        let sparse = Statement::sparse(&params, &base, &overrides, &J).unwrap();
+
+        // Materialize the same input set
+        let mut M = base.get_keys().to_vec();
+        for (index, key) in overrides {
+            M[usize::try_from(index).unwrap()] = key;
+        }
+        let materialized = Statement::new(&params, &Arc::new(InputSet::new(&M)), &J).unwrap();
+        assert!(sparse == materialized);
+
+        // The sparse input set shares the base keys, and reads through the overrides
+        let sparse_set = sparse.get_input_set();
+        assert!(Arc::ptr_eq(&sparse_set.M, &base.M));
+        assert_eq!(sparse_set.overrides.len(), overrides.len());
+        assert!(sparse_set.keys().copied().eq(M.iter().copied()));
+        assert_eq!(sparse_set.keys().len(), N);
+        assert_eq!(sparse_set.get_keys(), M.as_slice());
+
+        // A proof for one statement verifies against the other, including with chunked verification
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &sparse, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&materialized, &mut transcript.clone()).is_ok());
+        assert!(proof.verify(&sparse, &mut transcript.clone()).is_ok());
+        for chunk_size in 1..=N {
+            assert!(proof
+                .verify_chunked(&sparse, &mut transcript.clone(), chunk_size)
+                .is_ok());
+        }
+        let proof = Proof::prove_with_rng(&witness, &materialized, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&sparse, &mut transcript.clone()).is_ok());
+
+        // Overrides can be applied on top of a sparse input set, replacing any at the same index
+        let replacement = (other, RistrettoPoint::random(&mut rng));
+        let nested = Statement::sparse(&params, sparse.get_input_set(), &[replacement], &J).unwrap();
+        M[usize::try_from(other).unwrap()] = replacement.1;
+        assert!(nested == Statement::new(&params, &Arc::new(InputSet::new(&M)), &J).unwrap());
+        assert_eq!(nested.get_input_set().overrides.len(), overrides.len());
+
+        // Windows keep the overrides that fall within them
+        let wide = Arc::new(InputSet::new(&random_vector(2 * N)));
+        let wide_sparse = wide
+            .with_overrides(&[(params.get_N(), witness.compute_verification_key())])
+            .unwrap();
+        let window = Arc::new(wide_sparse.window(params.get_N(), params.get_N()).unwrap());
+        let mut window_keys = wide.get_keys()[N..].to_vec();
+        window_keys[0] = witness.compute_verification_key();
+        assert!(*window == InputSet::new(&window_keys));
+        assert!(window.keys().copied().eq(window_keys.iter().copied()));
+        let window_statement = Statement::sparse(&params, &window, &[], &J).unwrap();
+        assert!(window_statement == Statement::new(&params, &Arc::new(InputSet::new(&window_keys)), &J).unwrap());
+        assert!(wide_sparse.window(0, params.get_N()).unwrap().overrides.is_empty());
+
+        // Invalid and repeated override indexes are rejected
+        let evil_overrides = [(params.get_N(), RistrettoPoint::random(&mut rng))];
+        assert!(Statement::sparse(&params, &base, &evil_overrides, &J).is_err());
+        let evil_overrides = [overrides[1], overrides[1]];
+        assert!(Statement::sparse(&params, &base, &evil_overrides, &J).is_err());
+
+        // Without overrides, the base input set is used directly
+        assert!(Arc::ptr_eq(
+            Statement::sparse(&params, &base, &[], &J).unwrap().get_input_set(),
+            &base
+        ));
+    }
 }
@@ -0,0 +1,258 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::fmt::Write;
+
+use curve25519_dalek::RistrettoPoint;
+use rand_core::CryptoRngCore;
+
+use crate::{proof::ProofError, util::NullRng, InputSet, Parameters, Proof, Statement, Transcript, Witness};
+
+// Transcript label used for proofs
+const TRANSCRIPT_LABEL: &[u8] = b"Triptych test vector";
+
+// Message bound into the proof transcript
+const MESSAGE: &[u8] = b"Triptych test vector message";
+
+/// A test vector capturing the full lifecycle of a Triptych proof, for cross-implementation conformance testing.
+///
+/// The vector is generated deterministically from a seed, and contains the parameters, witness, statement, message,
+/// and resulting proof. It can be rendered as JSON using [`TestVector::to_json`], with the
+/// following schema:
+///
+/// ```text
+/// {
+///   "n": <integer>,                  // base
+///   "m": <integer>,                  // exponent
+///   "seed": <integer>,               // seed used to generate the vector
+///   "parameters": {
+///     "G": <hex>,                    // compressed generator `G`
+///     "U": <hex>,                    // compressed generator `U`
+///     "CommitmentG": [<hex>, ...],   // compressed commitment generators, `n*m` of them
+///     "CommitmentH": <hex>           // compressed commitment masking generator
+///   },
+///   "witness": {
+///     "l": <integer>,                // signing key index
+///     "r": <hex>                     // canonical signing key scalar encoding
+///   },
+///   "statement": {
+///     "M": [<hex>, ...],             // compressed verification keys, `n**m` of them
+///     "J": <hex>                     // compressed linking tag
+///   },
+///   "message": <hex>,                // message bytes
+///   "proof": <hex>                   // canonical proof encoding from `Proof::to_bytes`
+/// }
+/// ```
+///
+/// All hex strings are lowercase and unprefixed.
+///
+/// To reproduce the proof, initialize a Merlin transcript with the label `Triptych test vector`, append the message
+/// with the label `message`, and generate the proof using the transcript-based random number generator described in
+/// [`generate_test_vector`].
+/// The proof must verify against the statement using a transcript constructed in the same way.
+///
+/// Group elements are stored in compressed form, and scalars in canonical form.
+#[allow(non_snake_case)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TestVector {
+    /// The base `n`.
+    pub n: u32,
+    /// The exponent `m`.
+    pub m: u32,
+    /// The seed used to generate this vector.
+    pub seed: u64,
+    /// The generator `G`.
+    pub G: [u8; 32],
+    /// The generator `U`.
+    pub U: [u8; 32],
+    /// The commitment generators.
+    pub CommitmentG: Vec<[u8; 32]>,
+    /// The commitment masking generator.
+    pub CommitmentH: [u8; 32],
+    /// The signing key index.
+    pub l: u64,
+    /// The signing key.
+    pub r: [u8; 32],
+    /// The verification keys.
+    pub M: Vec<[u8; 32]>,
+    /// The linking tag.
+    pub J: [u8; 32],
+    /// The message bound into the proof transcript.
+    pub message: Vec<u8>,
+    /// The canonical proof encoding.
+    pub proof: Vec<u8>,
+}
+
+impl TestVector {
+    /// Render this [`TestVector`] as JSON according to the documented schema.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        // Writing to a `String` cannot fail
+        let _ = writeln!(json, "{{");
+        let _ = writeln!(json, "  \"n\": {},", self.n);
+        let _ = writeln!(json, "  \"m\": {},", self.m);
+        let _ = writeln!(json, "  \"seed\": {},", self.seed);
+        let _ = writeln!(json, "  \"parameters\": {{");
+        let _ = writeln!(json, "    \"G\": \"{}\",", hex(&self.G));
+        let _ = writeln!(json, "    \"U\": \"{}\",", hex(&self.U));
+        let _ = writeln!(json, "    \"CommitmentG\": [{}],", hex_list(&self.CommitmentG));
+        let _ = writeln!(json, "    \"CommitmentH\": \"{}\"", hex(&self.CommitmentH));
+        let _ = writeln!(json, "  }},");
+        let _ = writeln!(json, "  \"witness\": {{");
+        let _ = writeln!(json, "    \"l\": {},", self.l);
+        let _ = writeln!(json, "    \"r\": \"{}\"", hex(&self.r));
+        let _ = writeln!(json, "  }},");
+        let _ = writeln!(json, "  \"statement\": {{");
+        let _ = writeln!(json, "    \"M\": [{}],", hex_list(&self.M));
+        let _ = writeln!(json, "    \"J\": \"{}\"", hex(&self.J));
+        let _ = writeln!(json, "  }},");
+        let _ = writeln!(json, "  \"message\": \"{}\",", hex(&self.message));
+        let _ = writeln!(json, "  \"proof\": \"{}\"", hex(&self.proof));
+        let _ = writeln!(json, "}}");
+
+        json
+    }
+}
+
+/// Generate a [`TestVector`] deterministically from parameters `n` and `m` and a `seed`.
+///
+/// All randomness, including that used by the prover, comes from a Merlin transcript random number generator built
+/// from a transcript with label `Triptych test vector seed` and the `seed` appended with label `seed`, finalized with
+/// an all-zero external generator. The witness is generated first using [`Witness::random`], followed by the
+/// remaining verification keys in index order using [`RistrettoPoint::random`].
+///
+/// If the parameters are invalid, returns a [`ProofError`].
+#[allow(non_snake_case)]
+pub fn generate_test_vector(n: u32, m: u32, seed: u64) -> Result<TestVector, ProofError> {
+    let params = Arc::new(Parameters::new(n, m).map_err(|_| ProofError::InvalidParameter)?);
+
+    // Set up the deterministic random number generator
+    let mut seed_transcript = Transcript::new(b"Triptych test vector seed");
+    seed_transcript.append_u64(b"seed", seed);
+    let mut rng = seed_transcript.build_rng().finalize(&mut NullRng);
+
+    // Generate the witness and statement
+    let witness = Witness::random(&params, &mut rng);
+    let M = generate_input_set(&witness, &mut rng);
+    let input_set = Arc::new(InputSet::new(&M));
+    let J = witness.compute_linking_tag();
+    let statement = Statement::new(&params, &input_set, &J).map_err(|_| ProofError::InvalidParameter)?;
+
+    // Generate the proof
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    transcript.append_message(b"message", MESSAGE);
+    let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript)?;
+
+    Ok(TestVector {
+        n,
+        m,
+        seed,
+        G: params.base_generator().compress().to_bytes(),
+        U: params.linkability_generator().compress().to_bytes(),
+        CommitmentG: params
+            .commitment_generators()
+            .iter()
+            .map(|point| point.compress().to_bytes())
+            .collect(),
+        CommitmentH: params.commitment_blinding_generator().compress().to_bytes(),
+        l: witness.get_l(),
+        r: witness.get_r().to_bytes(),
+        M: M.iter().map(|point| point.compress().to_bytes()).collect(),
+        J: J.compress().to_bytes(),
+        message: MESSAGE.to_vec(),
+        proof: proof.to_bytes(),
+    })
+}
+
+// Generate verification keys, placing the witness verification key at its index
+#[allow(non_snake_case)]
+fn generate_input_set<R: CryptoRngCore>(witness: &Witness, rng: &mut R) -> Vec<RistrettoPoint> {
+    (0..witness.get_params().get_N())
+        .map(|i| {
+            if i == witness.get_l() {
+                witness.compute_verification_key()
+            } else {
+                RistrettoPoint::random(rng)
+            }
+        })
+        .collect()
+}
+
+// Encode bytes as lowercase hex
+fn hex(bytes: &[u8]) -> String {
+    let mut result = String::with_capacity(bytes.len().saturating_mul(2));
+    for byte in bytes {
+        // Writing to a `String` cannot fail
+        let _ = write!(result, "{byte:02x}");
+    }
+
+    result
+}
+
+// Encode a list of byte arrays as a comma-separated list of quoted hex strings
+fn hex_list(items: &[[u8; 32]]) -> String {
+    items
+        .iter()
+        .map(|item| {
+            let mut quoted = String::from("\"");
+            quoted.push_str(&hex(item));
+            quoted.push('"');
+            quoted
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::sync::Arc;
+
+    use curve25519_dalek::{ristretto::CompressedRistretto, RistrettoPoint};
+
+    use super::{generate_test_vector, MESSAGE, TRANSCRIPT_LABEL};
+    use crate::{InputSet, Parameters, Proof, Statement, Transcript};
+
+    // A checked-in vector that must never change
+    const FIXTURE: &str = include_str!("../test_vectors/n2_m2_seed0.json");
+
+    #[test]
+    fn test_reproducible() {
+        // The same seed must reproduce identical vectors
+        let vector = generate_test_vector(2, 3, 1).unwrap();
+        assert_eq!(vector, generate_test_vector(2, 3, 1).unwrap());
+        assert_eq!(vector.to_json(), generate_test_vector(2, 3, 1).unwrap().to_json());
+
+        // A different seed must not
+        assert_ne!(vector, generate_test_vector(2, 3, 2).unwrap());
+    }
+
+    #[test]
+    fn test_fixture() {
+        assert_eq!(generate_test_vector(2, 2, 0).unwrap().to_json(), FIXTURE);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_verify() {
+        let vector = generate_test_vector(2, 2, 0).unwrap();
+
+        // Reconstruct the statement and proof from the vector
+        let params = Arc::new(Parameters::new(vector.n, vector.m).unwrap());
+        let M = vector
+            .M
+            .iter()
+            .map(|bytes| CompressedRistretto(*bytes).decompress().unwrap())
+            .collect::<alloc::vec::Vec<RistrettoPoint>>();
+        let input_set = Arc::new(InputSet::new(&M));
+        let J = CompressedRistretto(vector.J).decompress().unwrap();
+        let statement = Statement::new(&params, &input_set, &J).unwrap();
+        let proof = Proof::from_bytes(&vector.proof).unwrap();
+
+        // The proof must verify
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_message(b"message", MESSAGE);
+        assert!(proof.verify(&statement, &mut transcript).is_ok());
+    }
+}
@@ -0,0 +1,109 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{sync::Arc, vec::Vec};
+
+use curve25519_dalek::RistrettoPoint;
+use rand_core::CryptoRngCore;
+
+use crate::{parameters::ParameterError, InputSet, Parameters, Statement, Witness};
+
+/// Generate a random [`Witness`] and a [`Statement`] that it is valid for.
+///
+/// This is intended for tests and benchmarks that need valid inputs. It generates [`Parameters`] with base `n` and
+/// exponent `m`, a random [`Witness`], and an [`InputSet`] of random verification keys that contains the witness
+/// verification key at its index. The [`Statement`] uses the witness linking tag and has no message.
+///
+/// The input set has `n**m` verification keys, so keep the parameters small. If the parameters are invalid, returns a
+/// [`ParameterError`].
+#[allow(non_snake_case)]
+pub fn random_witness_statement<R: CryptoRngCore>(
+    n: u32,
+    m: u32,
+    rng: &mut R,
+) -> Result<(Witness, Statement), ParameterError> {
+    random_witness_statement_with_params(&Arc::new(Parameters::new(n, m)?), rng)
+}
+
+/// Generate a random [`Witness`] and a [`Statement`] that it is valid for, using existing [`Parameters`] `params`.
+///
+/// This is like [`random_witness_statement`], but is useful when the parameters have custom generators or a context.
+/// If the statement can't be built, returns a [`ParameterError`].
+#[allow(non_snake_case)]
+pub fn random_witness_statement_with_params<R: CryptoRngCore>(
+    params: &Arc<Parameters>,
+    rng: &mut R,
+) -> Result<(Witness, Statement), ParameterError> {
+    // Generate the witness and an input set containing its verification key
+    let witness = Witness::random(params, rng);
+    let M = (0..params.get_N())
+        .map(|i| {
+            if i == witness.get_l() {
+                witness.compute_verification_key()
+            } else {
+                RistrettoPoint::random(rng)
+            }
+        })
+        .collect::<Vec<RistrettoPoint>>();
+
+    // This can only fail if a random verification key is the identity group element
+    let statement = Statement::new(params, &Arc::new(InputSet::new(&M)), &witness.compute_linking_tag())
+        .map_err(|_| ParameterError::InvalidParameter)?;
+
+    Ok((witness, statement))
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::sync::Arc;
+
+    use curve25519_dalek::RistrettoPoint;
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use super::{random_witness_statement, random_witness_statement_with_params};
+    use crate::{Parameters, Proof, Transcript};
+
+    #[test]
+    fn test_random_witness_statement() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // The witness is valid for the statement
+        for (n, m) in [(2, 2), (3, 2), (2, 4)] {
+            let (witness, statement) = random_witness_statement(n, m, &mut rng).unwrap();
+            assert!(witness.get_params() == statement.get_params());
+            assert_eq!(statement.get_params().get_n(), n);
+            assert_eq!(statement.get_params().get_m(), m);
+
+            let mut transcript = Transcript::new(b"Test transcript");
+            let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+            assert!(proof.verify(&statement, &mut transcript).is_ok());
+        }
+
+        // Invalid parameters are rejected
+        assert!(random_witness_statement(1, 2, &mut rng).is_err());
+    }
+
+    #[test]
+    fn test_random_witness_statement_with_params() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // The supplied parameters are used
+        let params = Arc::new(
+            Parameters::new_with_generators(
+                2,
+                2,
+                &RistrettoPoint::random(&mut rng),
+                &RistrettoPoint::random(&mut rng),
+            )
+            .unwrap(),
+        );
+        let (witness, statement) = random_witness_statement_with_params(&params, &mut rng).unwrap();
+        assert!(Arc::ptr_eq(witness.get_params(), &params));
+        assert!(Arc::ptr_eq(statement.get_params(), &params));
+
+        let transcript = Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&statement, &mut transcript.clone()).is_ok());
+    }
+}
@@ -0,0 +1,143 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use std::{cell::RefCell, string::String, thread_local, vec::Vec};
+
+thread_local! {
+    static TRACE: RefCell<Vec<(String, Vec<u8>)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Record a transcript operation on the current thread.
+pub(crate) fn record(label: &[u8], bytes: &[u8]) {
+    TRACE.with(|trace| {
+        trace
+            .borrow_mut()
+            .push((String::from_utf8_lossy(label).into_owned(), bytes.to_vec()));
+    });
+}
+
+/// Remove and return all transcript operations recorded on the current thread, in the order they occurred.
+///
+/// Every message that proving or verification appends to the caller's [`Transcript`](`crate::Transcript`), and every
+/// challenge it draws, is recorded as a `(label, bytes)` pair. This includes any message hash, message digest, or
+/// auxiliary data bound before a proof, as well as the data bound by balance and unlinkable proofs. This is useful for
+/// auditing the Fiat-Shamir transform, such as by checking that the prover and verifier bind exactly the same data in
+/// the same order.
+///
+/// Records accumulate until they are removed, so call this before and after the operation you want to audit.
+/// Tracing records proof data and is intended only for review and testing; don't enable it in production.
+pub fn take() -> Vec<(String, Vec<u8>)> {
+    TRACE.with(|trace| trace.take())
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::{RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use super::take;
+    use crate::{testing::random_witness_statement, BalanceProof, InputSet, Proof, Transcript};
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_trace() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a statement
+        let (witness, statement) = random_witness_statement(2, 2, &mut rng).unwrap();
+        let transcript = Transcript::new(b"Test transcript");
+
+        // Discard anything recorded earlier on this thread
+        take();
+
+        // Record proving and verification separately
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        let prover_trace = take();
+        assert!(proof.verify(&statement, &mut transcript.clone()).is_ok());
+        let verifier_trace = take();
+
+        // The prover and verifier bind the same data in the same order
+        assert_eq!(prover_trace, verifier_trace);
+        assert!(take().is_empty());
+
+        // The operations follow the protocol order
+        let labels = prover_trace
+            .iter()
+            .map(|(label, _)| label.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(labels, [
+            "dom-sep", "version", "params", "M", "J", "A", "B", "C", "D", "X", "X", "Y", "Y", "xi", "f", "f", "z_A",
+            "z_C", "z"
+        ]);
+        assert_eq!(prover_trace[0].1, b"Triptych proof");
+        assert_eq!(prover_trace[4].1, statement.get_J().compress().as_bytes());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_trace_bindings() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a statement
+        let (witness, statement) = random_witness_statement(2, 2, &mut rng).unwrap();
+        let transcript = Transcript::new(b"Test transcript");
+        take();
+
+        // Auxiliary data is recorded before the proof
+        let aux: [(&[u8], &[u8]); 1] = [(b"label", b"data")];
+        let proof =
+            Proof::prove_with_rng_and_aux(&witness, &statement, &aux, &mut rng, &mut transcript.clone()).unwrap();
+        let prover_trace = take();
+        assert!(proof.verify_with_aux(&statement, &aux, &mut transcript.clone()).is_ok());
+        assert_eq!(prover_trace, take());
+        assert_eq!(prover_trace[0], ("aux count".into(), 1u64.to_le_bytes().to_vec()));
+        assert_eq!(prover_trace[1], ("aux label".into(), b"label".to_vec()));
+        assert_eq!(prover_trace[2], ("aux data".into(), b"data".to_vec()));
+
+        // Message digests are recorded before the proof
+        let digest = [1u8; 32];
+        let proof =
+            Proof::prove_with_rng_and_message_digest(&witness, &statement, &digest, &mut rng, &mut transcript.clone())
+                .unwrap();
+        let prover_trace = take();
+        assert!(proof
+            .verify_with_message_digest(&statement, &digest, &mut transcript.clone())
+            .is_ok());
+        assert_eq!(prover_trace, take());
+        assert_eq!(prover_trace[0], ("message digest".into(), digest.to_vec()));
+
+        // Balance proofs record their statement and commitments, and end with their responses
+        let G = statement.get_params().base_generator();
+        let delta = Scalar::random(&mut rng);
+        let C_out = RistrettoPoint::random(&mut rng);
+        let mut C = (0..statement.get_params().get_N())
+            .map(|_| RistrettoPoint::random(&mut rng))
+            .collect::<Vec<RistrettoPoint>>();
+        C[usize::try_from(witness.get_l()).unwrap()] = C_out + delta * G;
+        let commitments = Arc::new(InputSet::new(&C));
+        let proof = BalanceProof::prove_with_rng(
+            &witness,
+            &statement,
+            &commitments,
+            &C_out,
+            &delta,
+            &mut rng,
+            &mut transcript.clone(),
+        )
+        .unwrap();
+        let prover_trace = take();
+        assert!(proof
+            .verify(&statement, &commitments, &C_out, &mut transcript.clone())
+            .is_ok());
+        assert_eq!(prover_trace, take());
+        let labels = prover_trace
+            .iter()
+            .map(|(label, _)| label.as_str())
+            .collect::<Vec<&str>>();
+        assert_eq!(labels[..7], ["dom-sep", "version", "params", "M", "C", "J", "C_out"]);
+        assert_eq!(labels[labels.len() - 2..], ["z", "z1"]);
+    }
+}
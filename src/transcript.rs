@@ -7,36 +7,142 @@ use curve25519_dalek::{RistrettoPoint, Scalar};
 use merlin::TranscriptRng;
 use rand_core::CryptoRngCore;
 
-use crate::{proof::ProofError, Parameters, Statement, Transcript, Witness};
+#[cfg(feature = "prover")]
+use crate::Witness;
+use crate::{proof::ProofError, Parameters, Statement, Transcript};
+
+// Without the prover, there is never a witness to bind
+#[cfg(not(feature = "prover"))]
+type Witness = core::convert::Infallible;
 
 // Version identifier
-const VERSION: u64 = 0;
+pub(crate) const VERSION: u64 = 0;
 
 // Domain separator
 const DOMAIN: &str = "Triptych proof";
 
+// Number of bytes drawn from the external generator each time a transcript generator is built
+pub(crate) const EXTERNAL_RNG_BYTES: usize = 32;
+
+// Number of times the prover builds a transcript generator: on initialization and commitment
+pub(crate) const PROVER_RNG_BUILDS: usize = 2;
+
+/// A Fiat-Shamir transcript used for proving and verification.
+///
+/// Merlin's [`Transcript`] implements this, and is the default used throughout the crate's documentation and examples.
+/// Other implementations let deployments use a different hash for the Fiat-Shamir transform, but proofs are only
+/// interoperable between provers and verifiers that use the same implementation.
+///
+/// Implementations must be deterministic, and every challenge must depend on all labels and messages appended before
+/// it, in order. Because the prover derives its nonces from [`FiatShamirTranscript::witness_rng`], that generator must
+/// be cryptographically secure and must depend on the transcript state, the witness data, and the external generator.
+pub trait FiatShamirTranscript: Clone {
+    /// The generator produced by [`FiatShamirTranscript::witness_rng`].
+    type Rng: CryptoRngCore;
+
+    /// Append a labeled message to the transcript.
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]);
+
+    /// Append a labeled [`prim@u64`] to the transcript.
+    ///
+    /// By default, this appends the value as an 8-byte little-endian message.
+    fn append_u64(&mut self, label: &'static [u8], value: u64) {
+        self.append_message(label, &value.to_le_bytes());
+    }
+
+    /// Fill `dest` with labeled challenge bytes derived from the transcript.
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]);
+
+    /// Get a labeled challenge scalar derived from the transcript.
+    ///
+    /// By default, this uses wide reduction of 64 challenge bytes.
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> Scalar {
+        let mut bytes = [0u8; 64];
+        self.challenge_bytes(label, &mut bytes);
+
+        Scalar::from_bytes_mod_order_wide(&bytes)
+    }
+
+    /// Build a generator from the transcript state, labeled secret `witness` data, and an external generator.
+    ///
+    /// This does not modify the transcript.
+    fn witness_rng<R: CryptoRngCore>(&self, witness: &[(&'static [u8], &[u8])], external_rng: &mut R) -> Self::Rng;
+}
+
+impl FiatShamirTranscript for Transcript {
+    type Rng = TranscriptRng;
+
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        Transcript::append_message(self, label, message);
+    }
+
+    fn append_u64(&mut self, label: &'static [u8], value: u64) {
+        Transcript::append_u64(self, label, value);
+    }
+
+    fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+        Transcript::challenge_bytes(self, label, dest);
+    }
+
+    fn witness_rng<R: CryptoRngCore>(&self, witness: &[(&'static [u8], &[u8])], external_rng: &mut R) -> TranscriptRng {
+        witness
+            .iter()
+            .fold(self.build_rng(), |builder, (label, bytes)| {
+                builder.rekey_with_witness_bytes(label, bytes)
+            })
+            .finalize(external_rng)
+    }
+}
+
+/// Append a labeled message to a caller's transcript, recording it if tracing is enabled.
+///
+/// All data bound into a caller's transcript must go through this or the other recording helpers, so traces are
+/// complete.
+pub(crate) fn append_message<T: FiatShamirTranscript>(transcript: &mut T, label: &'static [u8], message: &[u8]) {
+    transcript.append_message(label, message);
+    #[cfg(feature = "trace")]
+    crate::trace::record(label, message);
+}
+
+/// Append a labeled [`prim@u64`] to a caller's transcript, recording it if tracing is enabled.
+pub(crate) fn append_u64<T: FiatShamirTranscript>(transcript: &mut T, label: &'static [u8], value: u64) {
+    transcript.append_u64(label, value);
+    #[cfg(feature = "trace")]
+    crate::trace::record(label, &value.to_le_bytes());
+}
+
+/// Fill `dest` with labeled challenge bytes from a caller's transcript, recording them if tracing is enabled.
+pub(crate) fn challenge_bytes<T: FiatShamirTranscript>(transcript: &mut T, label: &'static [u8], dest: &mut [u8]) {
+    transcript.challenge_bytes(label, dest);
+    #[cfg(feature = "trace")]
+    crate::trace::record(label, dest);
+}
+
 /// A Triptych proof transcript.
-pub(crate) struct ProofTranscript<'a, R: CryptoRngCore> {
-    transcript: &'a mut Transcript,
+pub(crate) struct ProofTranscript<'a, T: FiatShamirTranscript, R: CryptoRngCore> {
+    transcript: &'a mut T,
     witness: Option<&'a Witness>,
-    transcript_rng: TranscriptRng,
+    transcript_rng: T::Rng,
     external_rng: &'a mut R,
 }
 
-impl<'a, R: CryptoRngCore> ProofTranscript<'a, R> {
+impl<'a, T: FiatShamirTranscript, R: CryptoRngCore> ProofTranscript<'a, T, R> {
     /// Initialize a transcript.
     pub(crate) fn new(
-        transcript: &'a mut Transcript,
+        transcript: &'a mut T,
         statement: &Statement,
         external_rng: &'a mut R,
         witness: Option<&'a Witness>,
     ) -> Self {
         // Update the transcript
-        transcript.append_message(b"dom-sep", DOMAIN.as_bytes());
-        transcript.append_u64(b"version", VERSION);
-        transcript.append_message(b"params", statement.get_params().get_hash());
-        transcript.append_message(b"M", statement.get_input_set().get_hash());
-        transcript.append_message(b"J", statement.get_J().compress().as_bytes());
+        append_message(transcript, b"dom-sep", DOMAIN.as_bytes());
+        append_u64(transcript, b"version", VERSION);
+        append_message(transcript, b"params", statement.get_params().get_hash());
+        append_message(transcript, b"M", statement.get_input_set().get_hash());
+        append_message(transcript, b"J", statement.get_J().compress().as_bytes());
+        if let Some(message) = statement.get_message() {
+            append_message(transcript, b"message", message);
+        }
 
         // Set up the transcript generator
         let transcript_rng = Self::build_transcript_rng(transcript, witness, external_rng);
@@ -64,15 +170,15 @@ impl<'a, R: CryptoRngCore> ProofTranscript<'a, R> {
         let m = params.get_m() as usize;
 
         // Update the transcript
-        self.transcript.append_message(b"A", A.compress().as_bytes());
-        self.transcript.append_message(b"B", B.compress().as_bytes());
-        self.transcript.append_message(b"C", C.compress().as_bytes());
-        self.transcript.append_message(b"D", D.compress().as_bytes());
+        append_message(self.transcript, b"A", A.compress().as_bytes());
+        append_message(self.transcript, b"B", B.compress().as_bytes());
+        append_message(self.transcript, b"C", C.compress().as_bytes());
+        append_message(self.transcript, b"D", D.compress().as_bytes());
         for X_item in X {
-            self.transcript.append_message(b"X", X_item.compress().as_bytes());
+            append_message(self.transcript, b"X", X_item.compress().as_bytes());
         }
         for Y_item in Y {
-            self.transcript.append_message(b"Y", Y_item.compress().as_bytes());
+            append_message(self.transcript, b"Y", Y_item.compress().as_bytes());
         }
 
         // Update the transcript generator
@@ -80,7 +186,7 @@ impl<'a, R: CryptoRngCore> ProofTranscript<'a, R> {
 
         // Get the initial challenge using wide reduction
         let mut xi_bytes = [0u8; 64];
-        self.transcript.challenge_bytes(b"xi", &mut xi_bytes);
+        challenge_bytes(self.transcript, b"xi", &mut xi_bytes);
         let xi = Scalar::from_bytes_mod_order_wide(&xi_bytes);
 
         // Get powers of the challenge and confirm they are nonzero
@@ -100,16 +206,9 @@ impl<'a, R: CryptoRngCore> ProofTranscript<'a, R> {
 
     /// Run the Fiat-Shamir response phase
     #[allow(non_snake_case)]
-    pub(crate) fn response(mut self, f: &Vec<Vec<Scalar>>, z_A: &Scalar, z_C: &Scalar, z: &Scalar) -> TranscriptRng {
+    pub(crate) fn response(mut self, f: &Vec<Vec<Scalar>>, z_A: &Scalar, z_C: &Scalar, z: &Scalar) -> T::Rng {
         // Update the transcript
-        for f_row in f {
-            for f in f_row {
-                self.transcript.append_message(b"f", f.as_bytes());
-            }
-        }
-        self.transcript.append_message(b"z_A", z_A.as_bytes());
-        self.transcript.append_message(b"z_C", z_C.as_bytes());
-        self.transcript.append_message(b"z", z.as_bytes());
+        self.bind_response(f, z_A, z_C, z);
 
         // Update the transcript generator
         self.transcript_rng = Self::build_transcript_rng(self.transcript, self.witness, self.external_rng);
@@ -117,21 +216,131 @@ impl<'a, R: CryptoRngCore> ProofTranscript<'a, R> {
         self.transcript_rng
     }
 
+    /// Bind the response values into the transcript, without updating the transcript generator.
+    ///
+    /// The prover uses this so its transcript ends in the same state as the verifier's.
+    #[allow(non_snake_case)]
+    pub(crate) fn bind_response(&mut self, f: &Vec<Vec<Scalar>>, z_A: &Scalar, z_C: &Scalar, z: &Scalar) {
+        for f_row in f {
+            for f in f_row {
+                append_message(self.transcript, b"f", f.as_bytes());
+            }
+        }
+        append_message(self.transcript, b"z_A", z_A.as_bytes());
+        append_message(self.transcript, b"z_C", z_C.as_bytes());
+        append_message(self.transcript, b"z", z.as_bytes());
+    }
+
     /// Get a mutable reference to the transcript generator
-    pub(crate) fn as_mut_rng(&mut self) -> &mut TranscriptRng {
+    #[cfg(feature = "prover")]
+    pub(crate) fn as_mut_rng(&mut self) -> &mut T::Rng {
         &mut self.transcript_rng
     }
 
     /// Build a random number generator from a transcript, optionally binding in witness data.
-    fn build_transcript_rng(transcript: &Transcript, witness: Option<&Witness>, external_rng: &mut R) -> TranscriptRng {
-        if let Some(witness) = witness {
+    fn build_transcript_rng(transcript: &T, witness: Option<&Witness>, external_rng: &mut R) -> T::Rng {
+        match witness {
+            #[cfg(feature = "prover")]
+            Some(witness) => transcript.witness_rng(
+                &[
+                    (b"l", &witness.get_l().to_le_bytes()),
+                    (b"r", witness.get_r().as_bytes()),
+                ],
+                external_rng,
+            ),
+            #[cfg(not(feature = "prover"))]
+            Some(never) => match *never {},
+            None => transcript.witness_rng(&[], external_rng),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use blake3::Hasher;
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::{CryptoRngCore, SeedableRng};
+
+    use super::FiatShamirTranscript;
+    use crate::{testing::random_witness_statement, Proof, Transcript};
+
+    // A transcript that uses `BLAKE3` for the Fiat-Shamir transform
+    #[derive(Clone)]
+    struct Blake3Transcript {
+        hasher: Hasher,
+    }
+
+    impl Blake3Transcript {
+        fn new(label: &'static [u8]) -> Self {
+            let mut transcript = Self { hasher: Hasher::new() };
+            transcript.append_message(b"dom-sep", label);
+
             transcript
-                .build_rng()
-                .rekey_with_witness_bytes(b"l", &witness.get_l().to_le_bytes())
-                .rekey_with_witness_bytes(b"r", witness.get_r().as_bytes())
-                .finalize(external_rng)
-        } else {
-            transcript.build_rng().finalize(external_rng)
         }
     }
+
+    impl FiatShamirTranscript for Blake3Transcript {
+        type Rng = ChaCha12Rng;
+
+        fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+            self.hasher.update(&(label.len() as u64).to_le_bytes());
+            self.hasher.update(label);
+            self.hasher.update(&(message.len() as u64).to_le_bytes());
+            self.hasher.update(message);
+        }
+
+        fn challenge_bytes(&mut self, label: &'static [u8], dest: &mut [u8]) {
+            self.append_message(label, &[]);
+            self.hasher.finalize_xof().fill(dest);
+
+            // Bind the challenge so later ones are distinct
+            self.append_message(b"challenge", dest);
+        }
+
+        fn witness_rng<R: CryptoRngCore>(
+            &self,
+            witness: &[(&'static [u8], &[u8])],
+            external_rng: &mut R,
+        ) -> ChaCha12Rng {
+            let mut transcript = self.clone();
+            for (label, bytes) in witness {
+                transcript.append_message(label, bytes);
+            }
+            let mut external = [0u8; 32];
+            external_rng.fill_bytes(&mut external);
+            transcript.append_message(b"external", &external);
+
+            let mut seed = [0u8; 32];
+            transcript.hasher.finalize_xof().fill(&mut seed);
+            ChaCha12Rng::from_seed(seed)
+        }
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_alternative_transcript() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Generate a statement
+        let (witness, statement) = random_witness_statement(2, 2, &mut rng).unwrap();
+
+        // Proofs using the alternative transcript verify with it, including in batches
+        let transcript = Blake3Transcript::new(b"Test transcript");
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&statement, &mut transcript.clone()).is_ok());
+        assert!(Proof::verify_batch(
+            &[statement.clone(), statement.clone()],
+            &[proof.clone(), proof.clone()],
+            &mut [transcript.clone(), transcript.clone()]
+        )
+        .is_ok());
+
+        // The transform is bound into the proof
+        assert!(proof
+            .verify(&statement, &mut Blake3Transcript::new(b"Evil transcript"))
+            .is_err());
+        assert!(proof
+            .verify(&statement, &mut Transcript::new(b"Test transcript"))
+            .is_err());
+    }
 }
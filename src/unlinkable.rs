@@ -0,0 +1,428 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+use alloc::{sync::Arc, vec::Vec};
+use core::iter::once;
+
+#[cfg(feature = "prover")]
+use curve25519_dalek::traits::MultiscalarMul;
+use curve25519_dalek::{
+    traits::{Identity, VartimeMultiscalarMul},
+    RistrettoPoint,
+    Scalar,
+};
+#[cfg(feature = "prover")]
+use rand_core::CryptoRngCore;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "prover")]
+use subtle::{ConditionallySelectable, ConstantTimeEq};
+#[cfg(feature = "prover")]
+use zeroize::Zeroizing;
+
+use crate::{
+    gray::GrayIterator,
+    proof::ProofError,
+    transcript::{append_message, append_u64, challenge_bytes},
+    util::NullRng,
+    FiatShamirTranscript,
+    InputSet,
+    Parameters,
+};
+#[cfg(feature = "prover")]
+use crate::{
+    proof::{compute_p, DecompositionCommitments},
+    util::OperationTiming,
+    Witness,
+};
+
+// Version identifier
+const VERSION: u64 = 0;
+
+// Domain separator
+const DOMAIN: &str = "Triptych unlinkable proof";
+
+/// A Triptych one-of-many proof without linkability.
+///
+/// This proves knowledge of the signing key for one verification key in an [`InputSet`], but does not produce or
+/// bind a linking tag. It omits the linking tag machinery entirely, so it is smaller and faster than a
+/// [`Proof`](`crate::Proof`).
+/// Because there is no linking tag, it is not possible to detect that two proofs were generated using the same
+/// signing key.
+///
+/// Unlinkable proofs use a separate transcript domain and are not interoperable with linkable
+/// [`Proofs`](`crate::Proof`): neither kind of proof can be verified as the other.
+#[allow(non_snake_case)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnlinkableProof {
+    A: RistrettoPoint,
+    B: RistrettoPoint,
+    C: RistrettoPoint,
+    D: RistrettoPoint,
+    X: Vec<RistrettoPoint>,
+    f: Vec<Vec<Scalar>>,
+    z_A: Scalar,
+    z_C: Scalar,
+    z: Scalar,
+}
+
+impl UnlinkableProof {
+    /// Generate an [`UnlinkableProof`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and an [`InputSet`] `input_set` whose verification
+    /// key at the witness index corresponds to the witness signing key.
+    /// If the witness and input set are invalid for the [`Parameters`] `params`, returns a [`ProofError`].
+    ///
+    /// This function provides a cryptographically-secure random number generator for you.
+    ///
+    /// You must also supply a [`Transcript`](`crate::Transcript`) `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "rand")]
+    pub fn prove<T: FiatShamirTranscript>(
+        witness: &Witness,
+        params: &Arc<Parameters>,
+        input_set: &Arc<InputSet>,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        use rand_core::OsRng;
+
+        Self::prove_with_rng(witness, params, input_set, &mut OsRng, transcript)
+    }
+
+    /// Generate an [`UnlinkableProof`].
+    ///
+    /// The proof is generated by supplying a [`Witness`] `witness` and an [`InputSet`] `input_set` whose verification
+    /// key at the witness index corresponds to the witness signing key.
+    /// If the witness and input set are invalid for the [`Parameters`] `params`, returns a [`ProofError`].
+    ///
+    /// You must also supply a [`CryptoRngCore`] random number generator `rng` and a [`Transcript`](`crate::Transcript`)
+    /// `transcript`.
+    ///
+    /// This function makes some attempt at avoiding timing side-channel attacks using constant-time operations.
+    #[cfg(feature = "prover")]
+    #[allow(non_snake_case)]
+    pub fn prove_with_rng<T: FiatShamirTranscript, R: CryptoRngCore>(
+        witness: &Witness,
+        params: &Arc<Parameters>,
+        input_set: &Arc<InputSet>,
+        rng: &mut R,
+        transcript: &mut T,
+    ) -> Result<Self, ProofError> {
+        // Check that the witness, parameters, and input set are consistent
+        if witness.get_params() != params {
+            return Err(ProofError::InvalidParameter);
+        }
+        let M = input_set.keys();
+        if usize::try_from(params.get_N()).ok() != Some(M.len()) {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Extract values for convenience
+        let r = witness.get_r();
+        let l = witness.get_l();
+
+        // Check that the witness is valid against the input set in constant time
+        let mut M_l = RistrettoPoint::identity();
+        for (index, item) in (0u64..).zip(M.clone()) {
+            M_l.conditional_assign(item, index.ct_eq(&l));
+        }
+        if M_l != params.mul_base_generator(r) {
+            return Err(ProofError::WitnessKeyMismatch { index: l });
+        }
+
+        // Set up the transcript and its generator, binding in the witness
+        Self::bind_statement(transcript, params, input_set);
+        let mut transcript_rng = transcript.witness_rng(&[(b"l", &l.to_le_bytes()), (b"r", r.as_bytes())], rng);
+
+        // Commit to the index decomposition
+        let DecompositionCommitments {
+            A,
+            B,
+            C,
+            D,
+            a,
+            sigma,
+            r_A,
+            r_B,
+            r_C,
+            r_D,
+        } = DecompositionCommitments::new(params, l, &mut transcript_rng, OperationTiming::Constant)?;
+
+        // Random masks
+        let rho = Zeroizing::new(
+            (0..params.get_m())
+                .map(|_| Scalar::random(&mut transcript_rng))
+                .collect::<Vec<Scalar>>(),
+        );
+
+        // Compute `p` polynomial vector coefficients and the `X` vector
+        let p = compute_p(params, &a, &sigma)?;
+        let X = rho
+            .iter()
+            .enumerate()
+            .map(|(j, rho)| {
+                RistrettoPoint::multiscalar_mul(
+                    p.iter().map(|p| &p[j]).chain(once(rho)),
+                    M.clone().chain(once(params.base_generator())),
+                )
+            })
+            .collect::<Vec<RistrettoPoint>>();
+
+        // Get the challenge powers
+        let xi_powers = Self::commit(transcript, params, &A, &B, &C, &D, &X)?;
+
+        // Compute the `f` matrix
+        let f = (0..params.get_m() as usize)
+            .map(|j| {
+                (1..params.get_n() as usize)
+                    .map(|i| sigma[j][i] * xi_powers[1] + a[j][i])
+                    .collect::<Vec<Scalar>>()
+            })
+            .collect::<Vec<Vec<Scalar>>>();
+
+        // Compute the remaining response values
+        let z_A = r_A + xi_powers[1] * r_B;
+        let z_C = xi_powers[1] * r_C + r_D;
+        let z = r * xi_powers[params.get_m() as usize] -
+            rho.iter()
+                .zip(xi_powers.iter())
+                .map(|(rho, xi_power)| rho * xi_power)
+                .sum::<Scalar>();
+
+        // Bind the responses so the transcript state matches that of the verifier
+        Self::response(transcript, &f, &z_A, &z_C, &z);
+
+        Ok(Self {
+            A,
+            B,
+            C,
+            D,
+            X,
+            f,
+            z_A,
+            z_C,
+            z,
+        })
+    }
+
+    /// Verify an [`UnlinkableProof`].
+    ///
+    /// Verification requires that the [`Parameters`] `params`, [`InputSet`] `input_set`, and `transcript` match those
+    /// used when the proof was generated.
+    ///
+    /// If this requirement is not met, or if the proof is invalid, returns a [`ProofError`].
+    #[allow(non_snake_case)]
+    pub fn verify<T: FiatShamirTranscript>(
+        &self,
+        params: &Arc<Parameters>,
+        input_set: &Arc<InputSet>,
+        transcript: &mut T,
+    ) -> Result<(), ProofError> {
+        // Check that the input set and proof are valid for the parameters; this must happen before any indexing
+        let M = input_set.keys();
+        if usize::try_from(params.get_N()).ok() != Some(M.len()) {
+            return Err(ProofError::InvalidParameter);
+        }
+        let m = params.get_m() as usize;
+        let n_minus_1 = params.get_n().checked_sub(1).ok_or(ProofError::InvalidParameter)? as usize;
+        if self.X.len() != m || self.f.len() != m || self.f.iter().any(|f_row| f_row.len() != n_minus_1) {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Replay the transcript to get the challenge powers and weights
+        Self::bind_statement(transcript, params, input_set);
+        let xi_powers = Self::commit(transcript, params, &self.A, &self.B, &self.C, &self.D, &self.X)?;
+        let xi = xi_powers[1];
+        Self::response(transcript, &self.f, &self.z_A, &self.z_C, &self.z);
+
+        // Generate nonzero weights for the verification equations
+        let mut transcript_weights_rng = transcript.witness_rng(&[], &mut NullRng);
+        let mut w1 = Scalar::ZERO;
+        let mut w2 = Scalar::ZERO;
+        let mut w3 = Scalar::ZERO;
+        while w1 == Scalar::ZERO || w2 == Scalar::ZERO || w3 == Scalar::ZERO {
+            w1 = Scalar::random(&mut transcript_weights_rng);
+            w2 = Scalar::random(&mut transcript_weights_rng);
+            w3 = Scalar::random(&mut transcript_weights_rng);
+        }
+
+        // Reconstruct the remaining `f` terms, which must be nonzero for batch inversion
+        let f = self
+            .f
+            .iter()
+            .map(|f_row| {
+                once(xi - f_row.iter().sum::<Scalar>())
+                    .chain(f_row.iter().copied())
+                    .collect()
+            })
+            .collect::<Vec<Vec<Scalar>>>();
+        if f.iter().flatten().any(|f| f == &Scalar::ZERO) {
+            return Err(ProofError::InvalidParameter);
+        }
+
+        // Compute the `M` scalars using the Gray iterator
+        let mut f_inverse_flat = f.iter().flatten().copied().collect::<Vec<Scalar>>();
+        Scalar::batch_invert(&mut f_inverse_flat);
+        let f_inverse = f_inverse_flat
+            .chunks_exact(params.get_n() as usize)
+            .collect::<Vec<&[Scalar]>>();
+        let mut f_product = f.iter().map(|f_row| f_row[0]).product::<Scalar>();
+        let M_scalars = GrayIterator::new(params.get_n(), params.get_m())
+            .ok_or(ProofError::InvalidParameter)?
+            .map(|(gray_index, gray_old, gray_new)| {
+                f_product *= f_inverse[gray_index][gray_old as usize] * f[gray_index][gray_new as usize];
+                w3 * f_product
+            })
+            .collect::<Vec<Scalar>>();
+
+        // Assemble the weighted verification equations
+        let scalars = [-w1, -w1 * xi, -w2 * xi, -w2]
+            .into_iter()
+            .chain(xi_powers[0..m].iter().map(|xi_power| -w3 * xi_power))
+            .chain(once(-w3 * self.z))
+            .chain(f.iter().flatten().map(|f| w1 * f + w2 * f * (xi - f)))
+            .chain(once(w1 * self.z_A + w2 * self.z_C))
+            .chain(M_scalars)
+            .collect::<Vec<Scalar>>();
+        let points = [&self.A, &self.B, &self.C, &self.D]
+            .into_iter()
+            .chain(self.X.iter())
+            .chain(once(params.base_generator()))
+            .chain(params.commitment_generators().iter())
+            .chain(once(params.commitment_blinding_generator()))
+            .chain(M)
+            .collect::<Vec<&RistrettoPoint>>();
+
+        // Perform the final check; this can be done in variable time since it holds no secrets
+        if RistrettoPoint::vartime_multiscalar_mul(scalars, points) == RistrettoPoint::identity() {
+            Ok(())
+        } else {
+            Err(ProofError::FailedVerification)
+        }
+    }
+
+    /// Bind the statement into the transcript.
+    fn bind_statement<T: FiatShamirTranscript>(transcript: &mut T, params: &Parameters, input_set: &InputSet) {
+        append_message(transcript, b"dom-sep", DOMAIN.as_bytes());
+        append_u64(transcript, b"version", VERSION);
+        append_message(transcript, b"params", params.get_hash());
+        append_message(transcript, b"M", input_set.get_hash());
+    }
+
+    /// Bind the commitments into the transcript and produce the challenge powers.
+    #[allow(non_snake_case)]
+    fn commit<T: FiatShamirTranscript>(
+        transcript: &mut T,
+        params: &Parameters,
+        A: &RistrettoPoint,
+        B: &RistrettoPoint,
+        C: &RistrettoPoint,
+        D: &RistrettoPoint,
+        X: &[RistrettoPoint],
+    ) -> Result<Vec<Scalar>, ProofError> {
+        append_message(transcript, b"A", A.compress().as_bytes());
+        append_message(transcript, b"B", B.compress().as_bytes());
+        append_message(transcript, b"C", C.compress().as_bytes());
+        append_message(transcript, b"D", D.compress().as_bytes());
+        for X_item in X {
+            append_message(transcript, b"X", X_item.compress().as_bytes());
+        }
+
+        // Get the challenge using wide reduction
+        let mut xi_bytes = [0u8; 64];
+        challenge_bytes(transcript, b"xi", &mut xi_bytes);
+        let xi = Scalar::from_bytes_mod_order_wide(&xi_bytes);
+
+        // Get powers of the challenge and confirm they are nonzero
+        let m = params.get_m() as usize;
+        let mut xi_powers = Vec::with_capacity(m.checked_add(1).ok_or(ProofError::InvalidParameter)?);
+        let mut xi_power = Scalar::ONE;
+        for _ in 0..=m {
+            if xi_power == Scalar::ZERO {
+                return Err(ProofError::InvalidChallenge);
+            }
+
+            xi_powers.push(xi_power);
+            xi_power *= xi;
+        }
+
+        Ok(xi_powers)
+    }
+
+    /// Bind the responses into the transcript.
+    #[allow(non_snake_case)]
+    fn response<T: FiatShamirTranscript>(
+        transcript: &mut T,
+        f: &[Vec<Scalar>],
+        z_A: &Scalar,
+        z_C: &Scalar,
+        z: &Scalar,
+    ) {
+        for f in f.iter().flatten() {
+            append_message(transcript, b"f", f.as_bytes());
+        }
+        append_message(transcript, b"z_A", z_A.as_bytes());
+        append_message(transcript, b"z_C", z_C.as_bytes());
+        append_message(transcript, b"z", z.as_bytes());
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::{RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use crate::{InputSet, Parameters, Transcript, UnlinkableProof, Witness};
+
+    #[test]
+    #[allow(non_snake_case, non_upper_case_globals)]
+    fn test_prove_verify() {
+        // Generate data
+        const n: u32 = 2;
+        const m: u32 = 4;
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Arc::new(Parameters::new(n, m).unwrap());
+        let witness = Witness::random(&params, &mut rng);
+        let mut M = (0..params.get_N())
+            .map(|_| RistrettoPoint::random(&mut rng))
+            .collect::<Vec<RistrettoPoint>>();
+        M[usize::try_from(witness.get_l()).unwrap()] = witness.compute_verification_key();
+        let input_set = Arc::new(InputSet::new(&M));
+        let transcript = Transcript::new(b"Test transcript");
+
+        // Generate and verify a proof
+        let proof =
+            UnlinkableProof::prove_with_rng(&witness, &params, &input_set, &mut rng, &mut transcript.clone()).unwrap();
+        assert!(proof.verify(&params, &input_set, &mut transcript.clone()).is_ok());
+
+        // Verification fails for a different transcript or input set
+        assert!(proof
+            .verify(&params, &input_set, &mut Transcript::new(b"Evil transcript"))
+            .is_err());
+        let mut evil_M = M.clone();
+        evil_M[(usize::try_from(witness.get_l()).unwrap() + 1) % M.len()] = RistrettoPoint::random(&mut rng);
+        assert!(proof
+            .verify(&params, &Arc::new(InputSet::new(&evil_M)), &mut transcript.clone())
+            .is_err());
+
+        // Verification fails for a tampered proof
+        let mut evil_proof = proof.clone();
+        evil_proof.z += Scalar::ONE;
+        assert!(evil_proof.verify(&params, &input_set, &mut transcript.clone()).is_err());
+        let mut evil_proof = proof.clone();
+        evil_proof.f[0].pop();
+        assert!(evil_proof.verify(&params, &input_set, &mut transcript.clone()).is_err());
+
+        // Proving fails for a witness that is not in the input set
+        let evil_witness = Witness::new(&params, witness.get_l(), &Scalar::random(&mut rng)).unwrap();
+        assert!(
+            UnlinkableProof::prove_with_rng(&evil_witness, &params, &input_set, &mut rng, &mut transcript.clone())
+                .is_err()
+        );
+    }
+}
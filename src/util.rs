@@ -1,16 +1,23 @@
 // Copyright (c) 2024, The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
+#[cfg(feature = "prover")]
+use blake3::{Hasher, OutputReader};
+#[cfg(feature = "prover")]
 use curve25519_dalek::Scalar;
 use rand_core::{
     impls::{next_u32_via_fill, next_u64_via_fill},
     CryptoRng,
     RngCore,
 };
+#[cfg(feature = "prover")]
 use subtle::{ConditionallySelectable, ConstantTimeEq};
 use zeroize::Zeroize;
 
 /// Options for constant- or variable-time operations.
+///
+/// Only the prover uses constant-time operations.
+#[cfg_attr(not(feature = "prover"), allow(dead_code))]
 #[derive(Clone, Copy)]
 pub(crate) enum OperationTiming {
     /// The operation should attempt to run in constant time
@@ -20,6 +27,7 @@ pub(crate) enum OperationTiming {
 }
 
 /// Kronecker delta function with scalar output, possibly in constant time.
+#[cfg(feature = "prover")]
 pub(crate) fn delta(x: u32, y: u32, timing: OperationTiming) -> Scalar {
     match timing {
         OperationTiming::Constant => {
@@ -27,16 +35,57 @@ pub(crate) fn delta(x: u32, y: u32, timing: OperationTiming) -> Scalar {
             result.conditional_assign(&Scalar::ONE, x.ct_eq(&y));
             result
         },
-        OperationTiming::Variable => {
-            if x == y {
-                Scalar::ONE
-            } else {
-                Scalar::ZERO
-            }
-        },
+        OperationTiming::Variable => Scalar::from(u8::from(x == y)),
+    }
+}
+
+/// A random number generator that deterministically expands a caller-supplied entropy buffer.
+/// It is only as secure as the entropy it is given.
+#[cfg(feature = "prover")]
+pub(crate) struct EntropyRng {
+    reader: OutputReader,
+}
+
+#[cfg(feature = "prover")]
+impl EntropyRng {
+    /// Create a new generator from an `entropy` buffer.
+    pub(crate) fn new(entropy: &[u8]) -> Self {
+        let mut hasher = Hasher::new();
+        hasher.update(b"Triptych entropy");
+        hasher.update(&(entropy.len() as u64).to_le_bytes());
+        hasher.update(entropy);
+
+        Self {
+            reader: hasher.finalize_xof(),
+        }
+    }
+}
+
+#[cfg(feature = "prover")]
+impl RngCore for EntropyRng {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.reader.fill(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+
+        Ok(())
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        next_u64_via_fill(self)
     }
 }
 
+// This is cryptographically secure as long as the supplied entropy is
+#[cfg(feature = "prover")]
+impl CryptoRng for EntropyRng {}
+
 /// A null random number generator that exists only for deterministic transcript-based weight generation.
 /// It only produces zero.
 /// This is DANGEROUS in general, and you almost certainly should not use it elsewhere!
@@ -68,14 +117,17 @@ impl CryptoRng for NullRng {}
 
 #[cfg(test)]
 mod test {
-    use curve25519_dalek::Scalar;
     use rand_core::RngCore;
 
-    use super::{NullRng, OperationTiming};
-    use crate::util::delta;
+    use super::NullRng;
 
     #[test]
+    #[cfg(feature = "prover")]
     fn test_delta() {
+        use curve25519_dalek::Scalar;
+
+        use super::{delta, OperationTiming};
+
         for timing in [OperationTiming::Constant, OperationTiming::Variable] {
             // Equal values
             assert_eq!(delta(0, 0, timing), Scalar::ONE);
@@ -87,6 +139,17 @@ mod test {
             assert_eq!(delta(1, 0, timing), Scalar::ZERO);
             assert_eq!(delta(u32::MAX, 0, timing), Scalar::ZERO);
         }
+
+        // Both timings must agree on all pairs, including those differing only in high bits
+        let values = [0, 1, 2, 255, 256, 1 << 16, 1 << 31, u32::MAX - 1, u32::MAX];
+        for x in values {
+            for y in values {
+                assert_eq!(
+                    delta(x, y, OperationTiming::Constant),
+                    delta(x, y, OperationTiming::Variable)
+                );
+            }
+        }
     }
 
     #[test]
@@ -0,0 +1,110 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! These functions are exported to JavaScript using `#[wasm_bindgen]`. They accept and return only byte vectors, use
+//! `getrandom`-backed [`OsRng`](`rand_core::OsRng`) internally, and generate [`Parameters`](`crate::Parameters`) using
+//! [`Parameters::new`](`crate::Parameters::new`). Errors are thrown to JavaScript as strings.
+//!
+//! On `wasm32` targets, this feature enables the `js` feature of `getrandom` so `OsRng` can use the browser's
+//! cryptographically-secure random number generator.
+//!
+//! Messages are bound into a [`Transcript`](`crate::Transcript`) with label `Triptych message` using the label
+//! `message`.
+//!
+//! Witnesses are encoded as the concatenation of:
+//! - `n` as a 4-byte little-endian integer
+//! - `m` as a 4-byte little-endian integer
+//! - `l` as an 8-byte little-endian integer
+//! - the canonical 32-byte encoding of the signing key `r`
+//!
+//! Statements are encoded as the concatenation of:
+//! - `n` as a 4-byte little-endian integer
+//! - `m` as a 4-byte little-endian integer
+//! - the 32-byte compressed encoding of the linking tag `J`
+//! - the 32-byte compressed encodings of all `n**m` verification keys, in order
+//!
+//! Statements with `n > 256` or `m > 32` are rejected, as are statements whose length does not match their dimensions,
+//! before any parameters are generated.
+//!
+//! Proofs use the canonical encoding of [`Proof::to_bytes`](`crate::Proof::to_bytes`).
+
+use alloc::{string::ToString, vec::Vec};
+
+use rand_core::OsRng;
+use wasm_bindgen::prelude::{wasm_bindgen, JsValue};
+
+use crate::{
+    encoding::{message_transcript, parse_statement, parse_witness},
+    proof::ProofError,
+    Proof,
+};
+
+impl From<ProofError> for JsValue {
+    fn from(error: ProofError) -> Self {
+        JsValue::from_str(&error.to_string())
+    }
+}
+
+/// Generate a [`Proof`] from an encoded witness, encoded statement, and message, returning the encoded proof.
+///
+/// If any encoding is invalid, or if the witness is invalid for the statement, returns a [`ProofError`].
+#[wasm_bindgen]
+pub fn prove_wasm(witness_bytes: &[u8], statement_bytes: &[u8], message: &[u8]) -> Result<Vec<u8>, ProofError> {
+    let statement = parse_statement(statement_bytes).ok_or(ProofError::FailedDeserialization)?;
+    let witness = parse_witness(witness_bytes, statement.get_params()).ok_or(ProofError::FailedDeserialization)?;
+
+    let proof = Proof::prove_with_rng(&witness, &statement, &mut OsRng, &mut message_transcript(message))?;
+
+    Ok(proof.to_bytes())
+}
+
+/// Verify an encoded [`Proof`] against an encoded statement and message.
+///
+/// Returns `true` if and only if all encodings are valid and the proof verifies.
+#[must_use = "verification result must be checked"]
+#[wasm_bindgen]
+pub fn verify_wasm(statement_bytes: &[u8], proof_bytes: &[u8], message: &[u8]) -> bool {
+    let statement = match parse_statement(statement_bytes) {
+        Some(statement) => statement,
+        None => return false,
+    };
+    let proof = match Proof::from_bytes(proof_bytes) {
+        Ok(proof) => proof,
+        Err(_) => return false,
+    };
+
+    proof.verify(&statement, &mut message_transcript(message)).is_ok()
+}
+
+#[cfg(test)]
+mod test {
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+
+    use super::{prove_wasm, verify_wasm};
+    use crate::encoding::test::generate_encodings;
+
+    #[test]
+    fn test_prove_verify() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let (witness_bytes, statement_bytes) = generate_encodings(&mut rng);
+
+        // Generate and verify a proof
+        let proof_bytes = prove_wasm(&witness_bytes, &statement_bytes, b"message").unwrap();
+        assert!(verify_wasm(&statement_bytes, &proof_bytes, b"message"));
+
+        // Verification fails for a different message or malformed inputs
+        assert!(!verify_wasm(&statement_bytes, &proof_bytes, b"evil message"));
+        assert!(!verify_wasm(&statement_bytes[1..], &proof_bytes, b"message"));
+        assert!(!verify_wasm(&statement_bytes, &proof_bytes[1..], b"message"));
+
+        // Proving fails for a malformed witness
+        assert!(prove_wasm(&witness_bytes[1..], &statement_bytes, b"message").is_err());
+
+        // Statements with huge dimensions are rejected without generating parameters
+        let mut evil_statement_bytes = statement_bytes.clone();
+        evil_statement_bytes[..4].copy_from_slice(&((1u32 << 31) - 1).to_le_bytes());
+        assert!(prove_wasm(&witness_bytes, &evil_statement_bytes, b"message").is_err());
+        assert!(!verify_wasm(&evil_statement_bytes, &proof_bytes, b"message"));
+    }
+}
@@ -1,11 +1,17 @@
 // Copyright (c) 2024, The Tari Project
 // SPDX-License-Identifier: BSD-3-Clause
 
-use alloc::sync::Arc;
+use alloc::{sync::Arc, vec::Vec};
 
-use curve25519_dalek::{RistrettoPoint, Scalar};
+use curve25519_dalek::{
+    constants::{RISTRETTO_BASEPOINT_POINT, RISTRETTO_BASEPOINT_TABLE},
+    ristretto::RistrettoBasepointTable,
+    RistrettoPoint,
+    Scalar,
+};
 use rand_core::CryptoRngCore;
 use snafu::prelude::*;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
 use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
 
 use crate::Parameters;
@@ -15,11 +21,22 @@ use crate::Parameters;
 /// The witness consists of a signing key and an index where the corresponding verification key will appear in  the
 /// [`InputSet`](`crate::statement::InputSet`) of a [`Statement`](`crate::statement::Statement`). It also contains
 /// [`Parameters`].
-#[derive(Zeroize, ZeroizeOnDrop)]
+///
+/// # Secret handling
+///
+/// The signing key and index are secret. They are zeroized when the [`Witness`] is dropped, and you can zeroize them
+/// earlier using [`Zeroize::zeroize`]. Accessors return the signing key by reference, so it isn't copied unless you
+/// copy it yourself; any such copies are your responsibility to clear. The [`Parameters`] are public, and are not
+/// zeroized.
+///
+/// Cloning a [`Witness`] copies its secrets, so each clone is another copy of the signing key in memory. Every clone is
+/// zeroized when dropped, but keep the number and lifetime of clones to a minimum. Clones share the original's
+/// [`Parameters`] rather than copying them.
+#[derive(Clone, Zeroize, ZeroizeOnDrop)]
 pub struct Witness {
     #[zeroize(skip)]
     params: Arc<Parameters>,
-    l: u32,
+    l: u64,
     r: Scalar,
 }
 
@@ -29,22 +46,29 @@ pub enum WitnessError {
     /// An invalid parameter was provided.
     #[snafu(display("An invalid parameter was provided"))]
     InvalidParameter,
+    /// The index is not valid for the parameters.
+    #[snafu(display("Witness index {index} is out of range"))]
+    IndexOutOfRange {
+        /// The witness index.
+        index: u64,
+    },
 }
 
 impl Witness {
     /// Generate a new [`Witness`] from secret data.
     ///
     /// The signing key `r` must be nonzero, and the index `l` must be valid for the [`Parameters`] `params`.
-    /// If any of these conditions is not met, returns a [`WitnessError`].
+    /// If any of these conditions is not met, returns a [`WitnessError`]; an invalid index is reported as
+    /// [`WitnessError::IndexOutOfRange`].
     ///
     /// If you'd like a [`Witness`] generated securely for you, use [`Witness::random`] instead.
     #[allow(non_snake_case)]
-    pub fn new(params: &Arc<Parameters>, l: u32, r: &Scalar) -> Result<Self, WitnessError> {
+    pub fn new(params: &Arc<Parameters>, l: u64, r: &Scalar) -> Result<Self, WitnessError> {
         if r == &Scalar::ZERO {
             return Err(WitnessError::InvalidParameter);
         }
         if l >= params.get_N() {
-            return Err(WitnessError::InvalidParameter);
+            return Err(WitnessError::IndexOutOfRange { index: l });
         }
 
         Ok(Self {
@@ -63,10 +87,12 @@ impl Witness {
     #[allow(clippy::cast_possible_truncation)]
     pub fn random<R: CryptoRngCore>(params: &Arc<Parameters>, rng: &mut R) -> Self {
         // Generate a random index using wide reduction
-        // This can't truncate since `N` is bounded by `u32`
+        // This can't truncate since `N` is bounded by `u64`
         // It is also defined since `N > 0`
+        let mut l_bytes = [0u8; 16];
+        rng.fill_bytes(&mut l_bytes);
         #[allow(clippy::arithmetic_side_effects)]
-        let l = (rng.as_rngcore().next_u64() % u64::from(params.get_N())) as u32;
+        let l = (u128::from_le_bytes(l_bytes) % u128::from(params.get_N())) as u64;
 
         Self {
             params: params.clone(),
@@ -81,23 +107,293 @@ impl Witness {
     }
 
     /// Get the index from this [`Witness`].
-    pub fn get_l(&self) -> u32 {
+    pub fn get_l(&self) -> u64 {
         self.l
     }
 
     /// Get the signing key from this [`Witness`].
+    ///
+    /// This returns a reference so the signing key isn't copied out of the [`Witness`], where it can't be zeroized.
     pub fn get_r(&self) -> &Scalar {
         &self.r
     }
 
     /// Compute the linking tag for the [`Witness`] signing key.
+    ///
+    /// The linking tag is `J = r^{-1} U`, where `r` is the signing key and `U` is the
+    /// [`linkability generator`](`Parameters::linkability_generator`) of the witness [`Parameters`].
+    ///
+    /// The generator `U` is fixed by the [`Parameters`] rather than derived from the verification key or any other part
+    /// of a statement, so this tag depends only on the signing key and parameters. It is exactly the tag that proving
+    /// checks (`r J = U`) and verification expects, for any input set and index.
     #[allow(non_snake_case)]
     pub fn compute_linking_tag(&self) -> RistrettoPoint {
-        *Zeroizing::new(self.r.invert()) * self.params.get_U()
+        *Zeroizing::new(self.r.invert()) * self.params.linkability_generator()
+    }
+
+    /// Compute the linking tags for many [`Witness`] signing keys at once.
+    ///
+    /// Each tag is derived exactly as in [`Witness::compute_linking_tag`], so a scanner can use this to precompute the
+    /// tags it expects to see. This is faster than computing the tags individually, since it inverts all signing keys
+    /// together and builds precomputed tables for the generator `U`. Witnesses may use different [`Parameters`], but
+    /// grouping witnesses that share the same generator `U` together avoids rebuilding tables.
+    ///
+    /// The linking tags are returned in the same order as the witnesses.
+    #[allow(non_snake_case)]
+    pub fn compute_linking_tags(witnesses: &[Witness]) -> Vec<RistrettoPoint> {
+        // Batch inversion requires nonzero scalars, so substitute for any zeroized signing keys and fix them up later
+        let is_zero = witnesses
+            .iter()
+            .map(|witness| witness.r.ct_eq(&Scalar::ZERO))
+            .collect::<Vec<Choice>>();
+        let mut r_inverses = Zeroizing::new(
+            witnesses
+                .iter()
+                .zip(is_zero.iter())
+                .map(|(witness, is_zero)| Scalar::conditional_select(&witness.r, &Scalar::ONE, *is_zero))
+                .collect::<Vec<Scalar>>(),
+        );
+        Scalar::batch_invert(&mut r_inverses);
+        for (r_inverse, is_zero) in r_inverses.iter_mut().zip(is_zero.iter()) {
+            r_inverse.conditional_assign(&Scalar::ZERO, *is_zero);
+        }
+
+        // The table for the most recently seen generator, if any
+        let mut table: Option<(RistrettoPoint, RistrettoBasepointTable)> = None;
+
+        witnesses
+            .iter()
+            .zip(r_inverses.iter())
+            .map(|(witness, r_inverse)| {
+                let U = witness.params.linkability_generator();
+
+                // Build a table if we haven't already done so for this generator
+                let (_, table) = match &mut table {
+                    Some(entry) if &entry.0 == U => entry,
+                    entry => entry.insert((*U, RistrettoBasepointTable::create(U))),
+                };
+
+                &*table * r_inverse
+            })
+            .collect()
     }
 
     /// Compute the verification key for the [`Witness`] signing key.
     pub fn compute_verification_key(&self) -> RistrettoPoint {
-        self.r * self.params.get_G()
+        self.params.mul_base_generator(&self.r)
+    }
+
+    /// Compute the verification keys for many [`Witness`] signing keys at once.
+    ///
+    /// This is faster than calling [`Witness::compute_verification_key`] on each witness, since it uses precomputed
+    /// tables for the generator `G`. Witnesses may use different [`Parameters`], but grouping witnesses that share the
+    /// same generator `G` together avoids rebuilding tables.
+    ///
+    /// The verification keys are returned in the same order as the witnesses.
+    #[allow(non_snake_case)]
+    pub fn compute_verification_keys(witnesses: &[Witness]) -> Vec<RistrettoPoint> {
+        // The table for the most recently seen custom generator, if any
+        let mut custom_table: Option<(RistrettoPoint, RistrettoBasepointTable)> = None;
+
+        witnesses
+            .iter()
+            .map(|witness| {
+                let G = witness.params.base_generator();
+
+                // Use a table from the parameters if they have one
+                if witness.params.has_precomputed_table() {
+                    return witness.params.mul_base_generator(&witness.r);
+                }
+
+                // The default generator has a static table
+                if G == &RISTRETTO_BASEPOINT_POINT {
+                    return RISTRETTO_BASEPOINT_TABLE * &witness.r;
+                }
+
+                // Otherwise, build a table if we haven't already done so for this generator
+                let (_, table) = match &mut custom_table {
+                    Some(entry) if &entry.0 == G => entry,
+                    entry => entry.insert((*G, RistrettoBasepointTable::create(G))),
+                };
+
+                &*table * &witness.r
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{sync::Arc, vec::Vec};
+
+    use curve25519_dalek::{RistrettoPoint, Scalar};
+    use rand_chacha::ChaCha12Rng;
+    use rand_core::SeedableRng;
+    use zeroize::{Zeroize, ZeroizeOnDrop};
+
+    use crate::{witness::WitnessError, Parameters, Witness};
+
+    #[test]
+    fn test_zeroize() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+
+        // Dropping a witness must zeroize it
+        fn assert_zeroize_on_drop<T: ZeroizeOnDrop>() {}
+        assert_zeroize_on_drop::<Witness>();
+
+        // Zeroizing a witness clears its secrets, but not its parameters
+        let mut witness = Witness::new(&params, 5, &Scalar::random(&mut rng)).unwrap();
+        witness.zeroize();
+        assert_eq!(witness.get_r(), &Scalar::ZERO);
+        assert_eq!(witness.get_l(), 0);
+        assert!(witness.get_params() == &params);
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let witness = Witness::random(&params, &mut rng);
+
+        // A clone has the same secrets and shares the parameters
+        let mut clone = witness.clone();
+        assert_eq!(clone.get_l(), witness.get_l());
+        assert_eq!(clone.get_r(), witness.get_r());
+        assert!(Arc::ptr_eq(clone.get_params(), witness.get_params()));
+
+        // Zeroizing a clone doesn't affect the original
+        clone.zeroize();
+        assert_eq!(clone.get_r(), &Scalar::ZERO);
+        assert_ne!(witness.get_r(), &Scalar::ZERO);
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_compute_verification_keys() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Use both default and custom generators, interleaved
+        let params = Arc::new(Parameters::new(2, 2).unwrap());
+        let G = RistrettoPoint::random(&mut rng);
+        let U = RistrettoPoint::random(&mut rng);
+        let custom_params = Arc::new(Parameters::new_with_generators(2, 2, &G, &U).unwrap());
+        let witnesses = (0..8)
+            .map(|i| {
+                if i % 3 == 0 {
+                    Witness::random(&custom_params, &mut rng)
+                } else {
+                    Witness::random(&params, &mut rng)
+                }
+            })
+            .collect::<Vec<Witness>>();
+
+        // The batch keys must match those computed individually
+        assert_eq!(
+            Witness::compute_verification_keys(&witnesses),
+            witnesses
+                .iter()
+                .map(|witness| witness.compute_verification_key())
+                .collect::<Vec<RistrettoPoint>>()
+        );
+        assert!(Witness::compute_verification_keys(&[]).is_empty());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_compute_linking_tags() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Use both default and custom generators, interleaved
+        let params = Arc::new(Parameters::new(2, 2).unwrap());
+        let G = RistrettoPoint::random(&mut rng);
+        let U = RistrettoPoint::random(&mut rng);
+        let custom_params = Arc::new(Parameters::new_with_generators(2, 2, &G, &U).unwrap());
+        let mut witnesses = (0..8)
+            .map(|i| {
+                if i % 3 == 0 {
+                    Witness::random(&custom_params, &mut rng)
+                } else {
+                    Witness::random(&params, &mut rng)
+                }
+            })
+            .collect::<Vec<Witness>>();
+
+        // The batch tags must match those computed individually, even for a zeroized witness
+        witnesses[4].zeroize();
+        assert_eq!(
+            Witness::compute_linking_tags(&witnesses),
+            witnesses
+                .iter()
+                .map(|witness| witness.compute_linking_tag())
+                .collect::<Vec<RistrettoPoint>>()
+        );
+        assert!(Witness::compute_linking_tags(&[]).is_empty());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn test_compute_linking_tag() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // The tag satisfies `r J = U` for default and custom generators
+        let G = RistrettoPoint::random(&mut rng);
+        let U = RistrettoPoint::random(&mut rng);
+        for params in [
+            Arc::new(Parameters::new(2, 2).unwrap()),
+            Arc::new(Parameters::new_with_generators(2, 2, &G, &U).unwrap()),
+        ] {
+            let witness = Witness::random(&params, &mut rng);
+            assert_eq!(
+                witness.get_r() * witness.compute_linking_tag(),
+                *params.linkability_generator()
+            );
+
+            // The tag does not depend on the index
+            let other_index = Witness::new(&params, (witness.get_l() + 1) % params.get_N(), witness.get_r()).unwrap();
+            assert_eq!(other_index.compute_linking_tag(), witness.compute_linking_tag());
+        }
+    }
+
+    #[test]
+    fn test_index_out_of_range() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+        let params = Arc::new(Parameters::new(2, 4).unwrap());
+        let r = Scalar::random(&mut rng);
+
+        // The last index is valid
+        assert!(Witness::new(&params, params.get_N() - 1, &r).is_ok());
+
+        // Any index beyond it is rejected at construction
+        for l in [params.get_N(), params.get_N() + 1, u64::MAX] {
+            assert!(matches!(
+                Witness::new(&params, l, &r),
+                Err(WitnessError::IndexOutOfRange { index }) if index == l
+            ));
+        }
+
+        // A zero signing key is reported separately
+        assert!(matches!(
+            Witness::new(&params, 0, &Scalar::ZERO),
+            Err(WitnessError::InvalidParameter)
+        ));
+    }
+
+    #[test]
+    fn test_wide_index() {
+        let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+
+        // Indexes beyond `u32` are valid for large enough parameters
+        let params = Arc::new(Parameters::new(2, 33).unwrap());
+        let r = Scalar::random(&mut rng);
+        for l in [u64::from(u32::MAX), 1 << 32, (1 << 33) - 1] {
+            assert_eq!(Witness::new(&params, l, &r).unwrap().get_l(), l);
+        }
+        assert!(Witness::new(&params, 1 << 33, &r).is_err());
+
+        // Random indexes are always in range
+        for _ in 0..16 {
+            assert!(Witness::random(&params, &mut rng).get_l() < params.get_N());
+        }
     }
 }
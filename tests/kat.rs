@@ -0,0 +1,264 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Known-answer tests for cross-implementation conformance.
+//!
+//! Each vector in `test_vectors/` fixes the parameters, witness, statement, message, and proof bytes for a seed, using
+//! the schema documented on `TestVector`. These tests load the vectors using only the public API, so they pin the
+//! generator derivation, transcript labels, proof encoding, and deterministic prover against accidental change.
+
+use std::sync::Arc;
+
+use curve25519_dalek::{ristretto::CompressedRistretto, RistrettoPoint, Scalar};
+#[cfg(feature = "prover")]
+use rand_core::{
+    impls::{next_u32_via_fill, next_u64_via_fill},
+    CryptoRng,
+    RngCore,
+};
+#[cfg(feature = "prover")]
+use triptych::Witness;
+use triptych::{InputSet, Parameters, Proof, Statement, Transcript};
+
+// Checked-in vectors that must never change
+const VECTORS: [&str; 2] = [
+    include_str!("../test_vectors/n2_m2_seed0.json"),
+    include_str!("../test_vectors/n3_m2_seed1.json"),
+];
+
+// Transcript label used for proofs
+const TRANSCRIPT_LABEL: &[u8] = b"Triptych test vector";
+
+// Transcript label used for the seed transcript
+#[cfg(feature = "prover")]
+const SEED_LABEL: &[u8] = b"Triptych test vector seed";
+
+// An external generator that only produces zero, used to finalize the seed transcript generator
+#[cfg(feature = "prover")]
+struct ZeroRng;
+
+#[cfg(feature = "prover")]
+impl RngCore for ZeroRng {
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        dest.fill(0);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        self.fill_bytes(dest);
+
+        Ok(())
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        next_u32_via_fill(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        next_u64_via_fill(self)
+    }
+}
+
+#[cfg(feature = "prover")]
+impl CryptoRng for ZeroRng {}
+
+// A vector loaded from JSON, whose witness data is only used for proving
+#[cfg_attr(not(feature = "prover"), allow(dead_code))]
+#[allow(non_snake_case)]
+struct Vector {
+    n: u32,
+    m: u32,
+    seed: u64,
+    G: RistrettoPoint,
+    U: RistrettoPoint,
+    CommitmentG: Vec<RistrettoPoint>,
+    CommitmentH: RistrettoPoint,
+    l: u64,
+    r: Scalar,
+    M: Vec<RistrettoPoint>,
+    J: RistrettoPoint,
+    message: Vec<u8>,
+    proof: Vec<u8>,
+}
+
+impl Vector {
+    // Load a vector, panicking if it is malformed
+    fn load(json: &str) -> Self {
+        Self {
+            n: field(json, "n").parse().unwrap(),
+            m: field(json, "m").parse().unwrap(),
+            seed: field(json, "seed").parse().unwrap(),
+            G: point(unquote(field(json, "G"))),
+            U: point(unquote(field(json, "U"))),
+            CommitmentG: list(field(json, "CommitmentG")).into_iter().map(point).collect(),
+            CommitmentH: point(unquote(field(json, "CommitmentH"))),
+            l: field(json, "l").parse().unwrap(),
+            r: Option::from(Scalar::from_canonical_bytes(array(unquote(field(json, "r"))))).unwrap(),
+            M: list(field(json, "M")).into_iter().map(point).collect(),
+            J: point(unquote(field(json, "J"))),
+            message: hex(unquote(field(json, "message"))),
+            proof: hex(unquote(field(json, "proof"))),
+        }
+    }
+
+    // Build the transcript used for proving and verification
+    fn transcript(&self) -> Transcript {
+        let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+        transcript.append_message(b"message", &self.message);
+
+        transcript
+    }
+}
+
+// Get the raw single-line value for a key
+fn field<'a>(json: &'a str, key: &str) -> &'a str {
+    let needle = format!("\"{key}\": ");
+    let start = json.find(&needle).unwrap().checked_add(needle.len()).unwrap();
+    let line = json[start..].lines().next().unwrap();
+
+    line.strip_suffix(',').unwrap_or(line)
+}
+
+// Strip the quotes from a string value
+fn unquote(value: &str) -> &str {
+    value.strip_prefix('"').unwrap().strip_suffix('"').unwrap()
+}
+
+// Split a list of strings into unquoted values
+fn list(value: &str) -> Vec<&str> {
+    value
+        .strip_prefix('[')
+        .unwrap()
+        .strip_suffix(']')
+        .unwrap()
+        .split(", ")
+        .map(unquote)
+        .collect()
+}
+
+// Decode lowercase hex
+fn hex(value: &str) -> Vec<u8> {
+    assert_eq!(value, value.to_lowercase());
+    value
+        .as_bytes()
+        .chunks(2)
+        .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).unwrap(), 16).unwrap())
+        .collect()
+}
+
+// Decode hex into a 32-byte array
+fn array(value: &str) -> [u8; 32] {
+    hex(value).try_into().unwrap()
+}
+
+// Decode hex into a canonical point
+fn point(value: &str) -> RistrettoPoint {
+    CompressedRistretto(array(value)).decompress().unwrap()
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_generators() {
+    for json in VECTORS {
+        let vector = Vector::load(json);
+        let params = Parameters::new(vector.n, vector.m).unwrap();
+
+        // The default generators must match the vector
+        assert_eq!(params.base_generator(), &vector.G);
+        assert_eq!(params.linkability_generator(), &vector.U);
+        assert_eq!(params.commitment_generators(), vector.CommitmentG.as_slice());
+        assert_eq!(params.commitment_blinding_generator(), &vector.CommitmentH);
+    }
+}
+
+#[cfg(feature = "prover")]
+#[test]
+fn test_witness() {
+    for json in VECTORS {
+        let vector = Vector::load(json);
+        let params = Arc::new(Parameters::new(vector.n, vector.m).unwrap());
+
+        // The witness must be consistent with the statement
+        let witness = Witness::new(&params, vector.l, &vector.r).unwrap();
+        assert_eq!(
+            witness.compute_verification_key(),
+            vector.M[usize::try_from(vector.l).unwrap()]
+        );
+        assert_eq!(witness.compute_linking_tag(), vector.J);
+    }
+}
+
+#[test]
+#[allow(non_snake_case)]
+fn test_verify() {
+    for json in VECTORS {
+        let vector = Vector::load(json);
+        let params = Arc::new(Parameters::new(vector.n, vector.m).unwrap());
+
+        // The proof must have a canonical encoding
+        let proof = Proof::from_bytes(&vector.proof).unwrap();
+        assert_eq!(proof.to_bytes(), vector.proof);
+
+        // The proof must verify
+        let input_set = Arc::new(InputSet::new(&vector.M));
+        let statement = Statement::new(&params, &input_set, &vector.J).unwrap();
+        assert!(proof.verify(&statement, &mut vector.transcript()).is_ok());
+
+        // The transcript label is bound into the proof
+        let mut evil_transcript = Transcript::new(b"Evil transcript");
+        evil_transcript.append_message(b"message", &vector.message);
+        assert!(proof.verify(&statement, &mut evil_transcript).is_err());
+    }
+}
+
+#[cfg(feature = "prover")]
+#[test]
+#[allow(non_snake_case)]
+fn test_prove() {
+    for json in VECTORS {
+        let vector = Vector::load(json);
+        let params = Arc::new(Parameters::new(vector.n, vector.m).unwrap());
+
+        // Set up the deterministic random number generator
+        let mut seed_transcript = Transcript::new(SEED_LABEL);
+        seed_transcript.append_u64(b"seed", vector.seed);
+        let mut rng = seed_transcript.build_rng().finalize(&mut ZeroRng);
+
+        // The witness is generated first
+        let witness = Witness::random(&params, &mut rng);
+        assert_eq!(witness.get_l(), vector.l);
+        assert_eq!(witness.get_r(), &vector.r);
+
+        // The remaining verification keys are generated in index order
+        let M = (0..params.get_N())
+            .map(|i| {
+                if i == witness.get_l() {
+                    witness.compute_verification_key()
+                } else {
+                    RistrettoPoint::random(&mut rng)
+                }
+            })
+            .collect::<Vec<RistrettoPoint>>();
+        assert_eq!(M, vector.M);
+
+        // The prover must reproduce the proof exactly
+        let input_set = Arc::new(InputSet::new(&M));
+        let statement = Statement::new(&params, &input_set, &witness.compute_linking_tag()).unwrap();
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut vector.transcript()).unwrap();
+        assert_eq!(proof.to_bytes(), vector.proof);
+    }
+}
+
+#[cfg(feature = "test-vectors")]
+#[test]
+fn test_generate() {
+    use triptych::test_vectors::generate_test_vector;
+
+    // The generator must reproduce each vector exactly
+    for json in VECTORS {
+        let vector = Vector::load(json);
+        assert_eq!(
+            generate_test_vector(vector.n, vector.m, vector.seed).unwrap().to_json(),
+            json
+        );
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Property tests for proof round trips.
+//!
+//! For random dimensions, witness indexes, and generator seeds, these check that proving and verifying always succeeds
+//! and that serialization round-trips. They also check that flipping randomly-chosen bits of the serialization causes
+//! deserialization or verification to fail.
+
+#[cfg(feature = "prover")]
+use std::sync::Arc;
+
+#[cfg(feature = "prover")]
+use curve25519_dalek::{RistrettoPoint, Scalar};
+#[cfg(feature = "prover")]
+use proptest::{collection::vec, prelude::*, sample::Index};
+#[cfg(feature = "prover")]
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "prover")]
+use rand_core::SeedableRng;
+#[cfg(feature = "prover")]
+use triptych::{InputSet, Parameters, Proof, Statement, Transcript, Witness};
+
+// Proving is slow, so only check a few cases
+#[cfg(feature = "prover")]
+const CASES: u32 = 16;
+
+// Number of bit flips to check for each proof
+#[cfg(feature = "prover")]
+const BIT_FLIPS: usize = 16;
+
+#[cfg(feature = "prover")]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    #[allow(non_snake_case, clippy::arithmetic_side_effects)]
+    fn test_round_trip(
+        n in 2u32..=3,
+        m in 2u32..=3,
+        index in any::<Index>(),
+        seed in any::<u64>(),
+        bits in vec(any::<Index>(), BIT_FLIPS),
+    ) {
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+
+        // Generate a witness at the chosen index, and a statement containing it
+        let params = Arc::new(Parameters::new(n, m).unwrap());
+        let N = usize::try_from(params.get_N()).unwrap();
+        let l = index.index(N);
+        let witness = Witness::new(&params, u64::try_from(l).unwrap(), &Scalar::random(&mut rng)).unwrap();
+        let mut M = (0..N).map(|_| RistrettoPoint::random(&mut rng)).collect::<Vec<RistrettoPoint>>();
+        M[l] = witness.compute_verification_key();
+        let statement = Statement::new(&params, &Arc::new(InputSet::new(&M)), &witness.compute_linking_tag()).unwrap();
+        let transcript = Transcript::new(b"Test transcript");
+
+        // Proving and verifying always succeeds, and serialization round-trips
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        prop_assert!(proof.verify(&statement, &mut transcript.clone()).is_ok());
+        let serialized = proof.to_bytes();
+        prop_assert_eq!(&Proof::from_bytes(&serialized).unwrap(), &proof);
+
+        // Flipping any of the chosen bits of the serialization causes deserialization or verification to fail
+        for bit in bits {
+            let bit = bit.index(serialized.len() * 8);
+            let mut evil_serialized = serialized.clone();
+            evil_serialized[bit / 8] ^= 1 << (bit % 8);
+
+            if let Ok(evil_proof) = Proof::from_bytes(&evil_serialized) {
+                prop_assert!(evil_proof.verify(&statement, &mut transcript.clone()).is_err());
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright (c) 2024, The Tari Project
+// SPDX-License-Identifier: BSD-3-Clause
+
+//! Statistical timing test for the prover.
+//!
+//! This checks that the time taken to generate a proof does not depend on the witness index, using Welch's t-test in
+//! the style of `dudect`. One class of proofs always uses index zero, and the other uses random indexes; the classes
+//! are interleaved randomly so that drift affects both equally. The slowest measurements are discarded, since they are
+//! usually caused by interruptions rather than the prover.
+//!
+//! Timing measurements are slow to collect and only meaningful with optimizations, so this test is ignored by default.
+//! Run it using `cargo test --release --all-features --test timing -- --ignored`.
+
+#[cfg(feature = "prover")]
+use std::{sync::Arc, time::Instant};
+
+#[cfg(feature = "prover")]
+use curve25519_dalek::{RistrettoPoint, Scalar};
+#[cfg(feature = "prover")]
+use rand_chacha::ChaCha12Rng;
+#[cfg(feature = "prover")]
+use rand_core::{RngCore, SeedableRng};
+#[cfg(feature = "prover")]
+use triptych::{InputSet, Parameters, Proof, Statement, Transcript, Witness};
+
+// Number of proofs to time for each class
+#[cfg(feature = "prover")]
+const SAMPLES: usize = 5000;
+
+// The fraction `1 / CROP_DIVISOR` of the slowest measurements is discarded
+#[cfg(feature = "prover")]
+const CROP_DIVISOR: usize = 10;
+
+// A t-statistic this large is strong evidence of a timing difference
+#[cfg(feature = "prover")]
+const THRESHOLD: f64 = 10.0;
+
+// Compute the Welch t-statistic for two samples
+#[cfg(feature = "prover")]
+fn welch_t(x: &[f64], y: &[f64]) -> f64 {
+    let mean_var = |samples: &[f64]| {
+        let count = f64::from(u32::try_from(samples.len()).unwrap());
+        let mean = samples.iter().sum::<f64>() / count;
+        let var = samples.iter().map(|sample| (sample - mean).powi(2)).sum::<f64>() / (count - 1.0);
+
+        (mean, var / count)
+    };
+    let (x_mean, x_var) = mean_var(x);
+    let (y_mean, y_var) = mean_var(y);
+
+    (x_mean - y_mean) / (x_var + y_var).sqrt()
+}
+
+#[cfg(feature = "prover")]
+#[test]
+#[ignore = "statistical timing test; run in release mode"]
+#[allow(non_snake_case)]
+fn test_prove_timing() {
+    let mut rng = ChaCha12Rng::seed_from_u64(8675309);
+    let params = Arc::new(Parameters::new(2, 4).unwrap());
+    let N = params.get_N();
+    let transcript = Transcript::new(b"Test transcript");
+
+    // Time proofs for interleaved classes
+    let mut timings = [Vec::with_capacity(SAMPLES), Vec::with_capacity(SAMPLES)];
+    while timings.iter().any(|class| class.len() < SAMPLES) {
+        let class = usize::from(rng.next_u32() & 1 == 1);
+        if timings[class].len() == SAMPLES {
+            continue;
+        }
+
+        // Build a witness and statement for the class, which aren't timed
+        let l = if class == 0 {
+            0
+        } else {
+            rng.next_u64().checked_rem(N).unwrap()
+        };
+        let witness = Witness::new(&params, l, &Scalar::random(&mut rng)).unwrap();
+        let M = (0..N)
+            .map(|i| {
+                if i == l {
+                    witness.compute_verification_key()
+                } else {
+                    RistrettoPoint::random(&mut rng)
+                }
+            })
+            .collect::<Vec<RistrettoPoint>>();
+        let statement = Statement::new(&params, &Arc::new(InputSet::new(&M)), &witness.compute_linking_tag()).unwrap();
+
+        let start = Instant::now();
+        let proof = Proof::prove_with_rng(&witness, &statement, &mut rng, &mut transcript.clone()).unwrap();
+        let elapsed = start.elapsed().as_nanos();
+        drop(proof);
+
+        timings[class].push(f64::from(u32::try_from(elapsed).unwrap_or(u32::MAX)));
+    }
+
+    // Discard the slowest measurements across both classes
+    let mut all = timings.concat();
+    all.sort_by(f64::total_cmp);
+    let cutoff = all[all.len().saturating_sub(all.len() / CROP_DIVISOR)];
+    let [fixed, random] = timings.map(|class| {
+        class
+            .into_iter()
+            .filter(|timing| *timing < cutoff)
+            .collect::<Vec<f64>>()
+    });
+
+    let t = welch_t(&fixed, &random);
+    assert!(
+        t.abs() < THRESHOLD,
+        "proving time depends on the witness index (t = {t})"
+    );
+}